@@ -1,8 +1,11 @@
-use crate::utils::AppHandleManager;
+use crate::host::TauriHost;
 use cobral::{
-  interpreter::{Interpreter, InterpreterState},
+  interpreter::{
+    config::{CancelHandle, InterpreterConfig},
+    host::{Host, HostHandle},
+    Interpreter, InterpreterState,
+  },
   lexer::Lexer,
-  logger::{self, batcher::LogBatchManager},
   parser::Parser,
 };
 use std::sync::Arc;
@@ -16,21 +19,33 @@ impl ExecutionContext {
     ExecutionContext {}
   }
 
-  pub async fn eval(&self, input: String) {
+  /// Runs a program. `run` identifies it in the `break_exec` event that stops
+  /// it and in the `exec_finished` event sent at the end
+  pub async fn eval(&self, handle: AppHandle, input: String, profile: bool, run: u64) {
     let has_received_event = Arc::new(Notify::new());
-    let handle = AppHandleManager.get_handle().unwrap();
+
+    // The program output and the messages below go to the window in order
+    let host = Arc::new(TauriHost::new(handle.clone()));
+
+    // Stopping only cancels this run, even while the interpreter is busy
+    let cancel = CancelHandle::default();
 
     let notify_clone = Arc::clone(&has_received_event);
     let handle_clone = handle.clone();
-    let id = handle.listen("break_exec", move |_| {
+    let cancel_clone = cancel.clone();
+    let id = handle.listen("break_exec", move |event| {
+      // Stopping a previous run must not stop this one
+      if serde_json::from_str::<u64>(event.payload()).ok() != Some(run) {
+        return;
+      }
+
+      cancel_clone.cancel();
       notify_clone.notify_one();
       handle_clone
         .get_webview_window("main")
         .unwrap()
         .request_user_attention(Some(UserAttentionType::Informational))
         .unwrap();
-
-      LogBatchManager.process_batch();
     });
 
     // Offload the evaluation task to a background thread
@@ -40,18 +55,27 @@ impl ExecutionContext {
 
       let finish_exec = |handle: &AppHandle| {
         handle.unlisten(id);
-        logger::info(format!("Tempo de execução: {:?}", start.elapsed()));
+        host.write_stdout(&format!("Tempo de execução: {:?}", start.elapsed()));
+
+        handle.emit("exec_finished", run).unwrap();
+        host.flush();
+      };
 
-        handle.emit("exec_finished", ()).unwrap();
-        LogBatchManager.process_batch();
+      // The report goes before the total time, line by line like the output
+      let write_profile = |interpreter: &Interpreter| {
+        if let Some(profile) = interpreter.profile() {
+          for line in profile.to_report().lines() {
+            host.write_stdout(line);
+          }
+        }
       };
 
       let break_exec = |handle: &AppHandle, e: String| {
-        logger::error(e);
+        host.write_stderr(&e);
 
         finish_exec(handle);
 
-        handle.emit("break_exec", ()).unwrap();
+        handle.emit("break_exec", run).unwrap();
       };
 
       let tokens = match Lexer::new(&input_clone) {
@@ -71,7 +95,18 @@ impl ExecutionContext {
       };
 
       // Create the interpreter and KEEP IT ALIVE
-      let mut interpreter = match Interpreter::new(exprs) {
+      let config = InterpreterConfig {
+        cancel,
+        host: HostHandle::from(host.clone()),
+        ..Default::default()
+      };
+
+      let interpreter = match profile {
+        true => Interpreter::with_profiler(exprs, config),
+        false => Interpreter::with_config(exprs, config),
+      };
+
+      let mut interpreter = match interpreter {
         Ok(interpreter) => interpreter,
         Err(e) => {
           break_exec(&handle, e.to_string());
@@ -122,11 +157,13 @@ impl ExecutionContext {
           match interpreter.get_state() {
             InterpreterState::Completed => {
               handle.unlisten(input_listener_id);
+              write_profile(&interpreter);
               finish_exec(&handle);
               return;
             }
             InterpreterState::Error(e) => {
               handle.unlisten(input_listener_id);
+              write_profile(&interpreter);
               break_exec(&handle, e.to_string());
               return;
             }
@@ -145,9 +182,6 @@ impl ExecutionContext {
             }
           }
         }
-
-        // Process any pending logs
-        LogBatchManager.process_batch();
       }
     });
   }
@@ -0,0 +1,87 @@
+use cobral::{
+  interpreter::host::{Host, LineBuffer},
+  logger::Payload,
+};
+use parking_lot::Mutex;
+use tauri::{AppHandle, Emitter};
+
+/// Output lines sent to the window at once, so long loops don't flood it
+const BATCH_SIZE: usize = 1000;
+
+/// Sends the program output to the window as `process_logs` batches and asks
+/// it for input with `spawn_input`
+pub struct TauriHost {
+  handle: AppHandle,
+  batch: Mutex<Vec<Payload>>,
+  line: LineBuffer,
+}
+
+impl TauriHost {
+  pub fn new(handle: AppHandle) -> Self {
+    TauriHost {
+      handle,
+      batch: Mutex::new(Vec::new()),
+      line: LineBuffer::default(),
+    }
+  }
+
+  fn push(&self, message: &str, level: &str) {
+    let mut batch = self.batch.lock();
+
+    batch.push(Payload {
+      message: message.to_string(),
+      level: level.to_string(),
+    });
+
+    if batch.len() >= BATCH_SIZE {
+      drop(batch);
+      self.flush();
+    }
+  }
+}
+
+impl Host for TauriHost {
+  fn write_stdout(&self, text: &str) {
+    self.push(&self.line.end(text), "info");
+  }
+
+  fn write_stderr(&self, text: &str) {
+    self.push(text, "error");
+  }
+
+  fn write(&self, text: &str) {
+    for line in self.line.push(text) {
+      self.push(&line, "info");
+    }
+  }
+
+  fn request_input(&self, prompt: &str) -> Result<(), String> {
+    self.flush();
+
+    self
+      .handle
+      .emit("spawn_input", Some(prompt))
+      .map_err(|e| format!("não foi possível mostrar o campo de entrada: {}", e))
+  }
+
+  /// Sends the output, with an unfinished `escreva` as a line of its own
+  fn flush(&self) {
+    if let Some(rest) = self.line.take() {
+      self.batch.lock().push(Payload {
+        message: rest,
+        level: "info".to_string(),
+      });
+    }
+
+    let batch = std::mem::take(&mut *self.batch.lock());
+
+    if batch.is_empty() {
+      return;
+    }
+
+    self
+      .handle
+      .emit("process_logs", batch)
+      .expect("failed to emit log_batch event");
+  }
+}
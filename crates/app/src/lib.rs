@@ -1,19 +1,24 @@
 mod context;
-mod utils;
+mod host;
 
-use cobral::{event::GLOBAL_EVENT_SYSTEM, logger};
 use context::ExecutionContext;
-use logger::batcher::{LogBatchConfig, LogBatchManager};
 use tauri::{
   window::{Effect, EffectsBuilder},
-  Emitter, Listener, Manager,
+  Manager,
 };
 use tauri_plugin_decorum::WebviewWindowExt;
-use utils::AppHandleManager;
 
 #[tauri::command]
-async fn eval(context: tauri::State<'_, ExecutionContext>, input: String) -> Result<(), String> {
-  context.eval(input).await;
+async fn eval(
+  handle: tauri::AppHandle,
+  context: tauri::State<'_, ExecutionContext>,
+  input: String,
+  profile: Option<bool>,
+  run: u64,
+) -> Result<(), String> {
+  context
+    .eval(handle, input, profile.unwrap_or(false), run)
+    .await;
   Ok(())
 }
 
@@ -29,45 +34,10 @@ pub fn run() {
     .manage(ExecutionContext::new())
     .invoke_handler(tauri::generate_handler![eval])
     .setup(|app| {
-      AppHandleManager::init(app.handle().clone());
-      LogBatchManager::init(LogBatchConfig::default());
-
       app
         .handle()
         .plugin(tauri_plugin_updater::Builder::new().build())?;
 
-      app.listen("break_exec", |_| {
-        GLOBAL_EVENT_SYSTEM.emit("break_exec", "".to_string());
-      });
-
-      app.listen("submit_pending_input", |event| {
-        GLOBAL_EVENT_SYSTEM.emit("submit_pending_input", event.payload().to_owned());
-      });
-
-      GLOBAL_EVENT_SYSTEM.listen(
-        "process_logs",
-        Box::new(|batch_data| {
-          let _ = AppHandleManager.with_handle(|handle| {
-            let data: serde_json::Value =
-              serde_json::from_str(&batch_data).expect("failed to deserialize batch data");
-            handle
-              .emit("process_logs", data)
-              .expect("failed to emit log_batch event");
-          });
-        }),
-      );
-
-      GLOBAL_EVENT_SYSTEM.listen(
-        "spawn_input",
-        Box::new(|input| {
-          let _ = AppHandleManager.with_handle(|handle| {
-            handle
-              .emit("spawn_input", Some(input))
-              .expect("failed to emit read_input event");
-          });
-        }),
-      );
-
       let main_window_builder =
         tauri::WebviewWindowBuilder::new(app.handle(), "main", tauri::WebviewUrl::App("/".into()))
           .title("Cobral")
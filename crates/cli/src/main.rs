@@ -0,0 +1,192 @@
+//! Runs CobraL, Portugol Studio and VisuAlg programs from the terminal.
+
+mod options;
+mod repl;
+
+use std::{
+  fs::{self, File},
+  io::{self, BufRead, BufReader},
+  process::ExitCode,
+};
+
+use cobral::{
+  interpreter::{
+    config::InterpreterConfig, coverage::Coverage, error::InterpreterError, Interpreter,
+    InterpreterState,
+  },
+  lexer::Lexer,
+  parser::Parser,
+  shared::ast::Statement,
+};
+use options::Options;
+
+fn main() -> ExitCode {
+  let options = match Options::parse(std::env::args().skip(1)) {
+    Ok(options) => options,
+    Err(message) => {
+      eprintln!("{}\n\n{}", message, options::USAGE);
+      return ExitCode::FAILURE;
+    }
+  };
+
+  let result = match options.repl {
+    true => repl::run(options.seed),
+    false => run(&options),
+  };
+
+  match result {
+    Ok(()) => ExitCode::SUCCESS,
+    Err(message) => {
+      eprintln!("{}", message);
+      ExitCode::FAILURE
+    }
+  }
+}
+
+fn run(options: &Options) -> Result<(), String> {
+  let code = fs::read_to_string(&options.program)
+    .map_err(|e| format!("Erro ao ler arquivo '{}': {}", options.program, e))?;
+
+  let tokens = Lexer::new(&code).map_err(|e| e.to_string())?;
+  let stmts = Parser::new(tokens).map_err(|e| e.to_string())?;
+
+  // The default host prints the program output straight to the terminal
+  let config = InterpreterConfig {
+    seed: options.seed,
+    ..Default::default()
+  };
+
+  if options.coverage() {
+    return run_coverage(options, &code, stmts, config);
+  }
+
+  let mut interpreter = match options.trace {
+    Some(_) => Interpreter::tracing(stmts, config),
+    None if options.profile || options.profile_file.is_some() => {
+      Interpreter::profiling(stmts, config)
+    }
+    None => {
+      return Interpreter::with_config(stmts, config)
+        .and_then(|mut interpreter| read_inputs(&mut interpreter, &mut io::stdin().lock()))
+        .map_err(|e| e.to_string());
+    }
+  };
+
+  // The table and the profile are written even when the program fails, up to
+  // the failing step
+  let result = interpreter
+    .run()
+    .and_then(|()| read_inputs(&mut interpreter, &mut io::stdin().lock()));
+  write_trace(options, &interpreter);
+  write_profile(options, &interpreter);
+
+  result.map_err(|e| e.to_string())
+}
+
+/// Runs the program once per file of `--entradas`, or once reading the
+/// terminal, and writes what all the runs executed together
+fn run_coverage(
+  options: &Options,
+  code: &str,
+  stmts: Vec<Statement>,
+  config: InterpreterConfig,
+) -> Result<(), String> {
+  let mut coverage = Coverage::of(&stmts);
+  let mut failure = None;
+
+  // A failing run still counts up to the statement that failed
+  let mut run_once = |input: &mut dyn BufRead| {
+    let mut interpreter = Interpreter::covering(stmts.clone(), config.clone());
+    let result = interpreter
+      .run()
+      .and_then(|()| read_inputs(&mut interpreter, input));
+
+    if let Some(run) = interpreter.coverage() {
+      coverage.merge(&run);
+    }
+
+    if let Err(e) = result {
+      eprintln!("{}", e);
+      failure.get_or_insert(e.to_string());
+    }
+  };
+
+  if options.inputs.is_empty() {
+    run_once(&mut io::stdin().lock());
+  }
+
+  for path in &options.inputs {
+    let file = File::open(path).map_err(|e| format!("Erro ao ler arquivo '{}': {}", path, e))?;
+
+    println!("== {} ==", path);
+    run_once(&mut BufReader::new(file));
+  }
+
+  if let Some(path) = &options.coverage_file {
+    write_file(path, &coverage.to_lcov(&options.program));
+  }
+
+  if let Some(path) = &options.coverage_html {
+    write_file(path, &coverage.to_html(&options.program, code));
+  }
+
+  match failure {
+    Some(message) => Err(message),
+    None => Ok(()),
+  }
+}
+
+/// Answers every `ler()` with a line from `input`
+fn read_inputs(
+  interpreter: &mut Interpreter,
+  input: &mut dyn BufRead,
+) -> Result<(), InterpreterError> {
+  while let InterpreterState::Waiting(_) = interpreter.get_state() {
+    let mut line = String::new();
+    match input.read_line(&mut line) {
+      Ok(0) => return Err(interpreter.end_input("não há mais linhas")),
+      Err(e) => return Err(interpreter.end_input(&e.to_string())),
+      Ok(_) => {}
+    }
+
+    interpreter.provide_input(line.trim_end_matches(['\n', '\r']).to_string())?;
+  }
+
+  match interpreter.get_state() {
+    InterpreterState::Error(e) => Err(e.clone()),
+    _ => Ok(()),
+  }
+}
+
+fn write_trace(options: &Options, interpreter: &Interpreter) {
+  let (Some(format), Some(trace)) = (options.trace, interpreter.trace()) else {
+    return;
+  };
+
+  let table = trace.export(format);
+
+  match &options.trace_file {
+    Some(path) => write_file(path, &table),
+    None => print!("\n{}", table),
+  }
+}
+
+fn write_profile(options: &Options, interpreter: &Interpreter) {
+  let Some(profile) = interpreter.profile() else {
+    return;
+  };
+
+  if options.profile {
+    print!("\n{}", profile.to_report());
+  }
+
+  if let Some(path) = &options.profile_file {
+    write_file(path, &profile.to_folded());
+  }
+}
+
+fn write_file(path: &str, contents: &str) {
+  if let Err(e) = fs::write(path, contents) {
+    eprintln!("Erro ao escrever arquivo '{}': {}", path, e);
+  }
+}
@@ -0,0 +1,113 @@
+use cobral::interpreter::trace::TraceFormat;
+
+pub const USAGE: &str = "Uso: cobral <arquivo> [opções]
+       cobral repl [--semente <número>]
+
+Opções:
+  --teste-de-mesa <formato>  Gera o teste de mesa da execução (csv, markdown ou json)
+  --saida <arquivo>          Escreve o teste de mesa no arquivo em vez do terminal
+  --semente <número>         Repete os mesmos sorteios e horários a cada execução
+  --perfil                   Mostra o tempo gasto em cada linha e função ao final
+  --perfil-pilhas <arquivo>  Escreve as pilhas de chamadas no formato do flamegraph
+  --cobertura <arquivo>      Escreve as linhas e ramos executados no formato LCOV
+  --cobertura-html <arquivo> Escreve o código com as linhas e ramos executados em HTML
+  --entradas <arquivo>       Executa o programa uma vez com cada arquivo de entradas,
+                             uma entrada por linha (pode ser repetida)";
+
+pub struct Options {
+  pub program: String,
+
+  /// `cobral repl` instead of a file
+  pub repl: bool,
+  pub trace: Option<TraceFormat>,
+  pub trace_file: Option<String>,
+  pub seed: Option<u64>,
+  pub profile: bool,
+  pub profile_file: Option<String>,
+  pub coverage_file: Option<String>,
+  pub coverage_html: Option<String>,
+  pub inputs: Vec<String>,
+}
+
+impl Options {
+  pub fn coverage(&self) -> bool {
+    self.coverage_file.is_some() || self.coverage_html.is_some()
+  }
+}
+
+impl Options {
+  pub fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+    let mut program = None;
+    let mut trace = None;
+    let mut trace_file = None;
+    let mut seed = None;
+    let mut profile = false;
+    let mut profile_file = None;
+    let mut coverage_file = None;
+    let mut coverage_html = None;
+    let mut inputs = Vec::new();
+
+    while let Some(arg) = args.next() {
+      match arg.as_str() {
+        "--teste-de-mesa" => trace = Some(value_of(&arg, args.next())?.parse()?),
+        "--saida" => trace_file = Some(value_of(&arg, args.next())?),
+        "--semente" => {
+          let value = value_of(&arg, args.next())?;
+          let parsed = value
+            .parse()
+            .map_err(|_| format!("Semente inválida: {}", value))?;
+          seed = Some(parsed);
+        }
+        "--perfil" => profile = true,
+        "--perfil-pilhas" => profile_file = Some(value_of(&arg, args.next())?),
+        "--cobertura" => coverage_file = Some(value_of(&arg, args.next())?),
+        "--cobertura-html" => coverage_html = Some(value_of(&arg, args.next())?),
+        "--entradas" => inputs.push(value_of(&arg, args.next())?),
+        flag if flag.starts_with("--") => return Err(format!("Opção desconhecida: {}", flag)),
+        _ if program.is_none() => program = Some(arg),
+        _ => return Err(format!("Argumento inesperado: {}", arg)),
+      }
+    }
+
+    if trace_file.is_some() && trace.is_none() {
+      return Err("A opção --saida requer --teste-de-mesa".into());
+    }
+
+    let profiling = profile || profile_file.is_some();
+    let covering = coverage_file.is_some() || coverage_html.is_some();
+
+    if trace.is_some() && profiling {
+      return Err("Não é possível usar --perfil junto com --teste-de-mesa".into());
+    }
+
+    if covering && (trace.is_some() || profiling) {
+      return Err("Não é possível usar --cobertura junto com --teste-de-mesa ou --perfil".into());
+    }
+
+    if !inputs.is_empty() && !covering {
+      return Err("A opção --entradas requer --cobertura ou --cobertura-html".into());
+    }
+
+    let repl = program.as_deref() == Some("repl");
+    if repl && (trace.is_some() || profiling || covering) {
+      return Err("O repl aceita somente a opção --semente".into());
+    }
+
+    Ok(Options {
+      program: program.ok_or("Nenhum arquivo informado")?,
+      repl,
+      trace,
+      trace_file,
+      seed,
+      profile,
+      profile_file,
+      coverage_file,
+      coverage_html,
+      inputs,
+    })
+  }
+}
+
+fn value_of(flag: &str, value: Option<String>) -> Result<String, String> {
+  value.ok_or_else(|| format!("A opção {} requer um valor", flag))
+}
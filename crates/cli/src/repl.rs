@@ -0,0 +1,288 @@
+//! `cobral repl`: evaluates one entry at a time against the same interpreter,
+//! so variables and functions persist between entries.
+
+use std::{fs, io, path::PathBuf};
+
+use cobral::{
+  interpreter::{config::InterpreterConfig, value::Value, Interpreter},
+  lexer::Lexer,
+  parser::{Environment, Parser},
+  shared::ast::{Expression, Statement},
+};
+use rustyline::{error::ReadlineError, DefaultEditor};
+
+use crate::read_inputs;
+
+const HELP: &str = "Comandos:
+  :vars             Mostra as variáveis e seus valores
+  :funcs            Mostra as funções definidas
+  :reset            Apaga todas as variáveis e funções
+  :load <arquivo>   Executa um arquivo nesta sessão
+  :ajuda            Mostra esta ajuda
+  :sair             Encerra o repl (ou Ctrl+D)
+
+O resultado da última expressão fica na variável _";
+
+/// Variable that keeps the value of the last expression entered
+const LAST_RESULT: &str = "_";
+
+pub fn run(seed: Option<u64>) -> Result<(), String> {
+  let mut editor = DefaultEditor::new().map_err(|e| e.to_string())?;
+  let history = history_path();
+  if let Some(path) = &history {
+    let _ = editor.load_history(path);
+  }
+
+  println!(
+    "CobraL {}. Digite :ajuda para ver os comandos",
+    env!("CARGO_PKG_VERSION")
+  );
+
+  let mut session = Session::new(seed)?;
+  let mut entry = String::new();
+
+  loop {
+    let prompt = match entry.is_empty() {
+      true => ">> ",
+      false => ".. ",
+    };
+
+    let line = match editor.readline(prompt) {
+      Ok(line) => line,
+      // Ctrl+C discards the entry being typed
+      Err(ReadlineError::Interrupted) => {
+        entry.clear();
+        continue;
+      }
+      Err(ReadlineError::Eof) => break,
+      Err(e) => return Err(e.to_string()),
+    };
+
+    if entry.is_empty() && line.trim_start().starts_with(':') {
+      let _ = editor.add_history_entry(line.trim());
+
+      match session.command(line.trim()) {
+        Ok(Flow::Continue) => {}
+        Ok(Flow::Quit) => break,
+        Err(message) => eprintln!("{}", message),
+      }
+      continue;
+    }
+
+    entry.push_str(&line);
+    entry.push('\n');
+
+    if !is_complete(&entry) {
+      continue;
+    }
+
+    if !entry.trim().is_empty() {
+      let _ = editor.add_history_entry(entry.trim_end());
+
+      if let Err(message) = session.eval(&entry) {
+        eprintln!("{}", message);
+      }
+    }
+
+    entry.clear();
+  }
+
+  if let Some(path) = &history {
+    let _ = editor.save_history(path);
+  }
+
+  Ok(())
+}
+
+enum Flow {
+  Continue,
+  Quit,
+}
+
+struct Session {
+  interpreter: Interpreter,
+
+  /// What the parser knows from earlier entries, e.g. which functions exist
+  parser: Environment,
+  seed: Option<u64>,
+}
+
+impl Session {
+  fn new(seed: Option<u64>) -> Result<Self, String> {
+    let config = InterpreterConfig {
+      seed,
+      ..Default::default()
+    };
+
+    let interpreter = Interpreter::with_config(Vec::new(), config).map_err(|e| e.to_string())?;
+
+    Ok(Session {
+      parser: Environment::new(interpreter.config().modules.clone()),
+      interpreter,
+      seed,
+    })
+  }
+
+  fn command(&mut self, line: &str) -> Result<Flow, String> {
+    let (command, argument) = match line.split_once(char::is_whitespace) {
+      Some((command, argument)) => (command, argument.trim()),
+      None => (line, ""),
+    };
+
+    match command {
+      ":vars" => {
+        for (name, value) in self.interpreter.variables() {
+          println!("{} = {}", name, display(&value));
+        }
+      }
+      ":funcs" => {
+        for (name, params) in self.interpreter.functions() {
+          println!("{}({})", name, params.join(", "));
+        }
+      }
+      ":reset" => *self = Session::new(self.seed)?,
+      ":load" if argument.is_empty() => return Err("Uso: :load <arquivo>".into()),
+      ":load" => {
+        let code = fs::read_to_string(argument)
+          .map_err(|e| format!("Erro ao ler arquivo '{}': {}", argument, e))?;
+        self.eval(&code)?;
+      }
+      ":ajuda" => println!("{}", HELP),
+      ":sair" => return Ok(Flow::Quit),
+      _ => return Err(format!("Comando desconhecido: {} (veja :ajuda)", command)),
+    }
+
+    Ok(Flow::Continue)
+  }
+
+  /// Parses and runs one entry, printing the value of a final expression
+  fn eval(&mut self, code: &str) -> Result<(), String> {
+    let tokens = Lexer::new(code).map_err(|e| e.to_string())?;
+
+    // Declarations of an entry that doesn't parse are forgotten
+    let parser = self.parser.fork();
+    let mut stmts = Parser::with_environment(tokens, parser.clone()).map_err(|e| e.to_string())?;
+    self.parser = parser;
+
+    let shows_result = keep_last_result(&mut stmts);
+
+    self
+      .interpreter
+      .extend(stmts)
+      .and_then(|()| read_inputs(&mut self.interpreter, &mut io::stdin().lock()))
+      .map_err(|e| e.to_string())?;
+
+    if shows_result {
+      let result = self
+        .interpreter
+        .variables()
+        .into_iter()
+        .find(|(name, _)| name == LAST_RESULT);
+
+      match result {
+        Some((_, Value::None)) | None => {}
+        Some((_, value)) => println!("{}", display(&value)),
+      }
+    }
+
+    Ok(())
+  }
+}
+
+/// Turns a final expression into `declare _ = expressão`, unless it already
+/// shows something or only changes a variable, like `escrever(x)` or `x++`
+fn keep_last_result(stmts: &mut [Statement]) -> bool {
+  let Some(last) = stmts.last_mut() else {
+    return false;
+  };
+
+  let Statement::Expression(expr, location) = last else {
+    return false;
+  };
+
+  let changes_only = match expr {
+    Expression::Call { callee, .. } => match &**callee {
+      Expression::Identifier(name, _) => {
+        matches!(name.as_str(), "escrever" | "erro" | "escreva" | "escreval")
+      }
+      _ => false,
+    },
+    Expression::PostfixIncrement(..)
+    | Expression::PostfixDecrement(..)
+    | Expression::PrefixIncrement(..)
+    | Expression::PrefixDecrement(..) => true,
+    _ => false,
+  };
+
+  if changes_only {
+    return false;
+  }
+
+  *last = Statement::Variable {
+    name: LAST_RESULT.to_string(),
+    initializer: Box::new(expr.clone()),
+    location: location.clone(),
+  };
+
+  true
+}
+
+/// Whether every brace, parenthesis, bracket, text and comment was closed
+fn is_complete(code: &str) -> bool {
+  let mut depth = 0i32;
+  let mut chars = code.chars().peekable();
+
+  while let Some(c) = chars.next() {
+    match c {
+      '"' => loop {
+        match chars.next() {
+          Some('\\') => {
+            chars.next();
+          }
+          Some('"') => break,
+          Some(_) => {}
+          None => return false,
+        }
+      },
+      '/' if chars.peek() == Some(&'/') => {
+        for c in chars.by_ref() {
+          if c == '\n' {
+            break;
+          }
+        }
+      }
+      '/' if chars.peek() == Some(&'*') => {
+        chars.next();
+        let mut closed = false;
+        while let Some(c) = chars.next() {
+          if c == '*' && chars.peek() == Some(&'/') {
+            chars.next();
+            closed = true;
+            break;
+          }
+        }
+        if !closed {
+          return false;
+        }
+      }
+      '{' | '(' | '[' => depth += 1,
+      '}' | ')' | ']' => depth -= 1,
+      _ => {}
+    }
+  }
+
+  depth <= 0
+}
+
+/// Texts in quotes, so that `"1"` and `1` look different
+fn display(value: &Value) -> String {
+  match value {
+    Value::String(s) => format!("\"{}\"", s),
+    _ => value.to_string(),
+  }
+}
+
+fn history_path() -> Option<PathBuf> {
+  let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"))?;
+  Some(PathBuf::from(home).join(".cobral_historico"))
+}
@@ -0,0 +1,77 @@
+use cobral::{
+  interpreter::{vm::Backend, Interpreter},
+  lexer::Lexer,
+  parser::Parser,
+  shared::ast::Statement,
+};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+const FOR_LOOP: &str = r#"
+declare soma = 0
+para (declare i = 0; i < 1000000; i++) {
+  soma = soma + i % 7
+}
+"#;
+
+const WHILE_LOOP: &str = r#"
+declare i = 0
+enquanto (i < 1000000) {
+  i = i + 1
+}
+"#;
+
+const FIBONACCI: &str = r#"
+funcao fib(n) {
+  se (n < 2) {
+    retorne n
+  }
+
+  retorne fib(n - 1) + fib(n - 2)
+}
+
+declare resultado = fib(20)
+"#;
+
+const LIST: &str = r#"
+declare valores = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+para (declare i = 0; i < 100000; i++) {
+  valores[i % 10] = valores[i % 10] + i
+}
+"#;
+
+fn parse(code: &str) -> Vec<Statement> {
+  let tokens = Lexer::new(code).expect("programa inválido");
+  Parser::new(tokens).expect("programa inválido")
+}
+
+fn execute(statements: &[Statement], backend: Backend) {
+  let mut interpreter =
+    Interpreter::with_backend(statements.to_vec(), backend).expect("programa inválido");
+  interpreter.run().expect("erro de execução");
+  black_box(interpreter);
+}
+
+fn bench_program(c: &mut Criterion, name: &str, code: &str) {
+  let statements = parse(code);
+  let mut group = c.benchmark_group(name);
+  group.sample_size(10);
+
+  group.bench_function("arvore", |b| {
+    b.iter(|| execute(&statements, Backend::TreeWalker))
+  });
+  group.bench_function("bytecode", |b| {
+    b.iter(|| execute(&statements, Backend::Bytecode))
+  });
+
+  group.finish();
+}
+
+fn evaluator(c: &mut Criterion) {
+  bench_program(c, "para", FOR_LOOP);
+  bench_program(c, "enquanto", WHILE_LOOP);
+  bench_program(c, "fibonacci", FIBONACCI);
+  bench_program(c, "vetor", LIST);
+}
+
+criterion_group!(benches, evaluator);
+criterion_main!(benches);
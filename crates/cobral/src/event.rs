@@ -1,88 +1,63 @@
-// global_event.rs
 use std::collections::HashMap;
-use std::sync::{Arc, LazyLock, Mutex};
-
-use rand::Rng;
-
-use crate::interpreter::error::InterpreterError;
-use crate::interpreter::value::Value;
-
-pub static GLOBAL_EVENT_SYSTEM: LazyLock<GlobalEventSystem> =
-  LazyLock::new(|| GlobalEventSystem::new());
-
-pub struct GlobalEventSystem {
-  listeners: Arc<Mutex<HashMap<String, Vec<(usize, Box<dyn Fn(String) + Send + Sync>)>>>>,
-  callbacks:
-    Arc<Mutex<HashMap<u32, Box<dyn Fn(String) -> Result<Value, InterpreterError> + Send + Sync>>>>,
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+type Listener = Arc<dyn Fn(String) + Send + Sync>;
+
+/// Events of a single interpreter, like `process_logs`, `spawn_input` and
+/// `debug_stopped`. Clones share the same listeners
+#[derive(Clone, Default)]
+pub struct EventSystem {
+  listeners: Arc<Mutex<HashMap<String, Vec<(usize, Listener)>>>>,
+  next_id: Arc<AtomicUsize>,
 }
 
-impl GlobalEventSystem {
+impl EventSystem {
   pub fn new() -> Self {
-    GlobalEventSystem {
-      listeners: Arc::new(Mutex::new(HashMap::new())),
-      callbacks: Arc::new(Mutex::new(HashMap::new())),
-    }
+    Self::default()
   }
 
   pub fn emit(&self, event: &str, payload: String) {
-    if let Some(listeners) = self.listeners.lock().unwrap().get(event) {
-      for (_, listener) in listeners {
-        listener(payload.clone());
-      }
+    // Listeners run without the lock, so they may listen or emit themselves
+    let listeners: Vec<Listener> = match self.listeners.lock().unwrap().get(event) {
+      Some(listeners) => listeners
+        .iter()
+        .map(|(_, listener)| listener.clone())
+        .collect(),
+      None => return,
+    };
+
+    for listener in listeners {
+      listener(payload.clone());
     }
   }
 
   pub fn listen(&self, event: &str, callback: Box<dyn Fn(String) + Send + Sync>) -> usize {
-    let mut rng = rand::rng();
-    let id: u32 = rng.random();
+    let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+
     self
       .listeners
       .lock()
       .unwrap()
       .entry(event.to_string())
-      .or_insert_with(Vec::new)
-      .push((id.try_into().unwrap(), callback));
-    id.try_into().unwrap()
+      .or_default()
+      .push((id, Arc::from(callback)));
+
+    id
   }
 
   pub fn unlisten(&self, id: usize) {
     let mut listeners = self.listeners.lock().unwrap();
-    for (_, event_listeners) in listeners.iter_mut() {
-      if let Some(index) = event_listeners
-        .iter()
-        .position(|(listener_id, _)| *listener_id == id)
-      {
-        #[allow(unused_must_use)]
-        event_listeners.remove(index);
-      }
-    }
-  }
-
-  pub fn register_callback(
-    &self,
-    callback: Box<dyn Fn(String) -> Result<Value, InterpreterError> + Send + Sync>,
-  ) -> u32 {
-    let mut rng = rand::rng();
-    let id: u32 = rng.random();
-
-    // Store the callback
-    let mut callbacks = self.callbacks.lock().unwrap();
-    callbacks.insert(id, callback);
 
-    id
+    for event_listeners in listeners.values_mut() {
+      event_listeners.retain(|(listener_id, _)| *listener_id != id);
+    }
   }
+}
 
-  // Add a method to resolve callbacks
-  pub fn resolve_callback(
-    &self,
-    id: &u32,
-    input: String,
-  ) -> Option<Result<Value, InterpreterError>> {
-    let callbacks = self.callbacks.lock().unwrap();
-    if let Some(callback) = callbacks.get(id) {
-      Some(callback(input))
-    } else {
-      None
-    }
+impl fmt::Debug for EventSystem {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("EventSystem").finish_non_exhaustive()
   }
 }
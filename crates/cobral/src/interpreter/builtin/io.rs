@@ -1,51 +1,81 @@
-use logger::batcher::LogBatchManager;
+use std::sync::atomic::{AtomicU32, Ordering};
 
 use crate::{
-  event::GLOBAL_EVENT_SYSTEM,
-  interpreter::{error::InterpreterError, value::Value},
-  logger,
+  interpreter::{error::InterpreterError, host::Host, value::Value},
   shared::ast::Location,
 };
 
-pub fn write(args: Vec<Value>, _location: Location) -> Result<Value, InterpreterError> {
-  let output = &args
+fn join(args: &[Value]) -> String {
+  args
     .iter()
     .map(|v| v.to_string())
     .collect::<Vec<_>>()
-    .join(" ");
-
-  logger::info(output);
+    .join(" ")
+}
 
-  Ok(Value::String(output.to_string()))
+fn concat(args: &[Value]) -> String {
+  args.iter().map(|v| v.to_string()).collect()
 }
 
-pub fn error(args: Vec<Value>, _location: Location) -> Result<Value, InterpreterError> {
-  let output = &args
-    .iter()
-    .map(|v| v.to_string())
-    .collect::<Vec<_>>()
-    .join(" ");
+pub fn write(
+  host: &dyn Host,
+  args: Vec<Value>,
+  _location: Location,
+) -> Result<Value, InterpreterError> {
+  let output = join(&args);
 
-  logger::error(output);
+  host.write_stdout(&output);
 
-  Ok(Value::String(output.to_string()))
+  Ok(Value::String(output))
 }
 
-pub fn read(args: Vec<Value>, _location: Location) -> Result<Value, InterpreterError> {
-  let output = &args
-    .iter()
-    .map(|v| v.to_string())
-    .collect::<Vec<_>>()
-    .join(" ");
+/// `escreva` of Portugol and VisuAlg: the values side by side, without a
+/// space between them or a line break after
+pub fn write_inline(host: &dyn Host, values: Vec<Value>) -> String {
+  let output = concat(&values);
+  host.write(&output);
+  output
+}
+
+/// `escreval` of VisuAlg: like `escreva`, then ends the line
+pub fn write_line(host: &dyn Host, values: Vec<Value>) -> String {
+  let output = concat(&values);
+  host.write_stdout(&output);
+  output
+}
+
+pub fn error(
+  host: &dyn Host,
+  args: Vec<Value>,
+  _location: Location,
+) -> Result<Value, InterpreterError> {
+  let output = join(&args);
 
-  LogBatchManager.process_batch();
+  host.write_stderr(&output);
 
-  GLOBAL_EVENT_SYSTEM.emit("spawn_input", output.to_string());
+  Ok(Value::String(output))
+}
+
+/// Asks the host for input. The interpreter suspends on the returned request
+/// and resumes with the value given to `provide_input`
+pub fn read(
+  host: &dyn Host,
+  requests: &AtomicU32,
+  args: Vec<Value>,
+  location: Location,
+) -> Result<Value, InterpreterError> {
+  let output = join(&args);
 
-  let callback_id = GLOBAL_EVENT_SYSTEM.register_callback(Box::new(|input| {
-    Ok(Value::String(input.trim_matches('"').to_string()))
-  }));
+  host.request_input(&output).map_err(|e| {
+    InterpreterError::RuntimeError(location, format!("Erro ao pedir entrada: {}", e))
+  })?;
+
+  Ok(Value::InputPending(
+    requests.fetch_add(1, Ordering::Relaxed) + 1,
+  ))
+}
 
-  // Return InputPending with the callback ID
-  Ok(Value::InputPending(callback_id))
+/// The value `ler()` returns for what the user typed
+pub fn input_value(input: String) -> Value {
+  Value::String(input.trim_matches('"').to_string())
 }
@@ -0,0 +1,238 @@
+use std::cmp::Ordering;
+
+use crate::{
+  interpreter::{
+    config::SizeLimits,
+    error::InterpreterError,
+    module::{NativeError, Number},
+    value::{List, Value},
+  },
+  shared::ast::Location,
+};
+
+/// `copiar(valor)`: a list with the same elements that shares nothing with the
+/// original, including the lists inside it
+pub fn copy(args: Vec<Value>, location: Location) -> Result<Value, InterpreterError> {
+  match args.as_slice() {
+    [value] => Ok(value.deep_copy()),
+    _ => Err(InterpreterError::ArgumentMismatchError(
+      location,
+      format!("copiar espera 1 argumento(s), recebeu {}", args.len()),
+    )),
+  }
+}
+
+// The functions below change the list they receive, which is the same list the
+// caller's variable holds: adicionar, inserir, remover, remover_em, ordenar
+// and preencher
+
+pub fn push(list: List, value: Value) -> Result<(), String> {
+  check_cycle(&list, &value)?;
+  list.write().push(value);
+  Ok(())
+}
+
+pub fn insert(list: List, index: i64, value: Value) -> Result<(), String> {
+  check_cycle(&list, &value)?;
+
+  let len = list.len();
+  match usize::try_from(index) {
+    Ok(index) if index <= len => {
+      list.write().insert(index, value);
+      Ok(())
+    }
+    _ => Err(out_of_range(index, len)),
+  }
+}
+
+/// Removes the first element equal to `value`, telling whether there was one
+pub fn remove(list: List, value: Value) -> bool {
+  // Searched before locking the list for writing, since `value` may be a list
+  // that is compared by reading the elements of this one
+  let position = list.read().iter().position(|item| same(item, &value));
+
+  match position {
+    Some(index) => {
+      list.write().remove(index);
+      true
+    }
+    None => false,
+  }
+}
+
+/// Removes the element at `index` and returns it
+pub fn remove_at(list: List, index: i64) -> Result<Value, String> {
+  let mut items = list.write();
+
+  match usize::try_from(index) {
+    Ok(i) if i < items.len() => Ok(items.remove(i)),
+    _ => Err(out_of_range(index, items.len())),
+  }
+}
+
+pub fn index_of(list: List, value: Value) -> i64 {
+  list
+    .read()
+    .iter()
+    .position(|item| same(item, &value))
+    .map_or(-1, |index| index as i64)
+}
+
+pub fn contains(list: &List, value: &Value) -> bool {
+  list.read().iter().any(|item| same(item, value))
+}
+
+/// Stable, so equal elements keep their order
+pub fn sort(list: List) -> Result<(), String> {
+  let mut items = list.write();
+  comparable(&items, "ordenar")?;
+  items.sort_by(compare);
+  Ok(())
+}
+
+/// A new list. Lists inside are still shared
+pub fn reverse(list: &List) -> List {
+  List::new(list.read().iter().rev().cloned().collect())
+}
+
+/// The elements from `start` up to, but not including, `end`
+pub fn slice(list: List, start: i64, end: i64) -> Result<List, String> {
+  let items = list.read();
+
+  match (usize::try_from(start), usize::try_from(end)) {
+    (Ok(from), Ok(to)) if from <= to && to <= items.len() => {
+      Ok(List::new(items[from..to].to_vec()))
+    }
+    _ => Err(format!(
+      "Fatia de {} até {} fora de alcance em um vetor com {} elemento(s)",
+      start,
+      end,
+      items.len()
+    )),
+  }
+}
+
+/// `inteiro` while every element is one, `real` otherwise
+pub fn sum(list: List) -> Result<Number, String> {
+  let mut total = Number::Integer(0);
+
+  for (index, item) in list.read().iter().enumerate() {
+    total = match (total, item) {
+      (Number::Integer(a), Value::Integer(b)) => a
+        .checked_add(*b)
+        .map(Number::Integer)
+        .ok_or("A soma passou do maior inteiro possível")?,
+      (total, Value::Integer(b)) => Number::Float(total.as_f64() + *b as f64),
+      (total, Value::Float(b)) => Number::Float(total.as_f64() + b),
+      (_, item) => {
+        return Err(format!(
+          "somar só soma números, mas o elemento {} é {}",
+          index,
+          item.type_name()
+        ))
+      }
+    };
+  }
+
+  Ok(total)
+}
+
+pub fn min(list: List) -> Result<Value, String> {
+  let items = list.read();
+  comparable(&items, "minimo")?;
+
+  items
+    .iter()
+    .min_by(|a, b| compare(a, b))
+    .cloned()
+    .ok_or_else(|| "minimo precisa de um vetor com pelo menos um elemento".into())
+}
+
+pub fn max(list: List) -> Result<Value, String> {
+  let items = list.read();
+  comparable(&items, "maximo")?;
+
+  items
+    .iter()
+    .max_by(|a, b| compare(a, b))
+    .cloned()
+    .ok_or_else(|| "maximo precisa de um vetor com pelo menos um elemento".into())
+}
+
+/// Replaces every element by `value`. Each element gets its own copy of a list
+pub fn fill(list: List, value: Value) -> Result<(), String> {
+  check_cycle(&list, &value)?;
+
+  for item in list.write().iter_mut() {
+    *item = value.deep_copy();
+  }
+
+  Ok(())
+}
+
+/// A list with `len` elements equal to `value`. Each element gets its own
+/// copy of a list, so `criar(3, [0, 0])` makes three separate rows
+pub fn create(limits: &SizeLimits, len: i64, value: Value) -> Result<List, NativeError> {
+  let len = usize::try_from(len)
+    .map_err(|_| format!("O tamanho do vetor não pode ser negativo, recebeu {}", len))?;
+  limits.check_list(len)?;
+
+  let mut items = Vec::new();
+  items
+    .try_reserve_exact(len)
+    .map_err(|_| format!("Não há memória para um vetor com {} elementos", len))?;
+  items.extend((0..len).map(|_| value.deep_copy()));
+
+  Ok(List::new(items))
+}
+
+fn check_cycle(list: &List, value: &Value) -> Result<(), String> {
+  match value {
+    Value::List(value) if value.contains(list) => {
+      Err("Um vetor não pode conter a si mesmo".to_string())
+    }
+    _ => Ok(()),
+  }
+}
+
+fn out_of_range(index: i64, len: usize) -> String {
+  format!(
+    "Índice {} fora de alcance em um vetor com {} elemento(s)",
+    index, len
+  )
+}
+
+/// Equality as in `==`, where `1` and `1.0` are the same number
+fn same(a: &Value, b: &Value) -> bool {
+  match (a, b) {
+    (Value::Integer(a), Value::Float(b)) | (Value::Float(b), Value::Integer(a)) => *a as f64 == *b,
+    (a, b) => a == b,
+  }
+}
+
+/// Only numbers (`inteiro` and `real` together) or only texts can be ordered
+fn comparable(items: &[Value], function: &str) -> Result<(), String> {
+  let numbers = items
+    .iter()
+    .all(|item| matches!(item, Value::Integer(_) | Value::Float(_)));
+  let texts = items.iter().all(|item| matches!(item, Value::String(_)));
+
+  match numbers || texts {
+    true => Ok(()),
+    false => Err(format!(
+      "{} só funciona com vetores de números ou de textos",
+      function
+    )),
+  }
+}
+
+fn compare(a: &Value, b: &Value) -> Ordering {
+  match (a, b) {
+    (Value::Integer(a), Value::Integer(b)) => a.cmp(b),
+    (Value::Integer(a), Value::Float(b)) => (*a as f64).total_cmp(b),
+    (Value::Float(a), Value::Integer(b)) => a.total_cmp(&(*b as f64)),
+    (Value::Float(a), Value::Float(b)) => a.total_cmp(b),
+    (Value::String(a), Value::String(b)) => a.cmp(b),
+    _ => Ordering::Equal,
+  }
+}
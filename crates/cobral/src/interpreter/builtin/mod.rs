@@ -1,41 +1,76 @@
-use crate::shared::ast::Location;
-
-use super::{error::InterpreterError, value::Value};
+use super::module::{Module, ModuleBuilder};
 
 pub mod io;
+pub mod list;
 pub mod math;
 pub mod parse;
+pub mod random;
+pub mod sequence;
+pub mod text;
+pub mod time;
 
-pub fn load(
-  name: &str,
-) -> Option<
-  Vec<(
-    &str,
-    fn(Vec<Value>, Location) -> Result<Value, InterpreterError>,
-  )>,
-> {
-  match name {
-    "matematica" => Some(vec![
-      ("raiz", math::square_root),
-      ("potencia", math::power),
-      ("PI", math::pi),
-    ]),
-    "conversao" => Some(vec![("int", parse::int), ("real", parse::float)]),
-    _ => None,
-  }
-}
-
-pub fn get_lib_funcs(name: &str) -> Vec<&'static str> {
-  match name {
-    "matematica" => vec!["raiz", "potencia", "PI"],
-    "conversao" => vec!["int", "real"],
-    _ => vec![],
-  }
-}
-
-pub fn has(name: &str) -> bool {
-  match name {
-    "escrever" | "erro" | "ler" | "raiz" | "potencia" | "int" | "real" => true,
-    _ => false,
-  }
+/// Libraries available to every program through `importe`
+pub fn modules() -> Vec<Module> {
+  vec![
+    ModuleBuilder::new("matematica")
+      .docs("Funções matemáticas")
+      .function("raiz", "Raiz quadrada de um número", math::square_root)
+      .function("potencia", "Eleva a base ao expoente", math::power)
+      .function("PI", "A constante π", math::pi)
+      .build(),
+    ModuleBuilder::new("conversao")
+      .docs("Conversão entre tipos")
+      .function("int", "Converte um número ou texto para inteiro", parse::int)
+      .function("real", "Converte um número ou texto para real", parse::float)
+      .build(),
+    ModuleBuilder::new("aleatorio")
+      .docs("Números aleatórios")
+      .function("aleatorio", "Um número real entre 0 e 1", random::random)
+      .function("sortear", "Um inteiro entre o mínimo e o máximo", random::draw)
+      .build(),
+    ModuleBuilder::new("texto")
+      .docs("Funções de texto")
+      .function("tamanho", "Quantidade de caracteres do texto", sequence::length)
+      .function("maiusculo", "O texto em letras maiúsculas", text::uppercase)
+      .function("minusculo", "O texto em letras minúsculas", text::lowercase)
+      .function("aparar", "O texto sem espaços no início e no fim", text::trim)
+      .function("dividir", "Os trechos entre cada separador", text::split)
+      .function("juntar", "Os valores do vetor, com o separador entre eles", text::join)
+      .function("substituir", "Troca cada ocorrência de um trecho por outro", text::replace)
+      .function("contem", "Se o trecho aparece no texto", sequence::contains)
+      .function("comeca_com", "Se o texto começa com o trecho", text::starts_with)
+      .function("termina_com", "Se o texto termina com o trecho", text::ends_with)
+      .function("posicao", "Onde o trecho aparece pela primeira vez, ou -1", text::position)
+      .function("repetir", "O texto repetido várias vezes", text::repeat)
+      .function("inverter", "O texto de trás para frente", sequence::reverse)
+      .function("caracteres", "Um vetor com cada caractere do texto", text::characters)
+      .build(),
+    ModuleBuilder::new("lista")
+      .docs("Funções de vetores")
+      .function("tamanho", "Quantidade de elementos do vetor", sequence::length)
+      .function("adicionar", "Adiciona um valor ao fim do vetor", list::push)
+      .function("inserir", "Insere um valor na posição indicada", list::insert)
+      .function("remover", "Remove a primeira ocorrência do valor", list::remove)
+      .function("remover_em", "Remove e retorna o elemento da posição", list::remove_at)
+      .function("indice_de", "Posição da primeira ocorrência do valor, ou -1", list::index_of)
+      .function("contem", "Se o valor está no vetor", sequence::contains)
+      .function("ordenar", "Ordena os números ou textos do vetor", list::sort)
+      .function("inverter", "Um novo vetor de trás para frente", sequence::reverse)
+      .function("fatiar", "Os elementos do início até antes do fim", list::slice)
+      .function("somar", "A soma dos números do vetor", list::sum)
+      .function("minimo", "O menor elemento do vetor", list::min)
+      .function("maximo", "O maior elemento do vetor", list::max)
+      .function("preencher", "Troca todos os elementos pelo valor", list::fill)
+      .function("criar", "Um vetor com o tamanho e o valor indicados", list::create)
+      .build(),
+    ModuleBuilder::new("saida")
+      .docs("Escrita sem espaços entre os valores, como no Portugol e no VisuAlg")
+      .variadic("escreva", "Escreve os valores sem quebrar a linha", io::write_inline)
+      .variadic("escreval", "Escreve os valores e quebra a linha", io::write_line)
+      .build(),
+    ModuleBuilder::new("tempo")
+      .docs("Relógio")
+      .function("agora", "Milissegundos desde 01/01/1970", time::now)
+      .build(),
+  ]
 }
@@ -1,42 +1,29 @@
-use crate::{
-  interpreter::{error::InterpreterError, value::Value},
-  shared::ast::Location,
-};
+use crate::interpreter::value::Value;
 
-pub fn int(args: Vec<Value>, location: Location) -> Result<Value, InterpreterError> {
-  let arg = args.get(0).ok_or(InterpreterError::ArgumentMismatchError(
-    location.clone(),
-    "int requires one argument".to_string(),
-  ))?;
-
-  match arg.clone() {
-    Value::Float(f) => Ok(Value::Integer(f as i64)),
-    Value::Integer(i) => Ok(Value::Integer(i)),
-    Value::String(s) => s.parse::<i64>().map(Value::Integer).map_err(|_| {
-      InterpreterError::TypeError(location, "Could not parse string as integer".to_string())
-    }),
-    _ => Err(InterpreterError::TypeError(
-      location,
-      "Cannot convert to integer".to_string(),
+pub fn int(value: Value) -> Result<i64, String> {
+  match value {
+    Value::Float(f) => Ok(f as i64),
+    Value::Integer(i) => Ok(i),
+    Value::String(s) => s
+      .parse::<i64>()
+      .map_err(|_| format!("Não foi possível converter \"{}\" para inteiro", s)),
+    value => Err(format!(
+      "Não é possível converter {} para inteiro",
+      value.type_name()
     )),
   }
 }
 
-pub fn float(args: Vec<Value>, location: Location) -> Result<Value, InterpreterError> {
-  let arg = args.get(0).ok_or(InterpreterError::ArgumentMismatchError(
-    location.clone(),
-    "float requires one argument".to_string(),
-  ))?;
-
-  match arg.clone() {
-    Value::Float(f) => Ok(Value::Float(f)),
-    Value::Integer(i) => Ok(Value::Float(i as f64)),
-    Value::String(s) => s.parse::<f64>().map(Value::Float).map_err(|_| {
-      InterpreterError::TypeError(location, "Could not parse string as float".to_string())
-    }),
-    _ => Err(InterpreterError::TypeError(
-      location,
-      "Cannot convert to float".to_string(),
+pub fn float(value: Value) -> Result<f64, String> {
+  match value {
+    Value::Float(f) => Ok(f),
+    Value::Integer(i) => Ok(i as f64),
+    Value::String(s) => s
+      .parse::<f64>()
+      .map_err(|_| format!("Não foi possível converter \"{}\" para real", s)),
+    value => Err(format!(
+      "Não é possível converter {} para real",
+      value.type_name()
     )),
   }
 }
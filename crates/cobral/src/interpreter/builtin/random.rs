@@ -0,0 +1,21 @@
+use crate::interpreter::host::Host;
+
+/// A number in `[0, 1)`
+pub fn random(host: &dyn Host) -> f64 {
+  host.random()
+}
+
+/// An integer between `min` and `max`, both included
+pub fn draw(host: &dyn Host, min: i64, max: i64) -> Result<i64, String> {
+  if min > max {
+    return Err(format!(
+      "O mínimo ({}) deve ser menor ou igual ao máximo ({})",
+      min, max
+    ));
+  }
+
+  let range = (max as i128 - min as i128 + 1) as f64;
+  let offset = (host.random() * range).floor() as i128;
+
+  Ok((min as i128 + offset).min(max as i128) as i64)
+}
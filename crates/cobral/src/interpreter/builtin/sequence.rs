@@ -0,0 +1,33 @@
+//! Functions that both `texto` and `lista` have. They accept a text or a list,
+//! so importing both libraries doesn't make one replace the other
+
+use crate::interpreter::{module::Sequence, value::Value};
+
+use super::{list, text};
+
+pub fn length(sequence: Sequence) -> i64 {
+  match sequence {
+    Sequence::Text(s) => text::length(s),
+    Sequence::List(items) => items.len() as i64,
+  }
+}
+
+/// In a text, looks for another text. In a list, for an element equal to `value`
+pub fn contains(sequence: Sequence, value: Value) -> Result<bool, String> {
+  match (sequence, value) {
+    (Sequence::Text(s), Value::String(part)) => Ok(text::contains(s, part)),
+    (Sequence::Text(_), value) => Err(format!(
+      "contem procura um texto dentro de outro, recebeu {}",
+      value.type_name()
+    )),
+    (Sequence::List(items), value) => Ok(list::contains(&items, &value)),
+  }
+}
+
+/// A new text or list, leaving the original as it was
+pub fn reverse(sequence: Sequence) -> Value {
+  match sequence {
+    Sequence::Text(s) => Value::String(text::reverse(s)),
+    Sequence::List(items) => Value::List(list::reverse(&items)),
+  }
+}
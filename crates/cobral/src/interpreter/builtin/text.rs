@@ -0,0 +1,305 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::interpreter::{config::SizeLimits, module::NativeError, value::Value};
+
+// Characters are grapheme clusters, so that "é" counts as one character even
+// when it is written as "e" followed by an accent
+
+pub fn length(text: String) -> i64 {
+  text.graphemes(true).count() as i64
+}
+
+pub fn uppercase(text: String) -> String {
+  text.to_uppercase()
+}
+
+pub fn lowercase(text: String) -> String {
+  text.to_lowercase()
+}
+
+pub fn trim(text: String) -> String {
+  text.trim().to_string()
+}
+
+pub fn split(text: String, separator: String) -> Result<Vec<String>, String> {
+  if separator.is_empty() {
+    return Err("O separador de dividir não pode ser vazio. Use caracteres".into());
+  }
+
+  Ok(text.split(&separator).map(str::to_string).collect())
+}
+
+/// Accepts any values, written the way `escrever` shows them
+pub fn join(
+  limits: &SizeLimits,
+  items: Vec<Value>,
+  separator: String,
+) -> Result<String, NativeError> {
+  let parts: Vec<_> = items.iter().map(Value::to_string).collect();
+
+  let separators = separator
+    .len()
+    .saturating_mul(parts.len().saturating_sub(1));
+  let len = parts
+    .iter()
+    .fold(separators, |len, part| len.saturating_add(part.len()));
+  limits.check_text(len)?;
+
+  Ok(parts.join(&separator))
+}
+
+pub fn replace(
+  limits: &SizeLimits,
+  text: String,
+  from: String,
+  to: String,
+) -> Result<String, NativeError> {
+  if from.is_empty() {
+    return Err(NativeError::Message(
+      "O trecho a substituir não pode ser vazio".into(),
+    ));
+  }
+
+  // Only a longer trecho can make the text go over the limit
+  if to.len() > from.len() {
+    let growth = (to.len() - from.len()).saturating_mul(text.matches(&from).count());
+    limits.check_text(text.len().saturating_add(growth))?;
+  }
+
+  Ok(text.replace(&from, &to))
+}
+
+pub fn contains(text: String, part: String) -> bool {
+  text.contains(&part)
+}
+
+pub fn starts_with(text: String, prefix: String) -> bool {
+  text.starts_with(&prefix)
+}
+
+pub fn ends_with(text: String, suffix: String) -> bool {
+  text.ends_with(&suffix)
+}
+
+/// Character where `part` first appears, or -1. Only whole characters match,
+/// so an accent alone is not found inside "é"
+pub fn position(text: String, part: String) -> i64 {
+  text
+    .grapheme_indices(true)
+    .map(|(start, _)| start)
+    .chain(std::iter::once(text.len()))
+    .position(|start| text[start..].starts_with(&part))
+    .map_or(-1, |index| index as i64)
+}
+
+pub fn repeat(limits: &SizeLimits, text: String, times: i64) -> Result<String, NativeError> {
+  if times < 0 {
+    return Err(NativeError::Message(format!(
+      "O número de repetições não pode ser negativo, recebeu {}",
+      times
+    )));
+  }
+
+  match text.len().checked_mul(times as usize) {
+    Some(len) if len <= isize::MAX as usize => limits.check_text(len)?,
+    _ => {
+      return Err(NativeError::Message(
+        "O texto repetido seria grande demais".into(),
+      ))
+    }
+  }
+
+  Ok(text.repeat(times as usize))
+}
+
+pub fn reverse(text: String) -> String {
+  text.graphemes(true).rev().collect()
+}
+
+pub fn characters(text: String) -> Vec<String> {
+  text.graphemes(true).map(str::to_string).collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    interpreter::{
+      builtin, config::Limit, error::InterpreterError, host::MemoryHost, value::Value,
+    },
+    shared::ast::Location,
+  };
+
+  use super::*;
+
+  /// "é" as "e" followed by a combining acute accent
+  const DECOMPOSED: &str = "cafe\u{301}";
+
+  const NO_LIMITS: SizeLimits = SizeLimits {
+    max_list_len: None,
+    max_string_len: None,
+  };
+
+  /// Texts of at most 8 bytes
+  const SHORT: SizeLimits = SizeLimits {
+    max_list_len: None,
+    max_string_len: Some(8),
+  };
+
+  fn text(s: &str) -> String {
+    s.to_string()
+  }
+
+  #[test]
+  fn length_counts_characters() {
+    assert_eq!(length(text("")), 0);
+    assert_eq!(length(text("olá")), 3);
+    assert_eq!(length(text(DECOMPOSED)), 4);
+    assert_eq!(length(text("👍🏽!")), 2);
+  }
+
+  #[test]
+  fn uppercase_and_lowercase() {
+    assert_eq!(uppercase(text("ação")), "AÇÃO");
+    assert_eq!(uppercase(text("straße")), "STRASSE");
+    assert_eq!(lowercase(text("ÁRVORE")), "árvore");
+  }
+
+  #[test]
+  fn trim_removes_unicode_spaces() {
+    assert_eq!(trim(text("  olá \n")), "olá");
+    assert_eq!(trim(text("\u{3000}meio espaço\u{a0}")), "meio espaço");
+  }
+
+  #[test]
+  fn split_by_separator() {
+    assert_eq!(
+      split(text("a,b,,c"), text(",")).unwrap(),
+      ["a", "b", "", "c"]
+    );
+    assert_eq!(
+      split(text("maçã→pêra"), text("→")).unwrap(),
+      ["maçã", "pêra"]
+    );
+    assert!(split(text("abc"), text("")).is_err());
+  }
+
+  #[test]
+  fn join_shows_each_value() {
+    let items = vec![
+      Value::String(text("a")),
+      Value::Integer(1),
+      Value::Boolean(true),
+    ];
+    assert_eq!(
+      join(&NO_LIMITS, items.clone(), text(", ")).unwrap(),
+      "a, 1, verdadeiro"
+    );
+    assert_eq!(join(&NO_LIMITS, Vec::new(), text("-")).unwrap(), "");
+    assert_eq!(
+      join(&SHORT, items, text(", ")),
+      Err(NativeError::Limit(Limit::StringLen(8)))
+    );
+  }
+
+  #[test]
+  fn replace_every_occurrence() {
+    assert_eq!(
+      replace(&NO_LIMITS, text("banana"), text("a"), text("ã")).unwrap(),
+      "bãnãnã"
+    );
+    assert!(replace(&NO_LIMITS, text("banana"), text(""), text("x")).is_err());
+    assert_eq!(
+      replace(&SHORT, text("banana"), text("an"), text("")).unwrap(),
+      "ba"
+    );
+    assert_eq!(
+      replace(&SHORT, text("banana"), text("a"), text("aa")),
+      Err(NativeError::Limit(Limit::StringLen(8)))
+    );
+  }
+
+  #[test]
+  fn contains_part() {
+    assert!(contains(text("coração"), text("ção")));
+    assert!(contains(text("abc"), text("")));
+    assert!(!contains(text("abc"), text("d")));
+  }
+
+  #[test]
+  fn starts_and_ends_with() {
+    assert!(starts_with(text("éramos"), text("é")));
+    assert!(!starts_with(text("éramos"), text("e")));
+    assert!(ends_with(text("você"), text("cê")));
+    assert!(!ends_with(text("você"), text("ce")));
+  }
+
+  #[test]
+  fn position_in_characters() {
+    assert_eq!(position(text("pão de queijo"), text("de")), 4);
+    assert_eq!(position(text(DECOMPOSED), text("e\u{301}")), 3);
+    assert_eq!(position(text(DECOMPOSED), text("\u{301}")), -1);
+    assert_eq!(position(text("abc"), text("x")), -1);
+    assert_eq!(position(text("abc"), text("")), 0);
+  }
+
+  #[test]
+  fn repeat_text() {
+    assert_eq!(repeat(&NO_LIMITS, text("ab"), 3).unwrap(), "ababab");
+    assert_eq!(repeat(&NO_LIMITS, text("ab"), 0).unwrap(), "");
+    assert!(repeat(&NO_LIMITS, text("ab"), -1).is_err());
+    assert!(repeat(&NO_LIMITS, text("ab"), i64::MAX).is_err());
+    assert_eq!(
+      repeat(&SHORT, text("ab"), 5),
+      Err(NativeError::Limit(Limit::StringLen(8)))
+    );
+  }
+
+  #[test]
+  fn reverse_keeps_characters_whole() {
+    assert_eq!(reverse(text("roma")), "amor");
+    assert_eq!(reverse(text(DECOMPOSED)), "e\u{301}fac");
+  }
+
+  #[test]
+  fn characters_of_text() {
+    assert_eq!(characters(text("ñu")), ["ñ", "u"]);
+    assert_eq!(characters(text(DECOMPOSED)), ["c", "a", "f", "e\u{301}"]);
+    assert!(characters(text("")).is_empty());
+  }
+
+  #[test]
+  fn errors_point_to_the_call() {
+    let module = builtin::modules()
+      .into_iter()
+      .find(|module| module.name == "texto")
+      .unwrap();
+    let location = Location {
+      file: text("programa.cobral"),
+      line: 3,
+      column: 7,
+    };
+    let host = MemoryHost::default();
+    let call = |name: &str, args: Vec<Value>| {
+      module
+        .function(name)
+        .unwrap()
+        .call(&host, &NO_LIMITS, args, location.clone())
+    };
+
+    assert_eq!(
+      call("tamanho", vec![Value::Integer(1)]),
+      Err(InterpreterError::TypeError(
+        location.clone(),
+        text("O argumento 1 de tamanho deve ser texto ou vetor, recebeu inteiro")
+      ))
+    );
+    assert!(matches!(
+      call("repetir", vec![Value::String(text("a")), Value::Integer(-2)]),
+      Err(InterpreterError::RuntimeError(at, _)) if at == location
+    ));
+    assert!(matches!(
+      call("dividir", vec![Value::String(text("a"))]),
+      Err(InterpreterError::ArgumentMismatchError(at, _)) if at == location
+    ));
+  }
+}
@@ -0,0 +1,12 @@
+use std::time::UNIX_EPOCH;
+
+use crate::interpreter::host::Host;
+
+/// Milliseconds since the Unix epoch, read from the host's clock
+pub fn now(host: &dyn Host) -> Result<i64, String> {
+  host
+    .now()
+    .duration_since(UNIX_EPOCH)
+    .map(|elapsed| elapsed.as_millis() as i64)
+    .map_err(|_| "O relógio do sistema está antes de 1970".to_string())
+}
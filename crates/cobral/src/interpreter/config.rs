@@ -0,0 +1,347 @@
+use std::{
+  fmt,
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+  },
+  time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::event::EventSystem;
+
+use super::{
+  coroutine::MAX_CALL_DEPTH,
+  environment::Environment,
+  error::InterpreterError,
+  host::{DeterministicHost, Host, HostHandle},
+  module::ModuleRegistry,
+  value::Value,
+  vm::Backend,
+  Interpreter,
+};
+
+/// How often, in steps, the clock is read when there is a time limit
+const CLOCK_INTERVAL: u64 = 1024;
+
+/// Settings of an `Interpreter`. Every limit is off by default; hosts running
+/// untrusted code, like a grading server, should set all of them
+#[derive(Debug, Clone, Default)]
+pub struct InterpreterConfig {
+  pub backend: Backend,
+
+  /// Stops the run from another thread. Each default config gets its own
+  /// handle, so only interpreters created from clones of it are cancelled
+  pub cancel: CancelHandle,
+
+  /// Where `escrever`, `erro` and `ler` print and ask for input
+  pub host: HostHandle,
+
+  /// Makes the run reproducible: `aleatorio` and `sortear` draw from a
+  /// generator seeded with it and `agora` reads a virtual clock
+  pub seed: Option<u64>,
+
+  /// Libraries the program can import. Parse it with the same registry
+  pub modules: ModuleRegistry,
+
+  /// Where this run announces debugger stops. Listen to it before creating
+  /// the interpreter to catch every event
+  pub events: EventSystem,
+
+  /// Statements executed, counting each loop iteration as one more
+  pub max_steps: Option<u64>,
+
+  /// Time spent running, not counting the time waiting for `ler()` input
+  pub max_duration: Option<Duration>,
+
+  /// Elements of a single list
+  pub max_list_len: Option<usize>,
+
+  /// Bytes of a single text
+  pub max_string_len: Option<usize>,
+
+  /// Bytes written by `escrever` and `erro` during the whole run
+  pub max_output: Option<usize>,
+
+  /// User function calls active at the same time. A built-in cap, derived from
+  /// the evaluator stack size, applies even without this limit
+  pub max_call_depth: Option<usize>,
+}
+
+/// Cancels a single run, checked before each statement and function call
+#[derive(Debug, Clone, Default)]
+pub struct CancelHandle(Arc<AtomicBool>);
+
+impl CancelHandle {
+  pub fn cancel(&self) {
+    self.0.store(true, Ordering::Relaxed);
+  }
+
+  pub fn is_cancelled(&self) -> bool {
+    self.0.load(Ordering::Relaxed)
+  }
+}
+
+/// The limit a program went over, with its configured value
+#[derive(Debug, Clone, PartialEq)]
+pub enum Limit {
+  Steps(u64),
+  Duration(Duration),
+  ListLen(usize),
+  StringLen(usize),
+  Output(usize),
+  CallDepth(usize),
+}
+
+impl fmt::Display for Limit {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Limit::Steps(max) => write!(f, "o programa executou mais de {} passos", max),
+      Limit::Duration(max) => write!(f, "o programa executou por mais de {} ms", max.as_millis()),
+      Limit::ListLen(max) => write!(f, "um vetor passou de {} elementos", max),
+      Limit::StringLen(max) => write!(f, "um texto passou de {} bytes", max),
+      Limit::Output(max) => write!(f, "a saída passou de {} bytes", max),
+      Limit::CallDepth(max) => write!(f, "as chamadas de função passaram de {} níveis", max),
+    }
+  }
+}
+
+/// The size limits of a config, for the library functions that check what
+/// they build before allocating it
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SizeLimits {
+  pub max_list_len: Option<usize>,
+  pub max_string_len: Option<usize>,
+}
+
+impl SizeLimits {
+  /// Fails if a list of `len` elements would go over the limit
+  pub fn check_list(&self, len: usize) -> Result<(), Limit> {
+    match self.max_list_len {
+      Some(max) if len > max => Err(Limit::ListLen(max)),
+      _ => Ok(()),
+    }
+  }
+
+  /// Fails if a text of `len` bytes would go over the limit
+  pub fn check_text(&self, len: usize) -> Result<(), Limit> {
+    match self.max_string_len {
+      Some(max) if len > max => Err(Limit::StringLen(max)),
+      _ => Ok(()),
+    }
+  }
+}
+
+impl InterpreterConfig {
+  pub fn size_limits(&self) -> SizeLimits {
+    SizeLimits {
+      max_list_len: self.max_list_len,
+      max_string_len: self.max_string_len,
+    }
+  }
+}
+
+/// What a run has consumed so far
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct Usage {
+  steps: u64,
+
+  /// Step at which the limits are checked again
+  #[serde(skip)]
+  next_check: u64,
+  output: usize,
+  elapsed: Duration,
+  #[serde(skip)]
+  running_since: Option<Instant>,
+}
+
+impl Usage {
+  fn elapsed(&self) -> Duration {
+    self.elapsed
+      + self
+        .running_since
+        .map_or(Duration::ZERO, |since| since.elapsed())
+  }
+}
+
+impl Interpreter {
+  pub fn with_config(
+    stmts: Vec<super::Statement>,
+    config: InterpreterConfig,
+  ) -> Result<Self, InterpreterError> {
+    let mut interpreter = Interpreter::build(stmts, config);
+
+    interpreter.run()?;
+
+    Ok(interpreter)
+  }
+
+  /// An interpreter that has not started yet, bound to the config's host
+  pub(crate) fn build(stmts: Vec<super::Statement>, mut config: InterpreterConfig) -> Self {
+    let deterministic = config.seed.map(|seed| {
+      let host = Arc::new(DeterministicHost::new(config.host.clone(), seed));
+      config.host = HostHandle::from(host.clone());
+      host
+    });
+
+    Interpreter {
+      environment: Environment::new(config.host.clone()),
+      statements: stmts.into(),
+      config,
+      deterministic,
+      ..Default::default()
+    }
+  }
+
+  pub fn config(&self) -> &InterpreterConfig {
+    &self.config
+  }
+
+  pub fn events(&self) -> &EventSystem {
+    &self.config.events
+  }
+
+  pub fn host(&self) -> &dyn Host {
+    &*self.config.host
+  }
+
+  /// Stops the run at the next statement with `InterpreterError::Cancelled`
+  pub fn cancel(&self) {
+    self.config.cancel.cancel();
+  }
+
+  /// Lets another thread cancel the run while `run` or `provide_input` blocks
+  pub fn cancel_handle(&self) -> CancelHandle {
+    self.config.cancel.clone()
+  }
+
+  /// Counts a statement or a loop iteration
+  #[inline]
+  pub(crate) fn tick(&mut self) -> Result<(), InterpreterError> {
+    self.check_cancelled()?;
+    self.usage.steps += 1;
+
+    if self.usage.steps < self.usage.next_check {
+      return Ok(());
+    }
+
+    self.check_budget()
+  }
+
+  fn check_budget(&mut self) -> Result<(), InterpreterError> {
+    if let Some(max) = self.config.max_steps {
+      if self.usage.steps > max {
+        return Err(self.limit_exceeded(Limit::Steps(max)));
+      }
+    }
+
+    if let Some(max) = self.config.max_duration {
+      if self.usage.elapsed() > max {
+        return Err(self.limit_exceeded(Limit::Duration(max)));
+      }
+    }
+
+    let steps = self.config.max_steps.map_or(u64::MAX, |max| max + 1);
+    let clock = match self.config.max_duration {
+      Some(_) => self.usage.steps + CLOCK_INTERVAL,
+      None => u64::MAX,
+    };
+    self.usage.next_check = steps.min(clock);
+
+    Ok(())
+  }
+
+  /// Starts the clock when the evaluation starts or resumes
+  pub(crate) fn start_clock(&mut self) {
+    self.usage.running_since.get_or_insert_with(Instant::now);
+  }
+
+  /// Stops the clock while the program waits for the host
+  pub(crate) fn stop_clock(&mut self) {
+    if let Some(since) = self.usage.running_since.take() {
+      self.usage.elapsed += since.elapsed();
+    }
+  }
+
+  /// Checks that a list or text that was just built is within the limits
+  pub(crate) fn check_size(&self, value: &Value) -> Result<(), InterpreterError> {
+    match (value, self.config.max_list_len, self.config.max_string_len) {
+      (Value::List(items), Some(max), _) if items.len() > max => {
+        Err(self.limit_exceeded(Limit::ListLen(max)))
+      }
+      (Value::String(s), _, Some(max)) if s.len() > max => {
+        Err(self.limit_exceeded(Limit::StringLen(max)))
+      }
+      _ => Ok(()),
+    }
+  }
+
+  /// The lists given to a library function, to check again after the call
+  /// since functions like `adicionar` grow them. Empty without a length limit
+  pub(crate) fn lists_to_check(&self, args: &[Value]) -> Vec<Value> {
+    if self.config.max_list_len.is_none() {
+      return Vec::new();
+    }
+
+    args
+      .iter()
+      .filter(|arg| matches!(arg, Value::List(_)))
+      .cloned()
+      .collect()
+  }
+
+  /// Counts what `escrever`, `erro`, `escreva` and `escreval` are about to
+  /// print, before they do
+  pub(crate) fn check_output(
+    &mut self,
+    name: &str,
+    args: &[Value],
+  ) -> Result<(), InterpreterError> {
+    let Some(max) = self.config.max_output else {
+      return Ok(());
+    };
+
+    // `escrever` and `erro` join the values with a space, `escreva` and
+    // `escreval` put them side by side
+    let spaces = match name {
+      "escrever" | "erro" => args.len().saturating_sub(1),
+      "escreva" | "escreval" => 0,
+      _ => return Ok(()),
+    };
+
+    let len = args.iter().map(|arg| arg.to_string().len()).sum::<usize>() + spaces;
+
+    self.usage.output += len;
+
+    if self.usage.output > max {
+      return Err(self.limit_exceeded(Limit::Output(max)));
+    }
+
+    Ok(())
+  }
+
+  pub(crate) fn check_call_depth(&self, depth: usize) -> Result<(), InterpreterError> {
+    let max = self
+      .config
+      .max_call_depth
+      .map_or(MAX_CALL_DEPTH, |max| max.min(MAX_CALL_DEPTH));
+
+    match depth > max {
+      true => Err(self.limit_exceeded(Limit::CallDepth(max))),
+      false => Ok(()),
+    }
+  }
+
+  pub(crate) fn check_cancelled(&self) -> Result<(), InterpreterError> {
+    if self.config.cancel.is_cancelled() {
+      return Err(InterpreterError::Cancelled(self.location.clone()));
+    }
+
+    Ok(())
+  }
+
+  fn limit_exceeded(&self, limit: Limit) -> InterpreterError {
+    InterpreterError::LimitExceeded(self.location.clone(), limit)
+  }
+}
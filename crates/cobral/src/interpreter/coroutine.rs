@@ -0,0 +1,156 @@
+use std::{
+  sync::mpsc::{self, Receiver, Sender},
+  thread,
+};
+
+use crate::shared::ast::Location;
+
+use super::{debugger::Stopped, error::InterpreterError, value::Value, Interpreter};
+
+/// Stack of the evaluator thread. Memory is only committed as it is used
+const EVALUATOR_STACK_SIZE: usize = 64 * 1024 * 1024;
+
+/// Evaluator stack given to each user function call. A call measured about
+/// 2 KB in release builds and 10 KB in debug builds, more with nested blocks
+const STACK_PER_CALL: usize = 16 * 1024;
+
+/// User function calls active at the same time, whatever the configuration,
+/// so that deep recursion ends with an error instead of overflowing the stack
+pub(crate) const MAX_CALL_DEPTH: usize = EVALUATOR_STACK_SIZE / STACK_PER_CALL;
+
+/// What the tree-walking evaluator reports back to the host
+pub(crate) enum Yield {
+  Waiting(u32),
+  Stopped(Stopped),
+  Completed,
+  Failed(InterpreterError),
+}
+
+/// What the host sends back to a suspended evaluator
+pub(crate) enum Resume {
+  Input(Value),
+  Continue,
+  /// Fails the suspended `ler()` call with this message
+  Fail(String),
+}
+
+/// Host side of an evaluation running on its own thread. The evaluator blocks
+/// at the `ler()` call itself until the input is sent back, so nothing is
+/// evaluated twice
+pub(crate) struct Coroutine {
+  resume: Sender<Resume>,
+  yields: Receiver<Yield>,
+}
+
+/// Evaluator side of a [`Coroutine`]
+pub(crate) struct Suspension {
+  yields: Sender<Yield>,
+  resume: Receiver<Resume>,
+}
+
+impl Coroutine {
+  pub fn spawn(mut evaluator: Interpreter) -> Self {
+    let (resume_sender, resume_receiver) = mpsc::channel();
+    let (yield_sender, yield_receiver) = mpsc::channel();
+
+    // Deep recursion in the evaluator needs a larger stack than the default.
+    // If the thread can't be created, `next` reports the run as interrupted
+    let _ = thread::Builder::new()
+      .stack_size(EVALUATOR_STACK_SIZE)
+      .spawn(move || {
+        evaluator.suspension = Some(Suspension {
+          yields: yield_sender.clone(),
+          resume: resume_receiver,
+        });
+
+        let outcome = match evaluator.eval_program() {
+          Ok(()) => Yield::Completed,
+          Err(e) => Yield::Failed(e),
+        };
+
+        // The host may have dropped the interpreter in the meantime
+        let _ = yield_sender.send(outcome);
+      });
+
+    Coroutine {
+      resume: resume_sender,
+      yields: yield_receiver,
+    }
+  }
+
+  /// Waits until the evaluator asks for input or finishes
+  pub fn next(&self) -> Yield {
+    self
+      .yields
+      .recv()
+      .unwrap_or_else(|_| Yield::Failed(interrupted(Location::default())))
+  }
+
+  /// Hands the input to the suspended `ler()` call and waits for the next stop
+  pub fn resume(&self, value: Value) -> Yield {
+    self.send(Resume::Input(value))
+  }
+
+  /// Makes the suspended `ler()` call fail, since its input will never come
+  pub fn fail(&self, message: String) -> Yield {
+    self.send(Resume::Fail(message))
+  }
+
+  /// Lets an evaluator stopped by the debugger go on
+  pub fn proceed(&self) -> Yield {
+    self.send(Resume::Continue)
+  }
+
+  fn send(&self, resume: Resume) -> Yield {
+    match self.resume.send(resume) {
+      Ok(()) => self.next(),
+      Err(_) => Yield::Failed(interrupted(Location::default())),
+    }
+  }
+}
+
+impl Interpreter {
+  /// Blocks the evaluator until the host provides the input for `callback_id`
+  pub(crate) fn suspend(&mut self, callback_id: u32) -> Result<Value, InterpreterError> {
+    if self.suspension.is_none() {
+      return Ok(Value::InputPending(callback_id));
+    }
+
+    match self.wait_for_host(Yield::Waiting(callback_id))? {
+      Resume::Input(value) => Ok(value),
+      Resume::Continue => Err(interrupted(self.location.clone())),
+      Resume::Fail(message) => Err(InterpreterError::RuntimeError(
+        self.location.clone(),
+        message,
+      )),
+    }
+  }
+
+  /// Reports `outcome` to the host and blocks until it answers
+  pub(crate) fn wait_for_host(&mut self, outcome: Yield) -> Result<Resume, InterpreterError> {
+    // Waiting for the host doesn't count as running time
+    self.stop_clock();
+    self.profile_pause();
+
+    let Some(suspension) = &self.suspension else {
+      return Err(interrupted(self.location.clone()));
+    };
+
+    if suspension.yields.send(outcome).is_err() {
+      return Err(interrupted(self.location.clone()));
+    }
+
+    let resume = suspension
+      .resume
+      .recv()
+      .map_err(|_| interrupted(self.location.clone()));
+
+    self.start_clock();
+    self.profile_resume();
+    resume
+  }
+}
+
+fn interrupted(location: Location) -> InterpreterError {
+  InterpreterError::RuntimeError(location, "A execução foi interrompida".into())
+}
@@ -0,0 +1,397 @@
+use std::{
+  collections::{BTreeMap, HashMap},
+  fmt::Write,
+  sync::Arc,
+};
+
+use parking_lot::Mutex;
+
+use crate::shared::ast::{Location, Statement};
+
+use super::{config::InterpreterConfig, error::InterpreterError, vm::Backend, Interpreter};
+
+/// One way through a `se` or an `escolha`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Branch {
+  /// E.g. `senao se 1`, `caso 2` or `nenhum` when no branch ran
+  pub label: String,
+  pub hits: u64,
+}
+
+/// A `se` or `escolha` statement and how often each of its branches ran
+#[derive(Debug, Clone, PartialEq)]
+pub struct BranchPoint {
+  pub line: usize,
+  pub column: usize,
+  pub kind: &'static str,
+  pub branches: Vec<Branch>,
+}
+
+impl BranchPoint {
+  /// Whether the statement itself ran
+  pub fn reached(&self) -> bool {
+    self.branches.iter().any(|branch| branch.hits > 0)
+  }
+}
+
+/// Which statements and branches of a program ran, in one or more runs.
+/// Statements of imported files are not measured
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Coverage {
+  /// Executions of the statements of each line
+  pub lines: BTreeMap<usize, u64>,
+
+  /// In source order
+  pub branches: Vec<BranchPoint>,
+}
+
+impl Coverage {
+  /// Every statement and branch of `stmts`, none executed yet
+  pub fn of(stmts: &[Statement]) -> Self {
+    let mut coverage = Coverage::default();
+    coverage.add_block(stmts);
+    coverage
+      .branches
+      .sort_by_key(|point| (point.line, point.column));
+    coverage
+  }
+
+  fn add_block(&mut self, stmts: &[Statement]) {
+    for stmt in stmts {
+      self.lines.insert(stmt.location().line, 0);
+
+      match stmt {
+        Statement::If {
+          true_block,
+          else_if_blocks,
+          else_block,
+          location,
+          ..
+        } => {
+          let mut labels = vec!["se".to_string()];
+          labels.extend((1..=else_if_blocks.len()).map(|i| format!("senao se {}", i)));
+          labels.push(else_label(else_block.is_some(), "senao"));
+          self.add_point(location, "se", labels);
+
+          self.add_block(true_block);
+          for (_, block) in else_if_blocks {
+            self.add_block(block);
+          }
+          if let Some(block) = else_block {
+            self.add_block(block);
+          }
+        }
+        Statement::Switch {
+          cases,
+          default,
+          location,
+          ..
+        } => {
+          let mut labels: Vec<String> = (1..=cases.len()).map(|i| format!("caso {}", i)).collect();
+          labels.push(else_label(default.is_some(), "padrao"));
+          self.add_point(location, "escolha", labels);
+
+          for (_, block, _) in cases {
+            self.add_block(block);
+          }
+          if let Some((block, _)) = default {
+            self.add_block(block);
+          }
+        }
+        Statement::While { body, .. }
+        | Statement::For { body, .. }
+        | Statement::ForEach { body, .. }
+        | Statement::Function { body, .. } => self.add_block(body),
+        _ => {}
+      }
+    }
+  }
+
+  fn add_point(&mut self, location: &Location, kind: &'static str, labels: Vec<String>) {
+    self.branches.push(BranchPoint {
+      line: location.line,
+      column: location.column,
+      kind,
+      branches: labels
+        .into_iter()
+        .map(|label| Branch { label, hits: 0 })
+        .collect(),
+    });
+  }
+
+  /// Adds the counts of another run of the same program
+  pub fn merge(&mut self, other: &Coverage) {
+    for (line, hits) in &other.lines {
+      *self.lines.entry(*line).or_default() += hits;
+    }
+
+    for point in &other.branches {
+      let existing = self
+        .branches
+        .iter_mut()
+        .find(|existing| (existing.line, existing.column) == (point.line, point.column));
+
+      match existing {
+        Some(existing) => {
+          for (branch, other) in existing.branches.iter_mut().zip(&point.branches) {
+            branch.hits += other.hits;
+          }
+        }
+        None => self.branches.push(point.clone()),
+      }
+    }
+  }
+
+  pub fn covered_lines(&self) -> usize {
+    self.lines.values().filter(|hits| **hits > 0).count()
+  }
+
+  pub fn covered_branches(&self) -> usize {
+    self
+      .branches
+      .iter()
+      .flat_map(|point| &point.branches)
+      .filter(|branch| branch.hits > 0)
+      .count()
+  }
+
+  pub fn total_branches(&self) -> usize {
+    self.branches.iter().map(|point| point.branches.len()).sum()
+  }
+
+  /// Branches that never ran, by line
+  pub fn missed_branches(&self) -> Vec<(usize, &str)> {
+    self
+      .branches
+      .iter()
+      .flat_map(|point| {
+        point
+          .branches
+          .iter()
+          .filter(|branch| branch.hits == 0)
+          .map(|branch| (point.line, branch.label.as_str()))
+      })
+      .collect()
+  }
+
+  /// The tracefile format read by genhtml and most coverage tools. `file` is
+  /// the path of the program
+  pub fn to_lcov(&self, file: &str) -> String {
+    let mut lcov = format!("TN:\nSF:{}\n", file);
+
+    for (block, point) in self.branches.iter().enumerate() {
+      let reached = point.reached();
+
+      for (index, branch) in point.branches.iter().enumerate() {
+        let taken = match reached {
+          true => branch.hits.to_string(),
+          false => "-".to_string(),
+        };
+
+        let _ = writeln!(lcov, "BRDA:{},{},{},{}", point.line, block, index, taken);
+      }
+    }
+
+    let _ = writeln!(lcov, "BRF:{}", self.total_branches());
+    let _ = writeln!(lcov, "BRH:{}", self.covered_branches());
+
+    for (line, hits) in &self.lines {
+      let _ = writeln!(lcov, "DA:{},{}", line, hits);
+    }
+
+    let _ = writeln!(lcov, "LF:{}", self.lines.len());
+    let _ = writeln!(lcov, "LH:{}", self.covered_lines());
+    lcov += "end_of_record\n";
+
+    lcov
+  }
+
+  /// The source of the program with each line marked as executed or not, and
+  /// the branches that never ran next to their `se` or `escolha`
+  pub fn to_html(&self, file: &str, source: &str) -> String {
+    let mut missed: HashMap<usize, Vec<&str>> = HashMap::new();
+    for (line, label) in self.missed_branches() {
+      missed.entry(line).or_default().push(label);
+    }
+
+    let mut html = format!(
+      "<!DOCTYPE html>\n<html lang=\"pt-BR\">\n<head>\n<meta charset=\"utf-8\">\n\
+       <title>Cobertura de {file}</title>\n<style>\n{STYLE}</style>\n</head>\n<body>\n\
+       <h1>{file}</h1>\n<p>Linhas: {} de {} ({:.1}%) &middot; Ramos: {} de {} ({:.1}%)</p>\n\
+       <table>\n",
+      self.covered_lines(),
+      self.lines.len(),
+      percent(self.covered_lines(), self.lines.len()),
+      self.covered_branches(),
+      self.total_branches(),
+      percent(self.covered_branches(), self.total_branches()),
+      file = escape(file),
+    );
+
+    for (index, code) in source.lines().enumerate() {
+      let line = index + 1;
+
+      let (class, hits) = match self.lines.get(&line) {
+        Some(0) => ("nao-executada", "0".to_string()),
+        Some(hits) => ("executada", hits.to_string()),
+        None => ("", String::new()),
+      };
+
+      let note = match missed.get(&line) {
+        Some(labels) => format!(
+          "<span class=\"ramos\">ramos não executados: {}</span>",
+          escape(&labels.join(", "))
+        ),
+        None => String::new(),
+      };
+
+      let _ = writeln!(
+        html,
+        "<tr class=\"{}\"><td class=\"linha\">{}</td><td class=\"vezes\">{}</td>\
+         <td><pre>{}</pre>{}</td></tr>",
+        class,
+        line,
+        hits,
+        escape(code),
+        note
+      );
+    }
+
+    html += "</table>\n</body>\n</html>\n";
+    html
+  }
+}
+
+const STYLE: &str = "body { font-family: sans-serif; }
+table { border-collapse: collapse; }
+td { padding: 0 8px; vertical-align: top; }
+pre { display: inline; margin: 0; }
+.linha, .vezes { color: #888; text-align: right; }
+.executada { background: #dfd; }
+.nao-executada { background: #fdd; }
+.ramos { margin-left: 16px; color: #a00; font-size: 0.9em; }
+";
+
+/// The branch that runs when no condition or case matches
+fn else_label(explicit: bool, keyword: &str) -> String {
+  match explicit {
+    true => keyword.to_string(),
+    false => "nenhum".to_string(),
+  }
+}
+
+fn percent(part: usize, total: usize) -> f64 {
+  match total {
+    0 => 100.0,
+    _ => part as f64 / total as f64 * 100.0,
+  }
+}
+
+fn escape(text: &str) -> String {
+  text
+    .replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('"', "&quot;")
+}
+
+/// Counts executions while the evaluator runs
+pub(crate) struct CoverageRecorder {
+  coverage: Coverage,
+
+  /// Position of each branch point in `coverage.branches`
+  points: HashMap<(usize, usize), usize>,
+}
+
+impl CoverageRecorder {
+  pub(crate) fn new(stmts: &[Statement]) -> Self {
+    let coverage = Coverage::of(stmts);
+    let points = coverage
+      .branches
+      .iter()
+      .enumerate()
+      .map(|(index, point)| ((point.line, point.column), index))
+      .collect();
+
+    CoverageRecorder { coverage, points }
+  }
+}
+
+impl Interpreter {
+  /// Creates an interpreter that counts which statements and branches run.
+  /// Coverage uses the tree-walking backend, which visits every statement
+  pub fn with_coverage(
+    stmts: Vec<Statement>,
+    config: InterpreterConfig,
+  ) -> Result<Self, InterpreterError> {
+    let mut interpreter = Interpreter::covering(stmts, config);
+
+    interpreter.run()?;
+
+    Ok(interpreter)
+  }
+
+  /// Like `with_coverage`, without starting the program. The counts are still
+  /// there when `run` fails, up to the failing statement
+  pub fn covering(stmts: Vec<Statement>, config: InterpreterConfig) -> Self {
+    let config = InterpreterConfig {
+      backend: Backend::TreeWalker,
+      ..config
+    };
+
+    let recorder = CoverageRecorder::new(&stmts);
+    Interpreter {
+      coverage: Some(Arc::new(Mutex::new(recorder))),
+      ..Interpreter::build(stmts, config)
+    }
+  }
+
+  /// What ran so far, if coverage is on
+  pub fn coverage(&self) -> Option<Coverage> {
+    self
+      .coverage
+      .as_ref()
+      .map(|recorder| recorder.lock().coverage.clone())
+  }
+
+  pub(crate) fn cover_stmt(&mut self, stmt: &Statement) {
+    let Some(recorder) = &self.coverage else {
+      return;
+    };
+
+    // Lines of imported files would count as lines of the program
+    if !stmt.location().file.is_empty() {
+      return;
+    }
+
+    if let Some(hits) = recorder
+      .lock()
+      .coverage
+      .lines
+      .get_mut(&stmt.location().line)
+    {
+      *hits += 1;
+    }
+  }
+
+  /// Notes that branch `index` of the `se` or `escolha` at `location` ran.
+  /// The last index is the implicit branch when there is no `senao`/`padrao`
+  pub(crate) fn cover_branch(&mut self, location: &Location, index: usize) {
+    let Some(recorder) = &self.coverage else {
+      return;
+    };
+
+    if !location.file.is_empty() {
+      return;
+    }
+
+    let mut recorder = recorder.lock();
+    let Some(&point) = recorder.points.get(&(location.line, location.column)) else {
+      return;
+    };
+
+    if let Some(branch) = recorder.coverage.branches[point].branches.get_mut(index) {
+      branch.hits += 1;
+    }
+  }
+}
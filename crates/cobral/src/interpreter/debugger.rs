@@ -0,0 +1,435 @@
+use std::{
+  collections::{BTreeMap, HashMap},
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+  },
+};
+
+use parking_lot::Mutex;
+use serde::Serialize;
+
+use crate::{
+  lexer::Lexer,
+  parser::Parser,
+  shared::ast::{Expression, Location, Statement},
+};
+
+use super::{
+  config::InterpreterConfig, coroutine::Yield, environment::Symbol, error::InterpreterError,
+  history::Recorder, value::Value, vm::Backend, Interpreter, InterpreterState,
+};
+
+/// Name shown for the top-level code in the call stack
+pub(crate) const MAIN_FRAME: &str = "principal";
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum StopReason {
+  Entry,
+  Breakpoint,
+  Step,
+  Pause,
+  /// Showing an earlier step from the history
+  StepBack,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StackFrame {
+  pub name: String,
+  pub location: Location,
+  pub variables: Vec<(String, Value)>,
+}
+
+/// Sent with the `debug_stopped` event whenever the program stops
+#[derive(Debug, Clone, Serialize)]
+pub struct Stopped {
+  pub reason: StopReason,
+  pub location: Location,
+
+  /// Innermost frame first
+  pub frames: Vec<StackFrame>,
+}
+
+/// Stops the program at the next statement, from any thread
+#[derive(Clone)]
+pub struct PauseHandle(Arc<Debugger>);
+
+impl PauseHandle {
+  pub fn pause(&self) {
+    self.0.pause.store(true, Ordering::SeqCst);
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Step {
+  Run,
+  Entry,
+  Into,
+  Over(usize),
+  Out(usize),
+}
+
+/// Debugging state shared by the host and the evaluator thread
+pub(crate) struct Debugger {
+  breakpoints: Mutex<HashMap<usize, Option<Expression>>>,
+  step: Mutex<Step>,
+  pause: AtomicBool,
+}
+
+impl Debugger {
+  fn new() -> Self {
+    Debugger {
+      breakpoints: Mutex::new(HashMap::new()),
+      step: Mutex::new(Step::Entry),
+      pause: AtomicBool::new(false),
+    }
+  }
+}
+
+/// A user function being executed. What it changes in the caller's symbols is
+/// kept in `Symbols`, to undo on return
+pub(crate) struct CallFrame {
+  pub name: String,
+  pub call_site: Location,
+}
+
+impl Interpreter {
+  /// Creates an interpreter stopped before the first statement. Debugging uses
+  /// the tree-walking backend, which visits every statement and its location.
+  /// The history is recorded so that `step_back` can show earlier steps
+  pub fn debug(stmts: Vec<Statement>, config: InterpreterConfig) -> Result<Self, InterpreterError> {
+    let mut config = InterpreterConfig {
+      backend: Backend::TreeWalker,
+      ..config
+    };
+    let recorder = Recorder::new(&mut config);
+
+    let mut interpreter = Interpreter {
+      debugger: Some(Arc::new(Debugger::new())),
+      history: Some(Arc::new(Mutex::new(recorder))),
+      ..Interpreter::build(stmts, config)
+    };
+    interpreter.environment.symbols.write().watch();
+
+    interpreter.run()?;
+
+    Ok(interpreter)
+  }
+
+  /// Stops at `line`, or only when `condition` evaluates to `verdadeiro`
+  pub fn set_breakpoint(
+    &self,
+    line: usize,
+    condition: Option<&str>,
+  ) -> Result<(), InterpreterError> {
+    let debugger = self.expect_debugger()?;
+    let condition = condition.map(parse_expression).transpose()?;
+
+    debugger.breakpoints.lock().insert(line, condition);
+
+    Ok(())
+  }
+
+  pub fn remove_breakpoint(&self, line: usize) -> Result<(), InterpreterError> {
+    self.expect_debugger()?.breakpoints.lock().remove(&line);
+    Ok(())
+  }
+
+  pub fn pause_handle(&self) -> Option<PauseHandle> {
+    self.debugger.clone().map(PauseHandle)
+  }
+
+  /// Where and why the program is stopped, with the call stack at that point
+  pub fn stopped(&self) -> Option<&Stopped> {
+    self.stopped.as_ref()
+  }
+
+  pub fn resume(&mut self) -> Result<(), InterpreterError> {
+    self.continue_with(Step::Run)
+  }
+
+  pub fn step_into(&mut self) -> Result<(), InterpreterError> {
+    self.continue_with(Step::Into)
+  }
+
+  pub fn step_over(&mut self) -> Result<(), InterpreterError> {
+    let depth = self.stopped_depth();
+    self.continue_with(Step::Over(depth))
+  }
+
+  pub fn step_out(&mut self) -> Result<(), InterpreterError> {
+    let depth = self.stopped_depth();
+    self.continue_with(Step::Out(depth))
+  }
+
+  /// Shows the program as it was one statement earlier. Only the variables of
+  /// the function running then are shown, in a single frame
+  pub fn step_back(&mut self) -> Result<(), InterpreterError> {
+    let viewed = self.viewed_step()?;
+    self.travel_to(viewed.saturating_sub(1).max(1))
+  }
+
+  /// Goes back to the latest earlier step at a breakpoint line, ignoring
+  /// conditions, or to the first step
+  pub fn reverse_continue(&mut self) -> Result<(), InterpreterError> {
+    let debugger = self.expect_debugger()?;
+    let viewed = self.viewed_step()?;
+
+    let breakpoints = debugger.breakpoints.lock().clone();
+    let target = (1..viewed)
+      .rev()
+      .find(|step| {
+        self
+          .recorded_line(*step)
+          .is_some_and(|line| breakpoints.contains_key(&line))
+      })
+      .unwrap_or(1);
+
+    self.travel_to(target)
+  }
+
+  /// Evaluates an expression over the variables of the innermost frame while
+  /// the program is paused
+  pub fn evaluate(&mut self, source: &str) -> Result<Value, InterpreterError> {
+    self.expect_debugger()?;
+
+    if self.state != InterpreterState::Paused {
+      return Err(not_paused(self.location.clone()));
+    }
+
+    if self.rewound.is_some() {
+      return Err(InterpreterError::RuntimeError(
+        self.location.clone(),
+        "Não é possível avaliar expressões em um passo anterior".into(),
+      ));
+    }
+
+    let expr = parse_expression(source)?;
+    let location = self.location.clone();
+    let value = self.eval_expr(&expr);
+    self.location = location;
+
+    value
+  }
+
+  fn continue_with(&mut self, step: Step) -> Result<(), InterpreterError> {
+    let debugger = self.expect_debugger()?;
+
+    if self.state != InterpreterState::Paused {
+      return Err(not_paused(self.location.clone()));
+    }
+
+    // Stepping forward through the past moves one statement at a time
+    if let Some((viewed, _)) = self.rewound {
+      match step {
+        Step::Into | Step::Over(_) => return self.travel_to(viewed + 1),
+        Step::Out(_) => return self.travel_to(usize::MAX),
+        Step::Run | Step::Entry => self.travel_to(usize::MAX)?,
+      }
+    }
+
+    *debugger.step.lock() = step;
+    self.stopped = None;
+    self.state = InterpreterState::Running;
+
+    let outcome = match &self.coroutine {
+      Some(coroutine) => coroutine.proceed(),
+      None => Yield::Completed,
+    };
+
+    self.settle(outcome)
+  }
+
+  /// The step being shown: the present one unless `step_back` was used
+  fn viewed_step(&self) -> Result<usize, InterpreterError> {
+    self.expect_debugger()?;
+
+    if self.state != InterpreterState::Paused {
+      return Err(not_paused(self.location.clone()));
+    }
+
+    Ok(
+      self
+        .rewound
+        .as_ref()
+        .map_or(self.history_len(), |(step, _)| *step),
+    )
+  }
+
+  /// Shows the state before `step`, or the present if `step` is not earlier
+  fn travel_to(&mut self, step: usize) -> Result<(), InterpreterError> {
+    if step >= self.history_len() {
+      if let Some((_, present)) = self.rewound.take() {
+        self.announce_stop(present);
+      }
+
+      return Ok(());
+    }
+
+    let state = self.state_at(step).ok_or_else(|| {
+      InterpreterError::RuntimeError(self.location.clone(), "Passo não registrado".into())
+    })?;
+
+    let present = match self.rewound.take() {
+      Some((_, present)) => present,
+      None => self.stopped.take().expect("paused programs have a stop"),
+    };
+    self.rewound = Some((step, present));
+
+    self.announce_stop(Stopped {
+      reason: StopReason::StepBack,
+      location: state.location.clone(),
+      frames: vec![StackFrame {
+        name: state.function,
+        location: state.location,
+        variables: state.variables.into_iter().collect(),
+      }],
+    });
+
+    Ok(())
+  }
+
+  fn stopped_depth(&self) -> usize {
+    self
+      .stopped
+      .as_ref()
+      .map_or(0, |stopped| stopped.frames.len() - 1)
+  }
+
+  fn expect_debugger(&self) -> Result<Arc<Debugger>, InterpreterError> {
+    self.debugger.clone().ok_or_else(|| {
+      InterpreterError::RuntimeError(
+        self.location.clone(),
+        "O interpretador não foi iniciado em modo de depuração".into(),
+      )
+    })
+  }
+
+  pub(crate) fn announce_stop(&mut self, stopped: Stopped) {
+    if let Ok(payload) = serde_json::to_string(&stopped) {
+      self.config.events.emit("debug_stopped", payload);
+    }
+
+    self.stopped = Some(stopped);
+    self.state = InterpreterState::Paused;
+  }
+
+  /// Called by the evaluator before each statement of a block. The parts of a
+  /// `para` header are not stops of their own
+  pub(crate) fn debug_hook(&mut self, stmt: &Statement) -> Result<(), InterpreterError> {
+    let Some(debugger) = self.debugger.clone() else {
+      return Ok(());
+    };
+
+    let location = stmt.location();
+    let depth = self.call_stack.len();
+
+    let Some(reason) = self.stop_reason(&debugger, depth, location.line)? else {
+      return Ok(());
+    };
+
+    *debugger.step.lock() = Step::Run;
+    debugger.pause.store(false, Ordering::SeqCst);
+
+    let stopped = Stopped {
+      reason,
+      frames: self.stack_frames(&location),
+      location,
+    };
+
+    self.wait_for_host(Yield::Stopped(stopped)).map(|_| ())
+  }
+
+  fn stop_reason(
+    &mut self,
+    debugger: &Debugger,
+    depth: usize,
+    line: usize,
+  ) -> Result<Option<StopReason>, InterpreterError> {
+    if debugger.pause.load(Ordering::SeqCst) {
+      return Ok(Some(StopReason::Pause));
+    }
+
+    let step = *debugger.step.lock();
+    let reason = match step {
+      Step::Entry => Some(StopReason::Entry),
+      Step::Into => Some(StopReason::Step),
+      Step::Over(from) if depth <= from => Some(StopReason::Step),
+      Step::Out(from) if depth < from => Some(StopReason::Step),
+      _ => None,
+    };
+
+    if reason.is_some() {
+      return Ok(reason);
+    }
+
+    let breakpoint = debugger.breakpoints.lock().get(&line).cloned();
+
+    match breakpoint {
+      Some(None) => Ok(Some(StopReason::Breakpoint)),
+      Some(Some(condition)) => {
+        // The condition must not move the reported location
+        let location = self.location.clone();
+        let value = self.eval_expr(&condition);
+        self.location = location;
+
+        match value? {
+          Value::Boolean(true) => Ok(Some(StopReason::Breakpoint)),
+          _ => Ok(None),
+        }
+      }
+      None => Ok(None),
+    }
+  }
+
+  fn stack_frames(&self, location: &Location) -> Vec<StackFrame> {
+    let mut frames = Vec::with_capacity(self.call_stack.len() + 1);
+    let mut location = location.clone();
+    let symbols = self.environment.symbols.read();
+    let mut variables = variables_of(&symbols);
+
+    for (index, frame) in self.call_stack.iter().enumerate().rev() {
+      frames.push(StackFrame {
+        name: frame.name.clone(),
+        location,
+        variables,
+      });
+
+      location = frame.call_site.clone();
+      variables = variables_of(&symbols.before_call(index));
+    }
+
+    frames.push(StackFrame {
+      name: MAIN_FRAME.to_string(),
+      location,
+      variables,
+    });
+
+    frames
+  }
+}
+
+/// Copies of the values at the stop, which the program may change afterwards
+fn variables_of(symbols: &BTreeMap<String, Symbol>) -> Vec<(String, Value)> {
+  symbols
+    .iter()
+    .map(|(name, symbol)| (name.clone(), symbol.get_value().deep_copy()))
+    .collect()
+}
+
+fn not_paused(location: Location) -> InterpreterError {
+  InterpreterError::RuntimeError(location, "O programa não está pausado".into())
+}
+
+/// Parses a breakpoint condition or an expression typed in the debugger
+fn parse_expression(source: &str) -> Result<Expression, InterpreterError> {
+  let invalid = |message: String| InterpreterError::EvalError(Location::default(), message);
+
+  let tokens = Lexer::new(source).map_err(|e| invalid(e.to_string()))?;
+  let mut stmts = Parser::new(tokens).map_err(|e| invalid(e.to_string()))?;
+
+  match (stmts.pop(), stmts.is_empty()) {
+    (Some(Statement::Expression(expr, _)), true) => Ok(expr),
+    _ => Err(invalid(format!("Expressão inválida: {}", source))),
+  }
+}
@@ -1,15 +1,21 @@
 use std::{
-  collections::{BTreeMap, HashMap},
-  sync::Arc,
+  collections::{BTreeMap, BTreeSet, HashMap},
+  ops::Deref,
+  sync::{atomic::AtomicU32, Arc},
 };
 
 use super::{
-  builtin::io::{error, read, write},
+  builtin::{
+    io::{error, read, write},
+    list::copy,
+  },
+  host::HostHandle,
   value::Value,
   LibFn,
 };
-use crate::shared::ast::{Expression, Statement};
+use crate::shared::ast::Statement;
 use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum EnvironmentError {
@@ -30,7 +36,7 @@ impl std::fmt::Display for EnvironmentError {
 
 impl std::error::Error for EnvironmentError {}
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum Symbol {
   Variable(Value),
   Constant(Value),
@@ -54,17 +60,204 @@ impl Symbol {
   }
 }
 
+/// Symbols a function call changed, with their values before the call. `None`
+/// for the ones the call declared
+pub(crate) type Changes = BTreeMap<String, Option<Symbol>>;
+
+/// The variables and constants in scope. Functions see the caller's symbols,
+/// so each call running keeps the changes it made, undone when it returns
+/// instead of copying every symbol at the call
+#[derive(Debug, Default)]
+pub struct Symbols {
+  values: BTreeMap<String, Symbol>,
+  calls: Vec<Changes>,
+
+  /// Names written since the trace or the history last looked, while one of
+  /// them is on
+  written: Option<BTreeSet<String>>,
+}
+
+impl Deref for Symbols {
+  type Target = BTreeMap<String, Symbol>;
+
+  fn deref(&self) -> &Self::Target {
+    &self.values
+  }
+}
+
+impl Symbols {
+  pub fn insert(&mut self, name: String, symbol: Symbol) {
+    self.note(&name);
+    self.values.insert(name, symbol);
+  }
+
+  pub fn remove(&mut self, name: &str) -> Option<Symbol> {
+    self.note(name);
+    self.values.remove(name)
+  }
+
+  pub fn get_mut(&mut self, name: &str) -> Option<&mut Symbol> {
+    self.note(name);
+    self.values.get_mut(name)
+  }
+
+  pub fn retain(&mut self, mut keep: impl FnMut(&str, &Symbol) -> bool) {
+    let dropped: Vec<String> = self
+      .values
+      .iter()
+      .filter(|(name, symbol)| !keep(name, symbol))
+      .map(|(name, _)| name.clone())
+      .collect();
+
+    for name in dropped {
+      self.remove(&name);
+    }
+  }
+
+  /// Replaces every symbol, e.g. when a snapshot is restored
+  pub(crate) fn replace(&mut self, values: BTreeMap<String, Symbol>, calls: Vec<Changes>) {
+    self.values = values;
+    self.calls = calls;
+  }
+
+  pub(crate) fn calls(&self) -> &[Changes] {
+    &self.calls
+  }
+
+  pub(crate) fn enter_call(&mut self) {
+    self.calls.push(Changes::new());
+  }
+
+  /// Undoes what the innermost call changed
+  pub(crate) fn exit_call(&mut self) {
+    let Some(changes) = self.calls.pop() else {
+      return;
+    };
+
+    for (name, old) in changes {
+      self.touch(&name);
+
+      match old {
+        Some(symbol) => self.values.insert(name, symbol),
+        None => self.values.remove(&name),
+      };
+    }
+  }
+
+  /// Undoes every call still running, e.g. after a run failed inside one
+  pub(crate) fn exit_calls(&mut self) {
+    while !self.calls.is_empty() {
+      self.exit_call();
+    }
+  }
+
+  /// The symbols as they were when the call `index`, counting from the
+  /// outermost, started
+  pub(crate) fn before_call(&self, index: usize) -> BTreeMap<String, Symbol> {
+    let mut values = self.values.clone();
+
+    for changes in self.calls.iter().skip(index).rev() {
+      for (name, old) in changes {
+        match old {
+          Some(symbol) => values.insert(name.clone(), symbol.clone()),
+          None => values.remove(name),
+        };
+      }
+    }
+
+    values
+  }
+
+  /// Starts noting the names written, for `take_written`
+  pub(crate) fn watch(&mut self) {
+    self.written.get_or_insert_with(BTreeSet::new);
+  }
+
+  /// Notes that `name` changed without being assigned, e.g. an element of its
+  /// list
+  pub(crate) fn touch(&mut self, name: &str) {
+    if let Some(written) = &mut self.written {
+      if !written.contains(name) {
+        written.insert(name.to_string());
+      }
+    }
+  }
+
+  /// The names written since the last call
+  pub(crate) fn take_written(&mut self) -> BTreeSet<String> {
+    self
+      .written
+      .as_mut()
+      .map(std::mem::take)
+      .unwrap_or_default()
+  }
+
+  /// Keeps the value of `name` before the innermost call changed it
+  fn note(&mut self, name: &str) {
+    self.touch(name);
+
+    let Some(changes) = self.calls.last_mut() else {
+      return;
+    };
+
+    if !changes.contains_key(name) {
+      changes.insert(name.to_string(), self.values.get(name).cloned());
+    }
+  }
+}
+
+/// Parameters and body of a user function, shared by every call instead of
+/// being cloned
+#[derive(Debug)]
+pub struct FunctionDef {
+  pub params: Vec<String>,
+  pub body: Vec<Statement>,
+}
+
 #[derive(Clone)]
 pub struct Environment {
-  pub symbols: Arc<RwLock<BTreeMap<String, Symbol>>>,
-  pub functions: Arc<RwLock<HashMap<String, (Vec<String>, Vec<Statement>)>>>,
+  pub symbols: Arc<RwLock<Symbols>>,
+  pub functions: Arc<RwLock<HashMap<String, Arc<FunctionDef>>>>,
   pub libs: Arc<RwLock<HashMap<String, LibFn>>>,
-  pub input_result: Arc<RwLock<Option<Value>>>,
-  pub pending_function_call: Arc<RwLock<Option<(String, Vec<Expression>)>>>,
+  /// `ler()` calls so far, which number the input requests
+  pub(crate) input_requests: Arc<AtomicU32>,
   fn_scope_depth: usize,
 }
 
 impl Environment {
+  /// Creates the global scope, with `escrever`, `erro` and `ler` bound to the
+  /// host of a single interpreter, and `copiar`
+  pub fn new(host: HostHandle) -> Self {
+    let requests = Arc::new(AtomicU32::new(0));
+
+    let read_requests = requests.clone();
+    let write_host = host.clone();
+    let error_host = host.clone();
+    let default_libs = HashMap::from([
+      (
+        "escrever".to_string(),
+        Arc::new(move |args, location| write(&*write_host, args, location)) as LibFn,
+      ),
+      (
+        "erro".to_string(),
+        Arc::new(move |args, location| error(&*error_host, args, location)) as LibFn,
+      ),
+      (
+        "ler".to_string(),
+        Arc::new(move |args, location| read(&*host, &read_requests, args, location)) as LibFn,
+      ),
+      ("copiar".to_string(), Arc::new(copy) as LibFn),
+    ]);
+
+    Environment {
+      symbols: Arc::new(RwLock::new(Symbols::default())),
+      functions: Arc::new(RwLock::new(HashMap::new())),
+      libs: Arc::new(RwLock::new(default_libs)),
+      input_requests: requests,
+      fn_scope_depth: 0,
+    }
+  }
+
   pub fn define_variable(&self, name: String, value: Value) -> Result<(), EnvironmentError> {
     let mut symbols = self.symbols.write();
     match symbols.get(&name) {
@@ -89,12 +282,10 @@ impl Environment {
     Ok(())
   }
 
-  pub fn get_symbol(&self, name: &str) -> Option<Arc<RwLock<Symbol>>> {
-    self
-      .symbols
-      .read()
-      .get(name)
-      .map(|symbol| Arc::new(RwLock::new(symbol.clone())))
+  /// A copy of the symbol. Lists are shared, so copying one is cheap and
+  /// changes to its elements are seen through the variable
+  pub fn get_symbol(&self, name: &str) -> Option<Symbol> {
+    self.symbols.read().get(name).cloned()
   }
 
   pub fn set_symbol_value(&self, name: &str, new_value: Value) -> Result<(), EnvironmentError> {
@@ -111,7 +302,7 @@ impl Environment {
     self.libs.read().get(name).cloned()
   }
 
-  pub fn get_function(&self, name: &str) -> Option<(Vec<String>, Vec<Statement>)> {
+  pub fn get_function(&self, name: &str) -> Option<Arc<FunctionDef>> {
     self.functions.read().get(name).cloned()
   }
 
@@ -124,59 +315,32 @@ impl Environment {
     }
   }
 
-  // Changed from define_input_result to set_input_result
-  pub fn set_input_result(&self, value: Value) {
-    let mut input = self.input_result.write();
-    *input = Some(value);
-  }
-
-  // Change this method to not require &mut self
-  pub fn take_input_result(&self) -> Option<Value> {
-    let mut input = self.input_result.write();
-    input.take()
-  }
-
-  pub fn set_pending_function_call(&self, function_name: String, args: Vec<Expression>) {
-    let mut pending = self.pending_function_call.write();
-    *pending = Some((function_name, args));
-  }
-
-  pub fn take_pending_function_call(&self) -> Option<(String, Vec<Expression>)> {
-    let mut pending = self.pending_function_call.write();
-    pending.take()
-  }
-  
   // Track if we're currently in a function scope
   pub fn enter_function_scope(&mut self) {
     self.fn_scope_depth += 1;
   }
-  
+
   pub fn exit_function_scope(&mut self) {
     if self.fn_scope_depth > 0 {
       self.fn_scope_depth -= 1;
     }
   }
-  
+
   pub fn is_in_function_scope(&self) -> bool {
     self.fn_scope_depth > 0
   }
+
+  pub fn function_depth(&self) -> usize {
+    self.fn_scope_depth
+  }
+
+  pub(crate) fn set_function_depth(&mut self, depth: usize) {
+    self.fn_scope_depth = depth;
+  }
 }
 
 impl Default for Environment {
   fn default() -> Self {
-    let default_libs = HashMap::from([
-      ("escrever".to_string(), Arc::new(write) as LibFn),
-      ("erro".to_string(), Arc::new(error) as LibFn),
-      ("ler".to_string(), Arc::new(read) as LibFn),
-    ]);
-
-    Environment {
-      symbols: Arc::new(RwLock::new(BTreeMap::new())),
-      functions: Arc::new(RwLock::new(HashMap::new())),
-      libs: Arc::new(RwLock::new(default_libs)),
-      input_result: Arc::new(RwLock::new(None)),
-      pending_function_call: Arc::new(RwLock::new(None)),
-      fn_scope_depth: 0,
-    }
+    Environment::new(HostHandle::default())
   }
 }
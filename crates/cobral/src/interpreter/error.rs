@@ -3,7 +3,7 @@ use thiserror::Error;
 
 use crate::shared::ast::Location;
 
-use super::environment::EnvironmentError;
+use super::{config::Limit, environment::EnvironmentError};
 
 const ERROR_MESSAGE: &str = "Erro de interpretação";
 
@@ -29,4 +29,8 @@ pub enum InterpreterError {
   TypeError(Location, String),
   #[error("{ERROR_MESSAGE}:\n\tErro no ambiente: '{0}'")]
   EnvironmentError(#[from] EnvironmentError),
+  #[error("{ERROR_MESSAGE}:\n\t{0}: Limite de execução excedido: {1}")]
+  LimitExceeded(Location, Limit),
+  #[error("{ERROR_MESSAGE}:\n\t{0}: Execução cancelada")]
+  Cancelled(Location),
 }
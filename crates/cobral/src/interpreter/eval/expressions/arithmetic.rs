@@ -7,15 +7,24 @@ use crate::{
 impl Interpreter {
   pub fn eval_arithmetic_expr(
     &mut self,
-    lhs: Expression,
-    op: Token,
-    rhs: Expression,
+    lhs: &Expression,
+    op: &Token,
+    rhs: &Expression,
   ) -> Result<Value, InterpreterError> {
-    let lhs_value = self.eval_expr(&lhs)?;
+    let lhs_value = self.eval_expr(lhs)?;
 
-    let rhs_value = self.eval_expr(&rhs)?;
+    let rhs_value = self.eval_expr(rhs)?;
 
-    match (lhs_value, rhs_value, op) {
+    self.apply_arithmetic(lhs_value, op, rhs_value)
+  }
+
+  pub fn apply_arithmetic(
+    &self,
+    lhs_value: Value,
+    op: &Token,
+    rhs_value: Value,
+  ) -> Result<Value, InterpreterError> {
+    let value = match (lhs_value, rhs_value, op) {
       // Handle integer arithmetic
       (Value::Integer(l), Value::Integer(r), Token::Rem) => Ok(Value::Integer(l % r)),
       (Value::Integer(l), Value::Integer(r), Token::Plus) => Ok(Value::Integer(l + r)),
@@ -108,18 +117,27 @@ impl Interpreter {
         self.location.clone(),
         "Operação inválida entre tipos incompatíveis".into(),
       )),
-    }
+    }?;
+
+    // Concatenation is the only way a text grows
+    self.check_size(&value)?;
+
+    Ok(value)
   }
 }
 
 impl Interpreter {
   pub fn eval_unary_expr(
     &mut self,
-    token: Token,
-    expr: Expression,
+    token: &Token,
+    expr: &Expression,
   ) -> Result<Value, InterpreterError> {
-    let value = self.eval_expr(&expr)?;
+    let value = self.eval_expr(expr)?;
+
+    self.apply_unary(token, value)
+  }
 
+  pub fn apply_unary(&self, token: &Token, value: Value) -> Result<Value, InterpreterError> {
     match token {
       Token::Plus => match value {
         Value::Integer(n) => Ok(Value::Integer(n.abs())),
@@ -1,13 +1,25 @@
+use std::sync::Arc;
+
 use crate::{
-  interpreter::{builtin, error::InterpreterError, value::Value, Interpreter},
+  interpreter::{
+    debugger::CallFrame, environment::FunctionDef, error::InterpreterError, value::Value,
+    Interpreter,
+  },
   shared::ast::{Expression, Statement},
 };
 
+/// A `retorne f(...)` waiting for the current call to end, so that `f` takes its place
+pub(crate) struct TailCall {
+  name: String,
+  function: Arc<FunctionDef>,
+  args: Vec<Value>,
+}
+
 impl Interpreter {
   pub fn eval_call_expr(
     &mut self,
-    callee: Expression,
-    args: Vec<Expression>,
+    callee: &Expression,
+    args: &[Expression],
   ) -> Result<Value, InterpreterError> {
     let name = match callee {
       Expression::Identifier(name, _) => name,
@@ -19,125 +31,184 @@ impl Interpreter {
       }
     };
 
-    // Check if we have a pending input result that can be used
-    // Only do this when we're actively processing input to avoid stealing inputs
-    if self.processing_input {
-      if let Some(input_value) = self.environment.take_input_result() {
-        // If this is the input function that was waiting, return the input directly
-        if name == "ler" {
-          return Ok(input_value);
-        }
-
-        // If it's not the input function but we got an input value,
-        // restore it for the actual input function to use
-        self.environment.set_input_result(input_value);
-      }
-    }
-
     // Case 1: Built-in library function
-    if let Some(func) = self.environment.get_lib(&name.clone()) {
+    if let Some(func) = self.environment.get_lib(name) {
       let location = self.location.clone();
 
       // Evaluate each argument before passing to the function
       let mut evaluated_args = Vec::new();
-      for arg in args.clone() {
-        let arg_result = self.eval_expr(&arg)?;
-
-        // For input functions used as arguments to other functions,
-        // we need to handle the pending input state
-        if let Value::InputPending(callback_id) = arg_result {
-          // Only set pending function call if not already processing input
-          if !self.processing_input {
-            // Save information about the current function call
-            self
-              .environment
-              .set_pending_function_call(name.clone(), args.clone());
-          }
-
-          // Signal that we need input
-          return Ok(Value::InputPending(callback_id));
-        }
-
-        evaluated_args.push(arg_result);
+      for arg in args {
+        evaluated_args.push(self.eval_expr(arg)?);
       }
 
       // Now call the function with evaluated arguments
+      self.touch_list_args(args, &evaluated_args);
+      self.check_output(name, &evaluated_args)?;
+      let lists = self.lists_to_check(&evaluated_args);
       let result = func(evaluated_args, location)?;
+
+      for list in &lists {
+        self.check_size(list)?;
+      }
+
+      // Input functions stop the evaluation until the host provides a value
+      let result = match result {
+        Value::InputPending(callback_id) => {
+          let input = self.suspend(callback_id)?;
+          self.note_input(&input);
+          input
+        }
+        result => result,
+      };
+
+      self.check_size(&result)?;
+
       return Ok(result);
     }
 
     // Case 2: User-defined function
-    if let Some((params, body)) = self.environment.get_function(&name.clone()) {
-      if args.len() != params.len() {
+    if let Some(function) = self.environment.get_function(name) {
+      if args.len() != function.params.len() {
         return Err(InterpreterError::ArgumentMismatchError(
           self.location.clone(),
-          name,
+          name.clone(),
         ));
       }
 
-      // Evaluate arguments
+      // Evaluate arguments, then point back to the call
+      let location = self.location.clone();
       let mut evaluated_args = Vec::new();
-      for arg in args.clone() {
-        let arg_result = self.eval_expr(&arg)?;
-
-        // Handle input pending in user-defined function calls
-        if let Value::InputPending(callback_id) = arg_result {
-          // Save information about the current function call
-          self
-            .environment
-            .set_pending_function_call(name.clone(), args.clone());
-
-          // Signal that we need input
-          return Ok(Value::InputPending(callback_id));
-        }
-
-        evaluated_args.push(arg_result);
+      for arg in args {
+        evaluated_args.push(self.eval_expr(arg)?);
       }
+      self.location = location;
+
+      return self.eval_user_function(name, function, evaluated_args);
+    }
 
-      // Store the current variable state
-      let current_vars = self.environment.symbols.read().clone();
+    Err(self.unknown_function_error(name))
+  }
 
-      // Set up argument bindings
-      for (param, arg_value) in params.iter().zip(evaluated_args) {
-        self.environment.define_variable(param.clone(), arg_value)?;
+  fn eval_user_function(
+    &mut self,
+    name: &str,
+    mut function: Arc<FunctionDef>,
+    mut args: Vec<Value>,
+  ) -> Result<Value, InterpreterError> {
+    self.check_cancelled()?;
+    self.check_call_depth(self.call_stack.len() + 1)?;
+
+    self.profile_call(name);
+
+    // Keep what the call changes, to undo it on return
+    self.call_stack.push(CallFrame {
+      name: name.to_string(),
+      call_site: self.location.clone(),
+    });
+    self.environment.symbols.write().enter_call();
+    let loops = std::mem::take(&mut self.loops);
+
+    let result = loop {
+      // Set up argument bindings and evaluate the function body
+      let result = function
+        .params
+        .iter()
+        .zip(args)
+        .try_for_each(|(param, arg_value)| {
+          self.environment.define_variable(param.clone(), arg_value)
+        })
+        .map_err(InterpreterError::from)
+        .and_then(|()| self.eval_function_block(&function.body));
+
+      // A tail call runs in this same frame, so that recursion with an
+      // accumulator doesn't grow the stack
+      let Some(call) = self.tail_call.take() else {
+        break result;
+      };
+
+      self.profile_return();
+      self.profile_call(&call.name);
+
+      // The frame shows the new function, reached from the original call site
+      if let Some(frame) = self.call_stack.last_mut() {
+        frame.name = call.name;
       }
 
-      // Evaluate function body
-      let result = self.eval_function_block(body);
+      function = call.function;
+      args = call.args;
+    };
 
-      // Restore variable state after function execution
-      *self.environment.symbols.write() = current_vars;
+    // Restore variable state after function execution
+    self.loops = loops;
+    if let Some(frame) = self.call_stack.pop() {
+      self.environment.symbols.write().exit_call();
+      // Errors after the call point to it, not to the last line of the function
+      self.location = frame.call_site;
+    }
+    self.profile_return();
 
-      return result;
+    result
+  }
+
+  /// The user function called by `retorne f(...)`, with its arguments, when
+  /// the call can replace the current one. Library functions are called as usual
+  pub(crate) fn tail_call_of(
+    &mut self,
+    callee: &Expression,
+    args: &[Expression],
+  ) -> Result<Option<TailCall>, InterpreterError> {
+    let Expression::Identifier(name, _) = callee else {
+      return Ok(None);
+    };
+
+    if self.loops > 0 || self.environment.get_lib(name).is_some() {
+      return Ok(None);
     }
 
-    Err(InterpreterError::EvalError(
+    let Some(function) = self.environment.get_function(name) else {
+      return Ok(None);
+    };
+
+    if args.len() != function.params.len() {
+      return Err(InterpreterError::ArgumentMismatchError(
+        self.location.clone(),
+        name.clone(),
+      ));
+    }
+
+    let mut evaluated_args = Vec::new();
+    for arg in args {
+      evaluated_args.push(self.eval_expr(arg)?);
+    }
+
+    Ok(Some(TailCall {
+      name: name.clone(),
+      function,
+      args: evaluated_args,
+    }))
+  }
+
+  pub fn unknown_function_error(&self, name: &str) -> InterpreterError {
+    InterpreterError::EvalError(
       self.location.clone(),
-      if !builtin::has(&name) {
-        format!("Função desconhecida: {}", name)
-      } else {
-        format!(
-          "Verifique se a biblioteca foi importada corretamente.\nEx.: importe \"matematica\""
-        )
+      match self.config.modules.module_of(name) {
+        Some(module) => format!(
+          "Verifique se a biblioteca foi importada corretamente.\nEx.: importe \"{}\"",
+          module.name
+        ),
+        None => format!("Função desconhecida: {}", name),
       },
-    ))
+    )
   }
 
-  fn eval_function_block(&mut self, block: Vec<Statement>) -> Result<Value, InterpreterError> {
+  fn eval_function_block(&mut self, block: &[Statement]) -> Result<Value, InterpreterError> {
     // Mark that we're entering a function scope
     self.environment.enter_function_scope();
 
     let mut result = Value::None;
 
     for stmt in block {
-      result = self.eval_stmt(&stmt)?;
-
-      // Handle input pending in function blocks
-      if let Value::InputPending(callback_id) = result {
-        // We're exiting the function with a pending input
-        self.environment.exit_function_scope();
-        return Ok(Value::InputPending(callback_id));
-      }
+      result = self.exec_stmt(stmt)?;
 
       if let Value::Return(value) = result {
         // We're exiting the function with a return value
@@ -7,16 +7,25 @@ use crate::{
 impl Interpreter {
   pub fn eval_comparison_expr(
     &mut self,
-    lhs: Expression,
-    op: Token,
-    rhs: Expression,
+    lhs: &Expression,
+    op: &Token,
+    rhs: &Expression,
   ) -> Result<Value, InterpreterError> {
     // Evaluate left-hand side expression
-    let lhs_value = self.eval_expr(&lhs)?;
+    let lhs_value = self.eval_expr(lhs)?;
 
     // Evaluate right-hand side expression
-    let rhs_value = self.eval_expr(&rhs)?;
+    let rhs_value = self.eval_expr(rhs)?;
 
+    self.apply_comparison(lhs_value, op, rhs_value)
+  }
+
+  pub fn apply_comparison(
+    &self,
+    lhs_value: Value,
+    op: &Token,
+    rhs_value: Value,
+  ) -> Result<Value, InterpreterError> {
     match (lhs_value, rhs_value, op) {
       (Value::Integer(l), Value::Integer(r), Token::Greater) => Ok(Value::Boolean(l > r)),
       (Value::Integer(l), Value::Integer(r), Token::GreaterEquals) => Ok(Value::Boolean(l >= r)),
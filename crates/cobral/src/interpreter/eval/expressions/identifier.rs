@@ -1,15 +1,17 @@
 use crate::interpreter::{error::InterpreterError, value::Value, Interpreter};
 
 impl Interpreter {
-  pub fn eval_identifier_expr(&self, identifier: String) -> Result<Value, InterpreterError> {
-    if let Some(symbol_lock) = self.environment.get_symbol(&identifier) {
-      let symbol = symbol_lock.write();
-      Ok(symbol.get_value().clone())
-    } else {
-      Err(InterpreterError::EvalError(
+  pub fn eval_identifier_expr(&self, identifier: &str) -> Result<Value, InterpreterError> {
+    self.load_symbol(identifier)
+  }
+
+  pub fn load_symbol(&self, identifier: &str) -> Result<Value, InterpreterError> {
+    match self.environment.symbols.read().get(identifier) {
+      Some(symbol) => Ok(symbol.get_value().clone()),
+      None => Err(InterpreterError::EvalError(
         self.location.clone(),
         format!("Símbolo '{}' não definido", identifier),
-      ))
+      )),
     }
   }
 }
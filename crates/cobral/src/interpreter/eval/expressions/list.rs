@@ -4,56 +4,63 @@ use crate::{
 };
 
 impl Interpreter {
-  pub fn eval_list_expr(&mut self, elements: Vec<Expression>) -> Result<Value, InterpreterError> {
-    let mut evaluated_elements = Vec::new();
+  pub fn eval_list_expr(&mut self, elements: &[Expression]) -> Result<Value, InterpreterError> {
+    let mut evaluated_elements = Vec::with_capacity(elements.len());
     for element in elements {
-      let value = self.eval_expr(&element)?;
+      let value = self.eval_expr(element)?;
       evaluated_elements.push(value);
     }
 
-    Ok(Value::List(evaluated_elements))
+    let list = Value::list(evaluated_elements);
+    self.check_size(&list)?;
+
+    Ok(list)
   }
 }
 
 impl Interpreter {
-  pub fn eval_index(&mut self, name: String, value: Expression) -> Result<Value, InterpreterError> {
-    let index = self.eval_expr(&value)?;
-
-    if let Some(symbol_lock) = self.environment.get_symbol(&name) {
-      let symbol = symbol_lock.write();
-      let data = symbol.get_value();
-
-      match data {
-        Value::List(list) => {
-          let index = match index {
-            Value::Integer(i) => i as usize,
-            _ => {
-              return Err(InterpreterError::ExpressionEvaluationFailure(
-                self.location.clone(),
-                "Índice deve ser um número inteiro".to_string(),
-              ))
-            }
-          };
-
-          if index >= list.len() {
+  pub fn eval_index(&mut self, name: &str, value: &Expression) -> Result<Value, InterpreterError> {
+    let index = self.eval_expr(value)?;
+
+    self.index_symbol(name, index)
+  }
+
+  pub fn index_symbol(&self, name: &str, index: Value) -> Result<Value, InterpreterError> {
+    let symbols = self.environment.symbols.read();
+
+    let data = match symbols.get(name) {
+      Some(symbol) => symbol.get_value(),
+      None => {
+        return Err(InterpreterError::ExpressionEvaluationFailure(
+          self.location.clone(),
+          format!("Símbolo '{}' não definido", name),
+        ))
+      }
+    };
+
+    match data {
+      Value::List(list) => {
+        let index = match index {
+          Value::Integer(i) => i as usize,
+          _ => {
             return Err(InterpreterError::ExpressionEvaluationFailure(
               self.location.clone(),
-              "Índice fora de alcance".to_string(),
-            ));
+              "Índice deve ser um número inteiro".to_string(),
+            ))
           }
+        };
 
-          Ok(list[index].clone())
-        }
-        _ => Err(InterpreterError::ExpressionEvaluationFailure(
-          self.location.clone(),
-          "A indexação é suportada somente em vetores".to_string(),
-        )),
+        list.get(index).ok_or_else(|| {
+          InterpreterError::ExpressionEvaluationFailure(
+            self.location.clone(),
+            "Índice fora de alcance".to_string(),
+          )
+        })
       }
-    } else {
-      Err(InterpreterError::ExpressionEvaluationFailure(
+      _ => Err(InterpreterError::ExpressionEvaluationFailure(
         self.location.clone(),
-        format!("Símbolo '{}' não definido", name),
-      ))
+        "A indexação é suportada somente em vetores".to_string(),
+      )),
     }
   }
 }
@@ -7,43 +7,30 @@ use crate::{
 impl Interpreter {
   pub fn eval_logical_expr(
     &mut self,
-    lhs: Expression,
-    op: Token,
-    rhs: Expression,
+    lhs: &Expression,
+    op: &Token,
+    rhs: &Expression,
   ) -> Result<Value, InterpreterError> {
     // Evaluate the left-hand side (LHS) expression
-    let lhs_value = self.eval_expr(&lhs)?;
+    let lhs_value = self.eval_expr(lhs)?;
 
-    // Ensure the LHS is a boolean
-    let lhs_bool = match lhs_value {
-      Value::Boolean(b) => b,
-      _ => {
-        return Err(InterpreterError::EvalError(
-          self.location.clone(),
-          format!(
-            "Operação lógica deve ser booleana, mas encontrou: {:?}",
-            lhs_value
-          ),
-        ));
-      }
-    };
+    // Ensure the LHS is a boolean before evaluating the RHS
+    self.expect_boolean(&lhs_value)?;
 
     // Evaluate the right-hand side (RHS) expression
-    let rhs_value = self.eval_expr(&rhs)?;
+    let rhs_value = self.eval_expr(rhs)?;
 
-    // Ensure the RHS is a boolean
-    let rhs_bool = match rhs_value {
-      Value::Boolean(b) => b,
-      _ => {
-        return Err(InterpreterError::EvalError(
-          self.location.clone(),
-          format!(
-            "Operação lógica deve ser booleana, mas encontrou: {:?}",
-            rhs_value
-          ),
-        ));
-      }
-    };
+    self.apply_logical(lhs_value, op, rhs_value)
+  }
+
+  pub fn apply_logical(
+    &self,
+    lhs_value: Value,
+    op: &Token,
+    rhs_value: Value,
+  ) -> Result<Value, InterpreterError> {
+    let lhs_bool = self.expect_boolean(&lhs_value)?;
+    let rhs_bool = self.expect_boolean(&rhs_value)?;
 
     // Apply the logical operation (AND `e` or OR `ou`)
     match op {
@@ -55,4 +42,17 @@ impl Interpreter {
       )),
     }
   }
+
+  pub(crate) fn expect_boolean(&self, value: &Value) -> Result<bool, InterpreterError> {
+    match value {
+      Value::Boolean(b) => Ok(*b),
+      _ => Err(InterpreterError::EvalError(
+        self.location.clone(),
+        format!(
+          "Operação lógica deve ser booleana, mas encontrou: {:?}",
+          value
+        ),
+      )),
+    }
+  }
 }
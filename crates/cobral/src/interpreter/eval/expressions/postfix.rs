@@ -4,87 +4,76 @@ use crate::{
 };
 
 impl Interpreter {
-  pub fn eval_postfix_decrement_expr(&self, expr: Expression) -> Result<Value, InterpreterError> {
-    if let Expression::Identifier(name, _) = expr {
-      // First get the original value while holding a read lock
-      let original_value = {
-        let symbol = self.environment.get_symbol(&name).ok_or_else(|| {
-          InterpreterError::EvalError(
-            self.location.clone(),
-            format!("Símbolo '{}' não definido", name),
-          )
-        })?;
-
-        let value = symbol.read().get_value().clone();
-
-        value
-      };
-
-      let new_value = match &original_value {
-        Value::Integer(n) => Value::Integer(n + 1),
-        Value::Float(n) => Value::Float(n + 1.0),
-        _ => {
-          return Err(InterpreterError::EvalError(
-            self.location.clone(),
-            "Operador de decremento deve ser aplicado a um valor numérico".to_string(),
-          ))
-        }
-      };
+  pub fn eval_postfix_decrement_expr(&self, expr: &Expression) -> Result<Value, InterpreterError> {
+    let (original_value, _) = self.step_expr(expr, -1)?;
+    Ok(original_value)
+  }
 
-      // Set the new value using our new method
-      self
-        .environment
-        .set_symbol_value(&name, new_value)
-        .map_err(|e| InterpreterError::EvalError(self.location.clone(), e.to_string()))?;
+  pub fn eval_postfix_increment_expr(&self, expr: &Expression) -> Result<Value, InterpreterError> {
+    let (original_value, _) = self.step_expr(expr, 1)?;
+    Ok(original_value)
+  }
 
-      Ok(original_value)
+  pub(super) fn step_expr(
+    &self,
+    expr: &Expression,
+    delta: i64,
+  ) -> Result<(Value, Value), InterpreterError> {
+    if let Expression::Identifier(name, _) = expr {
+      self.step_symbol(name, delta)
     } else {
       Err(InterpreterError::EvalError(
         self.location.clone(),
-        "Operador de decremento deve ser aplicado a um símbolo".to_string(),
+        format!(
+          "Operador de {} deve ser aplicado a um símbolo",
+          step_name(delta)
+        ),
       ))
     }
   }
 
-  pub fn eval_postfix_increment_expr(&self, expr: Expression) -> Result<Value, InterpreterError> {
-    if let Expression::Identifier(name, _) = expr {
-      // First get the original value while holding a read lock
-      let original_value = {
-        let symbol = self.environment.get_symbol(&name).ok_or_else(|| {
-          InterpreterError::EvalError(
-            self.location.clone(),
-            format!("Símbolo '{}' não definido", name),
-          )
-        })?;
-
-        let value = symbol.read().get_value().clone();
+  /// Adds `delta` to a numeric variable, returning its original and new values
+  pub fn step_symbol(&self, name: &str, delta: i64) -> Result<(Value, Value), InterpreterError> {
+    let original_value = self
+      .environment
+      .symbols
+      .read()
+      .get(name)
+      .map(|symbol| symbol.get_value().clone())
+      .ok_or_else(|| {
+        InterpreterError::EvalError(
+          self.location.clone(),
+          format!("Símbolo '{}' não definido", name),
+        )
+      })?;
 
-        value
-      };
+    let new_value = match &original_value {
+      Value::Integer(n) => Value::Integer(n + delta),
+      Value::Float(n) => Value::Float(n + delta as f64),
+      _ => {
+        return Err(InterpreterError::EvalError(
+          self.location.clone(),
+          format!(
+            "Operador de {} deve ser aplicado a um valor numérico",
+            step_name(delta)
+          ),
+        ))
+      }
+    };
 
-      let new_value = match &original_value {
-        Value::Integer(n) => Value::Integer(n + 1),
-        Value::Float(n) => Value::Float(n + 1.0),
-        _ => {
-          return Err(InterpreterError::EvalError(
-            self.location.clone(),
-            "Operador de incremento deve ser aplicado a um valor numérico".to_string(),
-          ))
-        }
-      };
+    self
+      .environment
+      .set_symbol_value(name, new_value.clone())
+      .map_err(|e| InterpreterError::EvalError(self.location.clone(), e.to_string()))?;
 
-      // Set the new value using our new method
-      self
-        .environment
-        .set_symbol_value(&name, new_value)
-        .map_err(|e| InterpreterError::EvalError(self.location.clone(), e.to_string()))?;
+    Ok((original_value, new_value))
+  }
+}
 
-      Ok(original_value)
-    } else {
-      Err(InterpreterError::EvalError(
-        self.location.clone(),
-        "Operador de incremento deve ser aplicado a um símbolo".to_string(),
-      ))
-    }
+fn step_name(delta: i64) -> &'static str {
+  if delta < 0 {
+    "decremento"
+  } else {
+    "incremento"
   }
 }
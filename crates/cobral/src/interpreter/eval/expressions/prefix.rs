@@ -4,87 +4,13 @@ use crate::{
 };
 
 impl Interpreter {
-  pub fn eval_prefix_decrement_expr(&self, expr: Expression) -> Result<Value, InterpreterError> {
-    if let Expression::Identifier(name, _) = expr {
-      // First get the original value while holding a read lock
-      let original_value = {
-        let symbol = self.environment.get_symbol(&name).ok_or_else(|| {
-          InterpreterError::EvalError(
-            self.location.clone(),
-            format!("Símbolo '{}' não definido", name),
-          )
-        })?;
-
-        let value = symbol.read().get_value().clone();
-
-        value
-      };
-
-      let new_value = match &original_value {
-        Value::Integer(n) => Value::Integer(n + 1),
-        Value::Float(n) => Value::Float(n + 1.0),
-        _ => {
-          return Err(InterpreterError::EvalError(
-            self.location.clone(),
-            "Operador de decremento deve ser aplicado a um valor numérico".to_string(),
-          ))
-        }
-      };
-
-      // Set the new value using our new method
-      self
-        .environment
-        .set_symbol_value(&name, new_value.clone())
-        .map_err(|e| InterpreterError::EvalError(self.location.clone(), e.to_string()))?;
-
-      Ok(new_value)
-    } else {
-      Err(InterpreterError::EvalError(
-        self.location.clone(),
-        "Operador de decremento deve ser aplicado a um símbolo".to_string(),
-      ))
-    }
+  pub fn eval_prefix_decrement_expr(&self, expr: &Expression) -> Result<Value, InterpreterError> {
+    let (_, new_value) = self.step_expr(expr, -1)?;
+    Ok(new_value)
   }
 
-  pub fn eval_prefix_increment_expr(&self, expr: Expression) -> Result<Value, InterpreterError> {
-    if let Expression::Identifier(name, _) = expr {
-      // First get the original value while holding a read lock
-      let original_value = {
-        let symbol = self.environment.get_symbol(&name).ok_or_else(|| {
-          InterpreterError::EvalError(
-            self.location.clone(),
-            format!("Símbolo '{}' não definido", name),
-          )
-        })?;
-
-        let value = symbol.read().get_value().clone();
-
-        value
-      };
-
-      let new_value = match &original_value {
-        Value::Integer(n) => Value::Integer(n + 1),
-        Value::Float(n) => Value::Float(n + 1.0),
-        _ => {
-          return Err(InterpreterError::EvalError(
-            self.location.clone(),
-            "Operador de incremento deve ser aplicado a um valor numérico".to_string(),
-          ))
-        }
-      };
-
-      // Set the new value using our new method
-      self
-        .environment
-        .set_symbol_value(&name, new_value.clone())
-        .map_err(|e| InterpreterError::EvalError(self.location.clone(), e.to_string()))?;
-
-      Ok(new_value)
-    } else {
-      Err(InterpreterError::EvalError(
-        self.location.clone(),
-        "Operador de incremento deve ser aplicado a um símbolo".to_string(),
-      ))
-    }
+  pub fn eval_prefix_increment_expr(&self, expr: &Expression) -> Result<Value, InterpreterError> {
+    let (_, new_value) = self.step_expr(expr, 1)?;
+    Ok(new_value)
   }
 }
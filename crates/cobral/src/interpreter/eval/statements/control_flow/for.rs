@@ -1,50 +1,55 @@
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::collections::BTreeMap;
 
-use crate::event::GLOBAL_EVENT_SYSTEM;
+use crate::interpreter::environment::Symbol;
 use crate::interpreter::error::InterpreterError;
 use crate::interpreter::value::Value;
 use crate::interpreter::Interpreter;
-use crate::shared::ast::{Expression, Statement};
+use crate::shared::ast::{Expression, Location, Statement};
 
 impl Interpreter {
   pub fn eval_for_stmt(
     &mut self,
-    initializer: Statement,
-    condition: Expression,
-    update: Statement,
-    body: Vec<Statement>,
+    initializer: &Statement,
+    condition: &Expression,
+    update: &Statement,
+    body: &[Statement],
   ) -> Result<Value, InterpreterError> {
     let state = self.environment.symbols.read().clone();
+    let loop_variables = match initializer {
+      Statement::Variable { name, .. } => vec![name.clone()],
+      _ => Vec::new(),
+    };
 
     // Initialize
-    self.eval_stmt(&initializer)?;
-
-    // Use a local AtomicBool for break detection
-    let should_break = Arc::new(AtomicBool::new(false));
-    let should_break_clone = should_break.clone();
-
-    let id = GLOBAL_EVENT_SYSTEM.listen(
-      "break_exec",
-      Box::new(move |_| {
-        should_break_clone.store(true, Ordering::SeqCst);
-      }),
-    );
-
-    // Main loop with optimized condition checking
-    while !should_break.load(Ordering::SeqCst) {
-      match self.eval_expr(&condition)? {
-        Value::Boolean(false) => break,
+    self.eval_stmt(initializer)?;
+
+    let result = self.eval_for_loop(condition, update, body);
+    self.restore_loop_scope(&loop_variables, state);
+
+    result
+  }
+
+  fn eval_for_loop(
+    &mut self,
+    condition: &Expression,
+    update: &Statement,
+    body: &[Statement],
+  ) -> Result<Value, InterpreterError> {
+    loop {
+      match self.eval_expr(condition)? {
+        Value::Boolean(false) => return Ok(Value::None),
         Value::Boolean(true) => {
-          // Execute the body first
-          self.eval_block(&body)?;
+          // Execute the body first. A `retorne` inside the loop leaves the
+          // enclosing function
+          if let Value::Return(value) = self.eval_block(body)? {
+            return Ok(Value::Return(value));
+          }
 
           // Handle the update statement
-          self.eval_stmt(&update)?;
+          self.eval_stmt(update)?;
+          self.tick()?;
         }
         _ => {
-          GLOBAL_EVENT_SYSTEM.unlisten(id);
-          *self.environment.symbols.write() = state;
           return Err(InterpreterError::EvalError(
             self.location.clone(),
             "Condição de laço inválida".into(),
@@ -52,11 +57,94 @@ impl Interpreter {
         }
       }
     }
+  }
+
+  pub fn eval_for_each_stmt(
+    &mut self,
+    names: &[String],
+    iterable: &Expression,
+    body: &[Statement],
+    location: &Location,
+  ) -> Result<Value, InterpreterError> {
+    let iterable = self.eval_expr(iterable)?;
+    let items = self.iteration_items(iterable, location)?;
+
+    let state = self.environment.symbols.read().clone();
+    let mut result = Ok(Value::None);
+
+    for item in items {
+      if let Err(e) = self.bind_loop_item(names, item, location) {
+        result = Err(e);
+        break;
+      }
+
+      match self.eval_block(body) {
+        // A `retorne` inside the loop leaves the enclosing function
+        Ok(Value::Return(value)) => {
+          result = Ok(Value::Return(value));
+          break;
+        }
+        Ok(_) => {}
+        Err(e) => {
+          result = Err(e);
+          break;
+        }
+      }
+
+      if let Err(e) = self.tick() {
+        result = Err(e);
+        break;
+      }
+    }
+
+    self.restore_loop_scope(names, state);
+
+    result
+  }
+
+  /// Elements visited by `para cada`: the items of a list or the characters of a text
+  pub fn iteration_items(
+    &self,
+    iterable: Value,
+    location: &Location,
+  ) -> Result<Vec<Value>, InterpreterError> {
+    match iterable {
+      Value::List(items) => Ok(items.items()),
+      Value::String(s) => Ok(s.chars().map(|c| Value::String(c.to_string())).collect()),
+      _ => Err(InterpreterError::EvalError(
+        location.clone(),
+        "Somente vetores e textos podem ser percorridos com 'para cada'".into(),
+      )),
+    }
+  }
+
+  pub fn bind_loop_item(
+    &self,
+    names: &[String],
+    item: Value,
+    location: &Location,
+  ) -> Result<(), InterpreterError> {
+    match names {
+      [name] => Ok(self.environment.define_variable(name.clone(), item)?),
+      _ => self.bind_values(names, item, true, location),
+    }
+  }
 
-    // Cleanup
-    GLOBAL_EVENT_SYSTEM.unlisten(id);
-    *self.environment.symbols.write() = state;
+  /// Drops the symbols declared inside the loop while keeping the writes made
+  /// to variables that already existed before it
+  pub(crate) fn restore_loop_scope(
+    &self,
+    loop_variables: &[String],
+    state: BTreeMap<String, Symbol>,
+  ) {
+    let mut symbols = self.environment.symbols.write();
+    symbols.retain(|name, _| state.contains_key(name));
 
-    Ok(Value::None)
+    // The loop variables may have shadowed outer ones
+    for name in loop_variables {
+      if let Some(symbol) = state.get(name) {
+        symbols.insert(name.clone(), symbol.clone());
+      }
+    }
   }
 }
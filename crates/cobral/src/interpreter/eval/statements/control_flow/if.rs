@@ -1,17 +1,18 @@
 use crate::{
   interpreter::{error::InterpreterError, value::Value, Interpreter},
-  shared::ast::{Expression, Statement},
+  shared::ast::{Expression, Location, Statement},
 };
 
 impl Interpreter {
   pub fn eval_if_stmt(
     &mut self,
-    condition: Option<Expression>,
-    true_block: Vec<Statement>,
-    else_if_block: Vec<(Box<Option<Expression>>, Vec<Statement>)>,
-    else_block: Option<Vec<Statement>>,
+    condition: Option<&Expression>,
+    true_block: &[Statement],
+    else_if_block: &[(Box<Option<Expression>>, Vec<Statement>)],
+    else_block: Option<&[Statement]>,
+    location: &Location,
   ) -> Result<Value, InterpreterError> {
-    let condition = self.eval_expr(&condition.expect("Condição não encontrada"))?;
+    let condition = self.eval_expr(condition.expect("Condição não encontrada"))?;
 
     // Ensure the condition result is a boolean
     let condition = match condition {
@@ -25,8 +26,10 @@ impl Interpreter {
     };
 
     if condition {
+      self.cover_branch(location, 0);
+
       // Evaluate the true block if the condition is true
-      let result = self.eval_block(&true_block)?;
+      let result = self.eval_block(true_block)?;
 
       // Check if we got a return value and propagate it
       if let Value::Return(_) = result {
@@ -36,8 +39,13 @@ impl Interpreter {
       Ok(result)
     } else {
       // Check each 'else if' block
-      for (else_if_condition, else_if_block) in else_if_block {
-        let else_if_condition = self.eval_expr(&else_if_condition.unwrap())?;
+      for (index, (else_if_condition, else_if_block)) in else_if_block.iter().enumerate() {
+        let else_if_condition = self.eval_expr(
+          else_if_condition
+            .as_ref()
+            .as_ref()
+            .expect("Condição não encontrada"),
+        )?;
 
         let else_if_condition = match else_if_condition {
           Value::Boolean(b) => b,
@@ -50,7 +58,8 @@ impl Interpreter {
         };
 
         if else_if_condition {
-          let result = self.eval_block(&else_if_block)?;
+          self.cover_branch(location, index + 1);
+          let result = self.eval_block(else_if_block)?;
 
           // Check if we got a return value and propagate it
           if let Value::Return(_) = result {
@@ -62,8 +71,9 @@ impl Interpreter {
       }
 
       // If none of the 'else if' conditions are true, evaluate the 'else' block
+      self.cover_branch(location, else_if_block.len() + 1);
       if let Some(else_block) = else_block {
-        let result = self.eval_block(&else_block)?;
+        let result = self.eval_block(else_block)?;
 
         // Check if we got a return value and propagate it
         if let Value::Return(_) = result {
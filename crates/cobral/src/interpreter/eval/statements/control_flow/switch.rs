@@ -1,23 +1,24 @@
 use crate::{
   interpreter::{error::InterpreterError, value::Value, Interpreter},
-  shared::ast::{Expression, Statement},
+  shared::ast::{Expression, Location, Statement},
 };
 
 impl Interpreter {
   pub fn eval_switch_stmt(
     &mut self,
-    switch_expr: Expression,
-    cases: Vec<(Box<Expression>, Vec<Statement>, bool)>, // Added bool for break
-    default_case: Option<(Vec<Statement>, bool)>,        // Added bool for break
+    switch_expr: &Expression,
+    cases: &[(Box<Expression>, Vec<Statement>, bool)], // Added bool for break
+    default_case: Option<&(Vec<Statement>, bool)>,     // Added bool for break
+    location: &Location,
   ) -> Result<Value, InterpreterError> {
-    let switch_value = self.eval_expr(&switch_expr)?;
+    let switch_value = self.eval_expr(switch_expr)?;
 
     let mut found_match = false;
     let mut result = Value::None;
 
     // Evaluate cases
-    for (case_value, case_statements, has_break) in cases {
-      let case_result = self.eval_expr(&*case_value)?;
+    for (index, (case_value, case_statements, has_break)) in cases.iter().enumerate() {
+      let case_result = self.eval_expr(case_value)?;
 
       // If we found a match previously and there was no break, continue executing
       let should_execute = found_match
@@ -35,10 +36,12 @@ impl Interpreter {
         };
 
       if should_execute {
+        self.cover_branch(location, index);
         found_match = true;
-        result = self.eval_block(&case_statements)?;
+        result = self.eval_block(case_statements)?;
 
-        if has_break {
+        // A `retorne` leaves the function without falling through
+        if *has_break || matches!(result, Value::Return(_)) {
           return Ok(result);
         }
       }
@@ -46,9 +49,13 @@ impl Interpreter {
 
     // If no case matched or no break was encountered, try default case
     if !found_match || !matches!(result, Value::None) {
+      if default_case.is_some() || !found_match {
+        self.cover_branch(location, cases.len());
+      }
+
       if let Some((default_statements, has_break)) = default_case {
-        result = self.eval_block(&default_statements)?;
-        if has_break {
+        result = self.eval_block(default_statements)?;
+        if *has_break {
           return Ok(result);
         }
       }
@@ -1,14 +1,19 @@
 use crate::{
-  interpreter::{environment::Symbol, error::InterpreterError, value::Value, Interpreter},
+  interpreter::{
+    environment::{EnvironmentError, Symbol},
+    error::InterpreterError,
+    value::Value,
+    Interpreter,
+  },
   shared::ast::Expression,
 };
 
 impl Interpreter {
   pub fn eval_assignment_stmt(
     &mut self,
-    name: Expression,
-    index: Option<Box<Expression>>,
-    value: Expression,
+    name: &Expression,
+    index: Option<&Expression>,
+    value: &Expression,
   ) -> Result<Value, InterpreterError> {
     let name = match name {
       Expression::Identifier(name, _) => name,
@@ -21,58 +26,100 @@ impl Interpreter {
     };
 
     // First evaluate the value to be assigned
-    let evaluated_value = self.eval_expr(&value)?;
+    let evaluated_value = self.eval_expr(value)?;
 
     // Check if it's a variable assignment
-    if self.environment.get_symbol(&name.clone()).is_some() {
-      if let Some(index) = index {
-        let index = self.eval_expr(&*index)?;
+    if self.environment.symbols.read().contains_key(name) {
+      let index = match index {
+        Some(index) => Some(self.eval_expr(index)?),
+        None => None,
+      };
 
-        let mut variables = self.environment.symbols.write();
+      return self.assign_symbol(name, index, evaluated_value);
+    }
 
-        if let Some(Symbol::Constant(_)) = variables.get(&name) {
-          return Err(InterpreterError::EvalError(
-            self.location.clone(),
-            format!("Não é possível atribuir um valor a uma constante: {}", name),
-          ));
-        }
+    // If we get here, the variable doesn't exist
+    Err(self.unknown_variable_error(name))
+  }
 
-        if let Some(Symbol::Variable(data)) = variables.get_mut(&name) {
-          match data {
-            Value::List(ref mut list) => {
-              let index = match index {
-                Value::Integer(i) => i as usize,
-                _ => {
-                  return Err(InterpreterError::ExpressionEvaluationFailure(
-                    self.location.clone(),
-                    "Índice deve ser um número inteiro".to_string(),
-                  ))
-                }
-              };
+  /// Assigns to an existing variable, or to one of its elements when `index` is given
+  pub fn assign_symbol(
+    &self,
+    name: &str,
+    index: Option<Value>,
+    evaluated_value: Value,
+  ) -> Result<Value, InterpreterError> {
+    if !self.environment.symbols.read().contains_key(name) {
+      return Err(self.unknown_variable_error(name));
+    }
+
+    if let Some(index) = index {
+      let variables = self.environment.symbols.read();
+
+      if let Some(Symbol::Constant(_)) = variables.get(name) {
+        return Err(InterpreterError::EvalError(
+          self.location.clone(),
+          format!("Não é possível atribuir um valor a uma constante: {}", name),
+        ));
+      }
 
-              list[index] = evaluated_value.clone();
+      if let Some(Symbol::Variable(data)) = variables.get(name) {
+        match data {
+          Value::List(list) => {
+            let index = match index {
+              Value::Integer(i) => i as usize,
+              _ => {
+                return Err(InterpreterError::ExpressionEvaluationFailure(
+                  self.location.clone(),
+                  "Índice deve ser um número inteiro".to_string(),
+                ))
+              }
+            };
+
+            if let Value::List(value) = &evaluated_value {
+              if value.contains(list) {
+                return Err(InterpreterError::ExpressionEvaluationFailure(
+                  self.location.clone(),
+                  "Um vetor não pode conter a si mesmo".to_string(),
+                ));
+              }
             }
-            _ => {
+
+            let mut items = list.write();
+            if index >= items.len() {
               return Err(InterpreterError::ExpressionEvaluationFailure(
                 self.location.clone(),
-                "A indexação é suportada somente em vetores".to_string(),
-              ))
+                "Índice fora de alcance".to_string(),
+              ));
             }
+
+            items[index] = evaluated_value.clone();
+          }
+          _ => {
+            return Err(InterpreterError::ExpressionEvaluationFailure(
+              self.location.clone(),
+              "A indexação é suportada somente em vetores".to_string(),
+            ))
           }
         }
-      } else {
-        self
-          .environment
-          .define_variable(name.clone(), evaluated_value.clone())?;
       }
-
-      return Ok(evaluated_value);
+    } else {
+      match self.environment.symbols.write().get_mut(name) {
+        Some(Symbol::Variable(data)) => *data = evaluated_value.clone(),
+        _ => return Err(EnvironmentError::SymbolAlreadyDefined.into()),
+      }
     }
 
-    // If we get here, the variable doesn't exist
-    Err(InterpreterError::EvalError(
+    // An element assigned changes the list without a write to the variable
+    self.touch(name);
+
+    Ok(evaluated_value)
+  }
+
+  fn unknown_variable_error(&self, name: &str) -> InterpreterError {
+    InterpreterError::EvalError(
       self.location.clone(),
-      format!("Variável desconhecida: {}", name), // Changed from 'value' to 'name'
-    ))
+      format!("Variável desconhecida: {}", name),
+    )
   }
 }
@@ -6,12 +6,14 @@ use crate::{
 impl Interpreter {
   pub fn eval_constant_stmt(
     &mut self,
-    name: String,
-    value: Expression,
+    name: &str,
+    value: &Expression,
   ) -> Result<Value, InterpreterError> {
-    let value = self.eval_expr(&value)?;
+    let value = self.eval_expr(value)?;
 
-    self.environment.define_constant(name, value.clone())?;
+    self
+      .environment
+      .define_constant(name.to_string(), value.clone())?;
 
     Ok(value)
   }
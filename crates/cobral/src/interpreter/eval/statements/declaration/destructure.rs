@@ -0,0 +1,80 @@
+use crate::{
+  interpreter::{environment::Symbol, error::InterpreterError, value::Value, Interpreter},
+  shared::ast::{Expression, Location},
+};
+
+impl Interpreter {
+  pub fn eval_destructure_stmt(
+    &mut self,
+    names: &[String],
+    value: &Expression,
+    declare: bool,
+    location: &Location,
+  ) -> Result<Value, InterpreterError> {
+    // Every value is evaluated before assigning, so `a, b = b, a` swaps them
+    let evaluated_value = self.eval_expr(value)?;
+
+    self.bind_values(names, evaluated_value.clone(), declare, location)?;
+
+    Ok(evaluated_value)
+  }
+
+  /// Spreads a list over `names`, declaring them or assigning to existing variables
+  pub fn bind_values(
+    &self,
+    names: &[String],
+    value: Value,
+    declare: bool,
+    location: &Location,
+  ) -> Result<(), InterpreterError> {
+    let values = self.unpack(names.len(), value, location)?;
+
+    for (name, value) in names.iter().zip(values) {
+      if !declare {
+        match self.environment.symbols.read().get(name) {
+          Some(Symbol::Constant(_)) => {
+            return Err(InterpreterError::EvalError(
+              location.clone(),
+              format!("Não é possível atribuir um valor a uma constante: {}", name),
+            ))
+          }
+          Some(Symbol::Variable(_)) => {}
+          None => {
+            return Err(InterpreterError::EvalError(
+              location.clone(),
+              format!("Variável desconhecida: {}", name),
+            ))
+          }
+        }
+      }
+
+      self.environment.define_variable(name.clone(), value)?;
+    }
+
+    Ok(())
+  }
+
+  /// Splits a list into exactly `count` values
+  pub fn unpack(
+    &self,
+    count: usize,
+    value: Value,
+    location: &Location,
+  ) -> Result<Vec<Value>, InterpreterError> {
+    match value {
+      Value::List(values) if values.len() == count => Ok(values.items()),
+      Value::List(values) => Err(InterpreterError::EvalError(
+        location.clone(),
+        format!(
+          "Esperados {} valores, mas foram recebidos {}",
+          count,
+          values.len()
+        ),
+      )),
+      _ => Err(InterpreterError::EvalError(
+        location.clone(),
+        format!("Esperados {} valores, mas foi recebido apenas um", count),
+      )),
+    }
+  }
+}
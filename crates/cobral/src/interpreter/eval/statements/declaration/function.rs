@@ -1,20 +1,27 @@
+use std::sync::Arc;
+
 use crate::{
-  interpreter::{error::InterpreterError, value::Value, Interpreter},
+  interpreter::{environment::FunctionDef, error::InterpreterError, value::Value, Interpreter},
   shared::ast::Statement,
 };
 
 impl Interpreter {
   pub fn eval_function_stmt(
     &self,
-    name: String,
-    args: Vec<String>,
-    body: Vec<Statement>,
+    name: &str,
+    params: &[String],
+    body: &[Statement],
   ) -> Result<Value, InterpreterError> {
+    let function = FunctionDef {
+      params: params.to_vec(),
+      body: body.to_vec(),
+    };
+
     self
       .environment
       .functions
       .write()
-      .insert(name, (args, body));
+      .insert(name.to_string(), Arc::new(function));
 
     Ok(Value::None)
   }
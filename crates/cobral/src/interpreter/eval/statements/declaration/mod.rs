@@ -1,4 +1,5 @@
 pub mod assignment;
 pub mod constant;
+pub mod destructure;
 pub mod function;
 pub mod variable;
@@ -6,14 +6,14 @@ use crate::{
 impl Interpreter {
   pub fn eval_variable_stmt(
     &mut self,
-    name: String,
-    value: Expression,
+    name: &str,
+    value: &Expression,
   ) -> Result<Value, InterpreterError> {
-    let value = self.eval_expr(&value)?;
+    let value = self.eval_expr(value)?;
 
     self
       .environment
-      .define_variable(name.clone(), value.clone())?;
+      .define_variable(name.to_string(), value.clone())?;
 
     Ok(value)
   }
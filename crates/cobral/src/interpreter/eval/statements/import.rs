@@ -1,57 +1,76 @@
-use std::{fs, path::Path, sync::Arc};
+use std::{fs, path::Path};
 
 use crate::{
-  interpreter::{builtin, error::InterpreterError, value::Value, Interpreter, LibFn},
+  interpreter::{error::InterpreterError, value::Value, Interpreter},
   lexer::Lexer,
   parser::Parser,
+  shared::ast::Statement,
 };
 
 impl Interpreter {
-  pub fn eval_import_stmt(&mut self, file: String) -> Result<Value, InterpreterError> {
-    if let Some(lib) = builtin::load(&file) {
-      // Add functions from the library to the interpreter's library map
-      for (name, func) in lib {
-        self
-          .environment
-          .libs
-          .write()
-          .insert(name.to_string(), Arc::new(func) as LibFn);
+  pub fn eval_import_stmt(&mut self, file: &str) -> Result<Value, InterpreterError> {
+    if self.load_library(file) {
+      return Ok(Value::None);
+    }
+
+    let stmts = self.read_module(file)?;
+
+    for stmt in stmts {
+      self.tick()?;
+
+      match self.eval_stmt(&stmt) {
+        Ok(_) => {}
+        Err(e) => {
+          return Err(InterpreterError::FileReadError(
+            file.to_string(),
+            e.to_string(),
+          ))
+        }
       }
+    }
 
-      return Ok(Value::None);
+    Ok(Value::None)
+  }
+
+  /// Adds the functions of a registered module, returning whether it exists
+  pub fn load_library(&self, name: &str) -> bool {
+    let Some(module) = self.config.modules.get(name) else {
+      return false;
+    };
+
+    // Add functions from the module to the interpreter's library map
+    let mut libs = self.environment.libs.write();
+    for function in &module.functions {
+      libs.insert(
+        function.name.clone(),
+        function.lib_fn(self.config.host.clone(), self.config.size_limits()),
+      );
     }
 
-    let path = Path::new(&file);
+    true
+  }
+
+  /// Lexes and parses an imported file
+  pub fn read_module(&self, file: &str) -> Result<Vec<Statement>, InterpreterError> {
+    let path = Path::new(file);
 
     if !path.exists() {
-      return Err(InterpreterError::FileNotFound(file));
+      return Err(InterpreterError::FileNotFound(file.to_string()));
     }
 
     let code = fs::read_to_string(path)
-      .map_err(|e| InterpreterError::FileReadError(file.clone(), e.to_string()))?;
+      .map_err(|e| InterpreterError::FileReadError(file.to_string(), e.to_string()))?;
 
-    // Evaluate the imported file
-    let tokens = match Lexer::new(&code) {
-      Ok(tokens) => tokens,
-      Err(e) => {
-        return Err(InterpreterError::FileReadError(file.clone(), e.to_string()));
-      }
-    };
+    let mut tokens = Lexer::new(&code)
+      .map_err(|e| InterpreterError::FileReadError(file.to_string(), e.to_string()))?;
 
-    let stmts = match Parser::new(tokens) {
-      Ok(stmts) => stmts,
-      Err(e) => {
-        return Err(InterpreterError::FileReadError(file.clone(), e.to_string()));
-      }
-    };
-
-    for stmt in stmts {
-      match self.eval_stmt(&stmt) {
-        Ok(_) => {}
-        Err(e) => return Err(InterpreterError::FileReadError(file.clone(), e.to_string())),
-      }
+    // Statements of the file point to it, so coverage and the profiler tell
+    // them apart from the program's own lines
+    for token in &mut tokens {
+      token.location.file = file.to_string();
     }
 
-    Ok(Value::None)
+    Parser::with_modules(tokens, self.config.modules.clone())
+      .map_err(|e| InterpreterError::FileReadError(file.to_string(), e.to_string()))
   }
 }
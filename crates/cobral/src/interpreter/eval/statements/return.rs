@@ -6,19 +6,30 @@ use crate::{
 impl Interpreter {
   pub fn eval_return_stmt(
     &mut self,
-    value: Option<Box<Expression>>,
-    location: Location,
+    value: Option<&Expression>,
+    location: &Location,
   ) -> Result<Value, InterpreterError> {
     // Check if we are inside a function scope
     if !self.environment.is_in_function_scope() {
       return Err(InterpreterError::EvalError(
-        location,
+        location.clone(),
         "Comando 'retorne' só pode ser usado dentro de funções".to_string(),
       ));
     }
 
+    // The call is made by the function that is returning, after it leaves
+    if let Some(Expression::Call {
+      callee, arguments, ..
+    }) = value
+    {
+      if let Some(call) = self.tail_call_of(callee, arguments)? {
+        self.tail_call = Some(call);
+        return Ok(Value::Return(Box::new(Value::None)));
+      }
+    }
+
     let return_value = if let Some(value) = value {
-      self.eval_expr(&*value)?
+      self.eval_expr(value)?
     } else {
       // Default to 0 instead of None when no return value is provided
       Value::Integer(0)
@@ -0,0 +1,317 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use parking_lot::Mutex;
+use serde::Serialize;
+
+use crate::shared::ast::{Location, Statement};
+
+use super::{
+  config::InterpreterConfig,
+  debugger::MAIN_FRAME,
+  environment::Symbols,
+  error::InterpreterError,
+  value::Value,
+  vm::Backend,
+  watch::{Io, Watch},
+  Interpreter,
+};
+
+/// Something that happened while a statement ran
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum Event {
+  /// A variable was declared or took a new value
+  Set {
+    name: String,
+    value: Value,
+  },
+  /// Some elements of a list changed and its length didn't
+  SetItems {
+    name: String,
+    items: Vec<(usize, Value)>,
+  },
+  /// A variable went out of scope, e.g. when a function returned
+  Remove {
+    name: String,
+  },
+  Output(String),
+  Input(String),
+}
+
+/// A statement that ran and what changed until the next one started
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HistoryStep {
+  pub step: usize,
+  pub location: Location,
+
+  /// Function running the statement, `principal` for the top level
+  pub function: String,
+  pub events: Vec<Event>,
+}
+
+/// The program right before a statement ran
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProgramState {
+  pub step: usize,
+  pub location: Location,
+  pub function: String,
+
+  /// Variables visible to the statement
+  pub variables: BTreeMap<String, Value>,
+  pub output: Vec<String>,
+  pub input: Vec<String>,
+}
+
+impl ProgramState {
+  fn apply(&mut self, event: &Event) {
+    match event {
+      // Copied, so that later changes don't reach the recorded values
+      Event::Set { name, value } => {
+        self.variables.insert(name.clone(), value.deep_copy());
+      }
+      Event::SetItems { name, items } => {
+        if let Some(Value::List(list)) = self.variables.get(name) {
+          let mut list = list.write();
+          for (index, value) in items {
+            list[*index] = value.deep_copy();
+          }
+        }
+      }
+      Event::Remove { name } => {
+        self.variables.remove(name);
+      }
+      Event::Output(text) => self.output.push(text.clone()),
+      Event::Input(text) => self.input.push(text.clone()),
+    }
+  }
+}
+
+/// Every statement a run executed, with only what changed at each one. Any
+/// earlier state is rebuilt by replaying the changes from the start
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct History {
+  pub steps: Vec<HistoryStep>,
+}
+
+impl History {
+  pub fn len(&self) -> usize {
+    self.steps.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.steps.is_empty()
+  }
+
+  /// The program right before step `step` ran. Steps start at 1
+  pub fn state_at(&self, step: usize) -> Option<ProgramState> {
+    let current = self.steps.get(step.checked_sub(1)?)?;
+
+    let mut state = ProgramState {
+      step,
+      location: current.location.clone(),
+      function: current.function.clone(),
+      variables: BTreeMap::new(),
+      output: Vec::new(),
+      input: Vec::new(),
+    };
+
+    for event in self.steps[..step - 1].iter().flat_map(|past| &past.events) {
+      state.apply(event);
+    }
+
+    Some(state)
+  }
+
+  /// Each value `name` took, with the step that gave it, e.g. to answer
+  /// "what was x two iterations ago?"
+  pub fn values_of(&self, name: &str) -> Vec<(usize, Value)> {
+    let mut state = ProgramState {
+      step: 0,
+      location: Location::default(),
+      function: String::new(),
+      variables: BTreeMap::new(),
+      output: Vec::new(),
+      input: Vec::new(),
+    };
+    let mut values = Vec::new();
+
+    for step in &self.steps {
+      for event in &step.events {
+        state.apply(event);
+
+        match event {
+          Event::Set { name: changed, .. } | Event::SetItems { name: changed, .. }
+            if changed == name =>
+          {
+            values.push((step.step, state.variables[name].deep_copy()));
+          }
+          _ => {}
+        }
+      }
+    }
+
+    values
+  }
+
+  /// Steps that ran a statement of `line`
+  pub fn steps_at_line(&self, line: usize) -> Vec<usize> {
+    self
+      .steps
+      .iter()
+      .filter(|step| step.location.line == line)
+      .map(|step| step.step)
+      .collect()
+  }
+}
+
+/// Records the history while the evaluator runs. Changes are gathered at each
+/// statement boundary, like the desk-check tracer does
+pub(crate) struct Recorder {
+  history: History,
+  pub(crate) watch: Watch,
+}
+
+impl Recorder {
+  pub(crate) fn new(config: &mut InterpreterConfig) -> Self {
+    Recorder {
+      history: History::default(),
+      watch: Watch::new(config),
+    }
+  }
+
+  /// Adds what changed since the last boundary to the latest step
+  fn flush(&mut self, symbols: &Symbols) {
+    let (writes, io) = self.watch.flush(symbols);
+
+    let mut events: Vec<Event> = io
+      .into_iter()
+      .map(|io| match io {
+        Io::Output(text) => Event::Output(text),
+        Io::Input(text) => Event::Input(text),
+      })
+      .collect();
+
+    events.extend(
+      writes
+        .into_iter()
+        .map(|write| match (write.old, write.new) {
+          (_, None) => Event::Remove { name: write.name },
+          (Some(Value::List(old)), Some(new)) => list_change(&write.name, &old.read(), new),
+          (_, Some(value)) => Event::Set {
+            name: write.name,
+            value,
+          },
+        }),
+    );
+
+    if let Some(step) = self.history.steps.last_mut() {
+      step.events.extend(events);
+    }
+  }
+}
+
+/// Only the elements that changed, unless the list grew or shrank. `new` is
+/// already a copy
+fn list_change(name: &str, old: &[Value], new: Value) -> Event {
+  match new {
+    Value::List(items) if items.len() == old.len() => Event::SetItems {
+      name: name.to_string(),
+      items: items
+        .items()
+        .into_iter()
+        .enumerate()
+        .filter(|(index, item)| old[*index] != *item)
+        .collect(),
+    },
+    value => Event::Set {
+      name: name.to_string(),
+      value,
+    },
+  }
+}
+
+impl Interpreter {
+  /// Creates an interpreter that records the history of the run. Recording
+  /// uses the tree-walking backend, which visits every statement
+  pub fn with_history(
+    stmts: Vec<Statement>,
+    config: InterpreterConfig,
+  ) -> Result<Self, InterpreterError> {
+    let mut config = InterpreterConfig {
+      backend: Backend::TreeWalker,
+      ..config
+    };
+    let recorder = Recorder::new(&mut config);
+
+    let mut interpreter = Interpreter {
+      history: Some(Arc::new(Mutex::new(recorder))),
+      ..Interpreter::build(stmts, config)
+    };
+    interpreter.environment.symbols.write().watch();
+
+    interpreter.run()?;
+
+    Ok(interpreter)
+  }
+
+  /// The history recorded so far, if recording is on
+  pub fn history(&self) -> Option<History> {
+    self
+      .history
+      .as_ref()
+      .map(|recorder| recorder.lock().history.clone())
+  }
+
+  /// The program right before step `step` ran, without copying the history
+  pub fn state_at(&self, step: usize) -> Option<ProgramState> {
+    self.history.as_ref()?.lock().history.state_at(step)
+  }
+
+  /// Steps recorded so far. While paused, the last one is the statement the
+  /// program is stopped at
+  pub fn history_len(&self) -> usize {
+    self
+      .history
+      .as_ref()
+      .map_or(0, |recorder| recorder.lock().history.len())
+  }
+
+  pub(crate) fn recorded_line(&self, step: usize) -> Option<usize> {
+    let recorder = self.history.as_ref()?.lock();
+    let step = recorder.history.steps.get(step.checked_sub(1)?)?;
+
+    Some(step.location.line)
+  }
+
+  pub(crate) fn record_step(&mut self, stmt: &Statement) {
+    let Some(recorder) = &self.history else {
+      return;
+    };
+
+    self.collect_writes();
+    let mut recorder = recorder.lock();
+    recorder.flush(&self.environment.symbols.read());
+
+    let step = recorder.history.len() + 1;
+    let function = self
+      .call_stack
+      .last()
+      .map_or(MAIN_FRAME, |frame| frame.name.as_str());
+
+    recorder.history.steps.push(HistoryStep {
+      step,
+      location: stmt.location(),
+      function: function.to_string(),
+      events: Vec::new(),
+    });
+  }
+
+  /// Records the changes of the last statement when the program ends
+  pub(crate) fn record_finish(&mut self) {
+    let Some(recorder) = &self.history else {
+      return;
+    };
+
+    self.collect_writes();
+    recorder.lock().flush(&self.environment.symbols.read());
+  }
+}
@@ -0,0 +1,342 @@
+use std::{
+  fmt,
+  io::{self, Write},
+  ops::Deref,
+  sync::Arc,
+  time::{Duration, SystemTime},
+};
+
+use parking_lot::Mutex;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  event::EventSystem,
+  logger::{
+    batcher::{LogBatchConfig, LogBatchManager},
+    Payload,
+  },
+};
+
+/// Where a program's output goes and where its input comes from. Embedders
+/// implement it to route `escrever`, `erro` and `ler` to their own UI
+pub trait Host: Send + Sync {
+  /// One line written by `escrever`, without the line break
+  fn write_stdout(&self, text: &str);
+
+  /// One line written by `erro`, without the line break
+  fn write_stderr(&self, text: &str);
+
+  /// Text written by `escreva`, which doesn't end the line. Line breaks come
+  /// inside `text`, and `write_stdout` ends the line it continues
+  fn write(&self, text: &str);
+
+  /// Called when `ler()` suspends the program. The answer comes back through
+  /// `Interpreter::provide_input`. An error ends the program, since no answer
+  /// would ever come
+  fn request_input(&self, prompt: &str) -> Result<(), String>;
+
+  fn now(&self) -> SystemTime {
+    SystemTime::now()
+  }
+
+  /// A number in `[0, 1)`
+  fn random(&self) -> f64 {
+    rand::random()
+  }
+
+  /// Sends buffered output. Called whenever the interpreter returns control
+  fn flush(&self) {}
+}
+
+/// The host of an interpreter, shared by every clone of its config. Defaults
+/// to the terminal
+#[derive(Clone)]
+pub struct HostHandle(Arc<dyn Host>);
+
+impl HostHandle {
+  pub fn new(host: impl Host + 'static) -> Self {
+    HostHandle(Arc::new(host))
+  }
+}
+
+impl<H: Host + 'static> From<Arc<H>> for HostHandle {
+  fn from(host: Arc<H>) -> Self {
+    HostHandle(host)
+  }
+}
+
+impl Default for HostHandle {
+  fn default() -> Self {
+    HostHandle::new(TerminalHost)
+  }
+}
+
+impl Deref for HostHandle {
+  type Target = dyn Host;
+
+  fn deref(&self) -> &Self::Target {
+    &*self.0
+  }
+}
+
+impl fmt::Debug for HostHandle {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("HostHandle").finish_non_exhaustive()
+  }
+}
+
+/// Prints to stdout and stderr, showing the `ler()` prompt on the same line
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TerminalHost;
+
+impl Host for TerminalHost {
+  fn write_stdout(&self, text: &str) {
+    println!("{}", text);
+  }
+
+  fn write_stderr(&self, text: &str) {
+    eprintln!("{}", text);
+  }
+
+  fn write(&self, text: &str) {
+    print!("{}", text);
+    let _ = io::stdout().flush();
+  }
+
+  fn request_input(&self, prompt: &str) -> Result<(), String> {
+    if !prompt.is_empty() {
+      print!("{} ", prompt);
+    }
+
+    let _ = io::stdout().flush();
+    Ok(())
+  }
+}
+
+/// Keeps everything in memory, e.g. to check the output of a program in tests
+#[derive(Debug, Default)]
+pub struct MemoryHost {
+  stdout: Mutex<Vec<String>>,
+  line: LineBuffer,
+  stderr: Mutex<Vec<String>>,
+  prompts: Mutex<Vec<String>>,
+}
+
+impl MemoryHost {
+  /// The lines written so far, including one `escreva` left unfinished
+  pub fn stdout(&self) -> Vec<String> {
+    let mut stdout = self.stdout.lock().clone();
+    stdout.extend(self.line.pending());
+    stdout
+  }
+
+  pub fn stderr(&self) -> Vec<String> {
+    self.stderr.lock().clone()
+  }
+
+  pub fn prompts(&self) -> Vec<String> {
+    self.prompts.lock().clone()
+  }
+}
+
+impl Host for MemoryHost {
+  fn write_stdout(&self, text: &str) {
+    self.stdout.lock().push(self.line.end(text));
+  }
+
+  fn write_stderr(&self, text: &str) {
+    self.stderr.lock().push(text.to_string());
+  }
+
+  fn write(&self, text: &str) {
+    self.stdout.lock().extend(self.line.push(text));
+  }
+
+  fn request_input(&self, prompt: &str) -> Result<(), String> {
+    self.prompts.lock().push(prompt.to_string());
+    Ok(())
+  }
+}
+
+/// Sends the output in batches as `process_logs` events, with `info` and
+/// `error` levels, and input requests as `spawn_input`
+#[derive(Clone)]
+pub struct EventHost {
+  batch: LogBatchManager,
+  line: LineBuffer,
+}
+
+impl EventHost {
+  pub fn new(events: EventSystem, config: LogBatchConfig) -> Self {
+    EventHost {
+      batch: LogBatchManager::new(config, events),
+      line: LineBuffer::default(),
+    }
+  }
+
+  fn info(&self, message: String) {
+    self.batch.add(Payload {
+      message,
+      level: String::from("info"),
+    });
+  }
+
+  /// Sends the output, with an unfinished `escreva` as a line of its own
+  fn send(&self) {
+    if let Some(rest) = self.line.take() {
+      self.info(rest);
+    }
+
+    self.batch.process_batch();
+  }
+}
+
+impl Host for EventHost {
+  fn write_stdout(&self, text: &str) {
+    self.info(self.line.end(text));
+  }
+
+  fn write_stderr(&self, text: &str) {
+    self.batch.add(Payload {
+      message: text.to_string(),
+      level: String::from("error"),
+    });
+  }
+
+  fn write(&self, text: &str) {
+    for line in self.line.push(text) {
+      self.info(line);
+    }
+  }
+
+  fn request_input(&self, prompt: &str) -> Result<(), String> {
+    self.send();
+    self.batch.events().emit("spawn_input", prompt.to_string());
+    Ok(())
+  }
+
+  fn flush(&self) {
+    self.send();
+  }
+}
+
+/// Makes a run reproducible: random numbers come from a generator seeded with
+/// a fixed value and the clock is virtual, starting at the Unix epoch and
+/// moving one millisecond forward each time it is read. Output and input go
+/// to the wrapped host
+pub struct DeterministicHost {
+  inner: HostHandle,
+  seed: u64,
+  rng: Mutex<StdRng>,
+  state: Mutex<DeterministicState>,
+}
+
+/// How far a `DeterministicHost` has gone, to continue from there after a
+/// snapshot is restored
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct DeterministicState {
+  /// Random numbers drawn so far
+  pub draws: u64,
+  pub clock: Duration,
+}
+
+impl DeterministicHost {
+  pub fn new(inner: HostHandle, seed: u64) -> Self {
+    DeterministicHost {
+      inner,
+      seed,
+      rng: Mutex::new(StdRng::seed_from_u64(seed)),
+      state: Mutex::new(DeterministicState::default()),
+    }
+  }
+
+  pub fn state(&self) -> DeterministicState {
+    *self.state.lock()
+  }
+
+  /// Moves the generator and the clock to `state`. The generator can't be
+  /// saved, so it starts over from the seed and skips the numbers already drawn
+  pub fn set_state(&self, state: DeterministicState) {
+    let mut rng = StdRng::seed_from_u64(self.seed);
+    for _ in 0..state.draws {
+      rng.random::<f64>();
+    }
+
+    *self.rng.lock() = rng;
+    *self.state.lock() = state;
+  }
+}
+
+impl Host for DeterministicHost {
+  fn write_stdout(&self, text: &str) {
+    self.inner.write_stdout(text);
+  }
+
+  fn write_stderr(&self, text: &str) {
+    self.inner.write_stderr(text);
+  }
+
+  fn write(&self, text: &str) {
+    self.inner.write(text);
+  }
+
+  fn request_input(&self, prompt: &str) -> Result<(), String> {
+    self.inner.request_input(prompt)
+  }
+
+  fn now(&self) -> SystemTime {
+    let mut state = self.state.lock();
+    state.clock += Duration::from_millis(1);
+
+    SystemTime::UNIX_EPOCH + state.clock
+  }
+
+  fn random(&self) -> f64 {
+    self.state.lock().draws += 1;
+    self.rng.lock().random()
+  }
+
+  fn flush(&self) {
+    self.inner.flush();
+  }
+}
+
+/// Joins what `escreva` writes into whole lines, for hosts that show the
+/// output line by line. Clones share the same line
+#[derive(Debug, Clone, Default)]
+pub struct LineBuffer(Arc<Mutex<String>>);
+
+impl LineBuffer {
+  /// Adds `text` to the line and returns the lines it completed
+  pub fn push(&self, text: &str) -> Vec<String> {
+    let mut line = self.0.lock();
+    line.push_str(text);
+
+    let Some(end) = line.rfind('\n') else {
+      return Vec::new();
+    };
+
+    let rest = line.split_off(end + 1);
+    let done = std::mem::replace(&mut *line, rest);
+
+    done[..end].split('\n').map(str::to_string).collect()
+  }
+
+  /// The line that `text` ends, starting with what is still unfinished
+  pub fn end(&self, text: &str) -> String {
+    let mut line = std::mem::take(&mut *self.0.lock());
+    line.push_str(text);
+    line
+  }
+
+  /// The unfinished line, which the buffer forgets
+  pub fn take(&self) -> Option<String> {
+    Some(std::mem::take(&mut *self.0.lock())).filter(|line| !line.is_empty())
+  }
+
+  /// The unfinished line, which stays in the buffer
+  pub fn pending(&self) -> Option<String> {
+    Some(self.0.lock().clone()).filter(|line| !line.is_empty())
+  }
+}
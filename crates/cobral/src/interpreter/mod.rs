@@ -1,19 +1,40 @@
 pub mod builtin;
+pub mod config;
+mod coroutine;
+pub mod coverage;
+pub mod debugger;
 mod environment;
 pub mod error;
 mod eval;
+pub mod history;
+pub mod host;
+pub mod module;
+pub mod profiler;
+pub mod snapshot;
+pub mod trace;
 pub mod value;
+pub mod vm;
+mod watch;
 
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
+use parking_lot::Mutex;
+
+use config::{InterpreterConfig, Usage};
+use coroutine::{Coroutine, Suspension, Yield};
+use coverage::CoverageRecorder;
+use debugger::{CallFrame, Debugger, Stopped};
 use environment::Environment;
 use error::InterpreterError;
+use eval::expressions::call::TailCall;
+use history::Recorder;
+use host::DeterministicHost;
+use profiler::Profiler;
+use trace::Tracer;
 use value::Value;
+use vm::{bytecode::Function, Backend, Exit, Machine};
 
-use crate::{
-  event::GLOBAL_EVENT_SYSTEM,
-  shared::ast::{Expression, Location, Statement},
-};
+use crate::shared::ast::{Expression, Location, Statement};
 
 pub type EvalFn<'a> = &'a mut dyn FnMut(Expression) -> Result<Value, InterpreterError>;
 pub type LibFn = Arc<dyn Fn(Vec<Value>, Location) -> Result<Value, InterpreterError> + Send + Sync>;
@@ -22,6 +43,7 @@ pub type LibFn = Arc<dyn Fn(Vec<Value>, Location) -> Result<Value, InterpreterEr
 pub enum InterpreterState {
   Running,
   Waiting(u32),
+  Paused,
   Completed,
   Error(InterpreterError),
 }
@@ -31,9 +53,27 @@ pub struct Interpreter {
   pub environment: Environment,
   state: InterpreterState,
   current_stmt_index: usize,
-  statements: Vec<Statement>,
-  current_input_value: Option<Value>,
-  processing_input: bool,
+  statements: Arc<[Statement]>,
+  config: InterpreterConfig,
+  /// The host that `config.seed` wraps around the caller's, kept to save its state
+  deterministic: Option<Arc<DeterministicHost>>,
+  usage: Usage,
+  coroutine: Option<Coroutine>,
+  suspension: Option<Suspension>,
+  debugger: Option<Arc<Debugger>>,
+  stopped: Option<Stopped>,
+  /// Step shown by `step_back` and where the program really is stopped
+  rewound: Option<(usize, Stopped)>,
+  call_stack: Vec<CallFrame>,
+  /// Loops running in the current function. A `retorne` inside one is never a tail call
+  loops: usize,
+  tail_call: Option<TailCall>,
+  tracer: Option<Arc<Mutex<Tracer>>>,
+  history: Option<Arc<Mutex<Recorder>>>,
+  profiler: Option<Arc<Mutex<Profiler>>>,
+  coverage: Option<Arc<Mutex<CoverageRecorder>>>,
+  machine: Option<Machine>,
+  functions: HashMap<String, Arc<Function>>,
 }
 
 impl Default for Interpreter {
@@ -43,60 +83,75 @@ impl Default for Interpreter {
       environment: Environment::default(),
       state: InterpreterState::Running,
       current_stmt_index: 0,
-      statements: Vec::new(),
-      current_input_value: None,
-      processing_input: false,
+      statements: Arc::new([]),
+      config: InterpreterConfig::default(),
+      deterministic: None,
+      usage: Usage::default(),
+      coroutine: None,
+      suspension: None,
+      debugger: None,
+      stopped: None,
+      rewound: None,
+      call_stack: Vec::new(),
+      loops: 0,
+      tail_call: None,
+      tracer: None,
+      history: None,
+      profiler: None,
+      coverage: None,
+      machine: None,
+      functions: HashMap::new(),
     }
   }
 }
 
 impl Interpreter {
   pub fn new(stmts: Vec<Statement>) -> Result<Self, InterpreterError> {
-    let mut interpreter = Interpreter {
-      location: Location::default(),
-      environment: Environment::default(),
-      state: InterpreterState::Running,
-      current_stmt_index: 0,
-      statements: stmts,
-      current_input_value: None,
-      processing_input: false,
-    };
-
-    interpreter.run()?;
+    Self::with_config(stmts, InterpreterConfig::default())
+  }
 
-    Ok(interpreter)
+  pub fn with_backend(stmts: Vec<Statement>, backend: Backend) -> Result<Self, InterpreterError> {
+    Self::with_config(
+      stmts,
+      InterpreterConfig {
+        backend,
+        ..Default::default()
+      },
+    )
   }
 
   pub fn run(&mut self) -> Result<(), InterpreterError> {
     match self.state {
       InterpreterState::Waiting(_) => return Ok(()),
+      InterpreterState::Paused => return Ok(()),
       InterpreterState::Completed => return Ok(()),
       InterpreterState::Error(ref e) => return Err(e.clone()),
       InterpreterState::Running => {}
     }
 
-    while self.current_stmt_index < self.statements.len() {
-      let stmt = self.statements[self.current_stmt_index].clone();
-      let result = self.eval_stmt(&stmt);
-
-      match result {
-        Ok(Value::InputPending(callback_id)) => {
-          self.state = InterpreterState::Waiting(callback_id);
-
-          return Ok(());
-        }
-        Ok(_) => {
-          self.current_stmt_index += 1;
-        }
-        Err(e) => {
-          self.state = InterpreterState::Error(e.clone());
-          return Err(e);
-        }
-      }
+    if self.config.backend == Backend::Bytecode {
+      return self.run_bytecode();
     }
 
-    self.state = InterpreterState::Completed;
-    Ok(())
+    // The tree-walker runs as a coroutine that stops at each `ler()` call
+    let coroutine = match self.coroutine.take() {
+      Some(coroutine) => coroutine,
+      None => Coroutine::spawn(Interpreter {
+        environment: self.environment.clone(),
+        statements: self.statements.clone(),
+        config: self.config.clone(),
+        debugger: self.debugger.clone(),
+        tracer: self.tracer.clone(),
+        history: self.history.clone(),
+        profiler: self.profiler.clone(),
+        coverage: self.coverage.clone(),
+        ..Default::default()
+      }),
+    };
+
+    let outcome = coroutine.next();
+    self.coroutine = Some(coroutine);
+    self.settle(outcome)
   }
 
   pub fn is_waiting_for_input(&self) -> bool {
@@ -111,143 +166,272 @@ impl Interpreter {
   }
 
   pub fn provide_input(&mut self, input: String) -> Result<(), InterpreterError> {
-    let callback_id = match self.state {
-      InterpreterState::Waiting(id) => id,
-      _ => {
-        return Err(InterpreterError::RuntimeError(
-          self.location.clone(),
-          "Não há input pendente".into(),
-        ))
+    if !self.is_waiting_for_input() {
+      return Err(InterpreterError::RuntimeError(
+        self.location.clone(),
+        "Não há input pendente".into(),
+      ));
+    }
+
+    let input_value = builtin::io::input_value(input);
+
+    if let Err(e) = self.check_size(&input_value) {
+      self.state = InterpreterState::Error(e.clone());
+      return Err(e);
+    }
+
+    if self.config.backend == Backend::Bytecode {
+      if let Some(machine) = self.machine.as_mut() {
+        machine.resume_with(input_value);
       }
+
+      self.state = InterpreterState::Running;
+      return self.run();
+    }
+
+    // Resume the evaluator right where `ler()` was called
+    let outcome = match &self.coroutine {
+      Some(coroutine) => coroutine.resume(input_value),
+      None => Yield::Completed,
     };
 
-    // Set the processing_input flag to true to prevent duplicate input requests
-    self.processing_input = true;
-
-    let result =
-      if let Some(callback_result) = GLOBAL_EVENT_SYSTEM.resolve_callback(&callback_id, input) {
-        callback_result
-      } else {
-        self.processing_input = false;
-        return Err(InterpreterError::RuntimeError(
-          self.location.clone(),
-          "Callback não encontrado".into(),
-        ));
-      };
-
-    match result {
-      Ok(input_value) => {
-        // Store the input value in the environment rather than just in the current instance
-        self.environment.set_input_result(input_value.clone());
-
-        // Check if we need to resume a function call
-        if let Some((fn_name, fn_args)) = self.environment.take_pending_function_call() {
-          // Create a call expression to re-evaluate
-          let callee = Expression::Identifier(fn_name, self.location.clone());
-          let result = self.eval_call_expr(callee, fn_args)?;
-
-          // Store the result as the current input value
-          self.current_input_value = Some(result);
-        } else {
-          // Otherwise, just store the input value directly
-          self.current_input_value = Some(input_value);
-        }
-
-        // Don't increment the statement index yet - we'll re-evaluate the current statement
-        // but this time with the input value available
-        self.state = InterpreterState::Running;
-
-        // Re-evaluate the current statement with the input value
-        let current_stmt = self.statements[self.current_stmt_index].clone();
-        let stmt_result = self.eval_stmt(&current_stmt);
-
-        // Reset the processing_input flag
-        self.processing_input = false;
-
-        match stmt_result {
-          Ok(_) => {
-            // Now we can increment the statement index
-            self.current_input_value = None;
-            self.current_stmt_index += 1;
-            self.run()
-          }
-          Err(e) => {
-            self.state = InterpreterState::Error(e.clone());
-            Err(e)
-          }
-        }
+    self.settle(outcome)
+  }
+
+  /// Ends a program waiting for input that will never come, e.g. when the
+  /// input file ran out, with an error at the `ler()`
+  pub fn end_input(&mut self, reason: &str) -> InterpreterError {
+    if !self.is_waiting_for_input() {
+      return InterpreterError::RuntimeError(self.location.clone(), "Não há input pendente".into());
+    }
+
+    let message = format!("Erro ao ler a entrada: {}", reason);
+
+    // The evaluator fails at the `ler()` itself and ends the run as usual
+    if let Some(coroutine) = &self.coroutine {
+      let outcome = coroutine.fail(message);
+      return self
+        .settle(outcome)
+        .expect_err("a failed input ends the run");
+    }
+
+    // Undoes what the calls waiting for the input changed, like a failed run
+    let error = InterpreterError::RuntimeError(self.location.clone(), message);
+    self.machine = None;
+    self.environment.symbols.write().exit_calls();
+    self.environment.set_function_depth(0);
+
+    self.state = InterpreterState::Error(error.clone());
+    error
+  }
+
+  pub fn reset(&mut self) {
+    self.state = InterpreterState::Running;
+    self.current_stmt_index = 0;
+    self.machine = None;
+    self.coroutine = None;
+    self.stopped = None;
+    self.rewound = None;
+  }
+
+  /// Runs more statements with the variables, functions and imports of the
+  /// previous ones, like each entry of the REPL. The previous statements must
+  /// have finished, even if with an error
+  pub fn extend(&mut self, stmts: Vec<Statement>) -> Result<(), InterpreterError> {
+    if !matches!(
+      self.state,
+      InterpreterState::Completed | InterpreterState::Error(_)
+    ) {
+      return Err(InterpreterError::RuntimeError(
+        self.location.clone(),
+        "O programa anterior ainda não terminou".into(),
+      ));
+    }
+
+    self.statements = stmts.into();
+    self.reset();
+    self.run()
+  }
+
+  /// Variables and constants defined so far, by name
+  pub fn variables(&self) -> Vec<(String, Value)> {
+    self
+      .environment
+      .symbols
+      .read()
+      .iter()
+      .map(|(name, symbol)| (name.clone(), symbol.get_value().clone()))
+      .collect()
+  }
+
+  /// User functions defined so far and their parameters, by name
+  pub fn functions(&self) -> Vec<(String, Vec<String>)> {
+    let mut functions: Vec<(String, Vec<String>)> = self
+      .environment
+      .functions
+      .read()
+      .iter()
+      .map(|(name, function)| (name.clone(), function.params.clone()))
+      .collect();
+
+    functions.sort();
+    functions
+  }
+
+  pub fn backend(&self) -> Backend {
+    self.config.backend
+  }
+
+  fn run_bytecode(&mut self) -> Result<(), InterpreterError> {
+    let outcome = self.execute();
+    self.config.host.flush();
+
+    match outcome {
+      Ok(Exit::Waiting(callback_id)) => {
+        self.state = InterpreterState::Waiting(callback_id);
+        Ok(())
+      }
+      Ok(Exit::Completed) => {
+        self.state = InterpreterState::Completed;
+        Ok(())
       }
       Err(e) => {
-        self.processing_input = false;
+        // Undoes what the calls still running when it failed changed
+        self.machine = None;
+        self.environment.symbols.write().exit_calls();
+        self.environment.set_function_depth(0);
+
         self.state = InterpreterState::Error(e.clone());
         Err(e)
       }
     }
   }
 
-  pub fn reset(&mut self) {
-    self.state = InterpreterState::Running;
-    self.current_stmt_index = 0;
-  }
+  fn settle(&mut self, outcome: Yield) -> Result<(), InterpreterError> {
+    self.config.host.flush();
 
-  pub fn get_state(&self) -> &InterpreterState {
-    &self.state
+    match outcome {
+      Yield::Waiting(callback_id) => {
+        self.state = InterpreterState::Waiting(callback_id);
+        Ok(())
+      }
+      Yield::Stopped(stopped) => {
+        self.announce_stop(stopped);
+        Ok(())
+      }
+      Yield::Completed => {
+        self.coroutine = None;
+        self.state = InterpreterState::Completed;
+        Ok(())
+      }
+      Yield::Failed(e) => {
+        self.coroutine = None;
+        self.state = InterpreterState::Error(e.clone());
+        Err(e)
+      }
+    }
   }
 
-  fn handle_input_pending(&mut self, value: Value) -> Result<Value, InterpreterError> {
-    match value {
-      Value::InputPending(_) => {
-        // If we're currently processing input and have an input result available,
-        // use it instead of InputPending
-        if self.processing_input {
-          if let Some(input_value) = self.environment.take_input_result() {
-            return Ok(input_value);
-          }
-        }
-        // Otherwise, return the InputPending value as is
-        Ok(value)
+  /// Evaluates every top-level statement, suspending inside `ler()` calls
+  fn eval_program(&mut self) -> Result<(), InterpreterError> {
+    // Shared with the loop below, so statements are evaluated by reference
+    let statements = self.statements.clone();
+
+    let mut result = Ok(());
+    self.start_clock();
+
+    while self.current_stmt_index < statements.len() {
+      if let Err(e) = self.exec_stmt(&statements[self.current_stmt_index]) {
+        result = Err(e);
+        break;
       }
-      _ => Ok(value),
+
+      self.current_stmt_index += 1;
     }
+
+    self.stop_clock();
+    self.trace_finish();
+    self.record_finish();
+    self.profile_finish();
+    result
+  }
+
+  /// Evaluates a statement of a block, going through the history, debugger,
+  /// tracer, profiler and coverage
+  fn exec_stmt(&mut self, stmt: &Statement) -> Result<Value, InterpreterError> {
+    self.tick()?;
+    self.cover_stmt(stmt);
+    self.record_step(stmt);
+    self.debug_hook(stmt)?;
+    self.trace_enter(stmt);
+    self.profile_enter(stmt);
+
+    let result = self.eval_stmt(stmt)?;
+    self.profile_exit();
+    self.trace_exit();
+
+    Ok(result)
+  }
+
+  pub fn get_state(&self) -> &InterpreterState {
+    &self.state
   }
 
   fn eval_stmt(&mut self, stmt: &Statement) -> Result<Value, InterpreterError> {
     self.location = stmt.location();
 
-    match stmt.clone() {
-      Statement::Expression(expr, _location) => self.eval_expr(&expr),
+    match stmt {
+      Statement::Expression(expr, _location) => self.eval_expr(expr),
       Statement::Assignment {
         target,
         index,
         value,
         location: _,
-      } => self.eval_assignment_stmt(*target, index, *value),
+      } => self.eval_assignment_stmt(target, index.as_deref(), value),
+      Statement::Destructure {
+        names,
+        value,
+        declare,
+        location,
+      } => self.eval_destructure_stmt(names, value, *declare, location),
       Statement::If {
         condition,
         true_block,
         else_if_blocks,
         else_block,
-        location: _,
-      } => self.eval_if_stmt(*condition, true_block, else_if_blocks, else_block),
+        location,
+      } => self.eval_if_stmt(
+        condition.as_ref().as_ref(),
+        true_block,
+        else_if_blocks,
+        else_block.as_deref(),
+        location,
+      ),
       Statement::While {
         condition,
         body,
         location: _,
-      } => self.eval_while_stmt(*condition, body),
+      } => self.in_loop(|this| this.eval_while_stmt(condition, body)),
       Statement::For {
         initializer,
         condition,
         update,
         body,
         location: _,
-      } => self.eval_for_stmt(*initializer, *condition, *update, body),
+      } => self.in_loop(|this| this.eval_for_stmt(initializer, condition, update, body)),
+      Statement::ForEach {
+        names,
+        iterable,
+        body,
+        location,
+      } => self.in_loop(|this| this.eval_for_each_stmt(names, iterable, body, location)),
       // Statement::Return(value) => self.eval_return(value),
       Statement::Switch {
         expression,
         cases,
         default,
-        location: _,
-      } => self.eval_switch_stmt(*expression, cases, default),
+        location,
+      } => self.eval_switch_stmt(expression, cases, default.as_ref(), location),
       Statement::Function {
         name,
         params,
@@ -258,13 +442,13 @@ impl Interpreter {
         name,
         initializer,
         location: _,
-      } => self.eval_variable_stmt(name, *initializer),
+      } => self.eval_variable_stmt(name, initializer),
       Statement::Constant {
         name,
         initializer,
         location: _,
-      } => self.eval_constant_stmt(name, *initializer),
-      Statement::Return { value, location } => self.eval_return_stmt(value, location),
+      } => self.eval_constant_stmt(name, initializer),
+      Statement::Return { value, location } => self.eval_return_stmt(value.as_deref(), location),
       Statement::Import(path, _location) => self.eval_import_stmt(path),
     }
   }
@@ -272,74 +456,71 @@ impl Interpreter {
   fn eval_expr(&mut self, expr: &Expression) -> Result<Value, InterpreterError> {
     self.location = expr.location();
 
-    if let Some(input_value) = &self.current_input_value {
-      if let Expression::Call { .. } = expr {
-        return Ok(input_value.clone());
-      }
-    }
-
-    let value = match expr.clone() {
+    match expr {
       Expression::Logical {
         left,
         operator,
         right,
         location: _,
-      } => self.eval_logical_expr(*left, operator, *right),
+      } => self.eval_logical_expr(left, operator, right),
       Expression::Comparison {
         left,
         operator,
         right,
         location: _,
-      } => self.eval_comparison_expr(*left, operator, *right),
-      Expression::PostfixIncrement(expr, _location) => self.eval_postfix_increment_expr(*expr),
-      Expression::PostfixDecrement(expr, _location) => self.eval_postfix_decrement_expr(*expr),
-      Expression::PrefixIncrement(expr, _location) => self.eval_prefix_increment_expr(*expr),
-      Expression::PrefixDecrement(expr, _location) => self.eval_prefix_decrement_expr(*expr),
+      } => self.eval_comparison_expr(left, operator, right),
+      Expression::PostfixIncrement(expr, _location) => self.eval_postfix_increment_expr(expr),
+      Expression::PostfixDecrement(expr, _location) => self.eval_postfix_decrement_expr(expr),
+      Expression::PrefixIncrement(expr, _location) => self.eval_prefix_increment_expr(expr),
+      Expression::PrefixDecrement(expr, _location) => self.eval_prefix_decrement_expr(expr),
       Expression::Call {
         callee,
         arguments,
         location: _,
-      } => self.eval_call_expr(*callee, arguments),
-      Expression::Float(value, _location) => Ok(Value::Float(value)),
-      Expression::Integer(value, _location) => Ok(Value::Integer(value)),
-      Expression::Boolean(value, _location) => Ok(Value::Boolean(value)),
-      Expression::String(value, _location) => Ok(Value::String(value)),
+      } => self.eval_call_expr(callee, arguments),
+      Expression::Float(value, _location) => Ok(Value::Float(*value)),
+      Expression::Integer(value, _location) => Ok(Value::Integer(*value)),
+      Expression::Boolean(value, _location) => Ok(Value::Boolean(*value)),
+      Expression::String(value, _location) => Ok(Value::String(value.clone())),
       Expression::List(elements, _location) => self.eval_list_expr(elements),
       Expression::Unary {
         operator,
         expr,
         location: _,
-      } => self.eval_unary_expr(operator, *expr),
+      } => self.eval_unary_expr(operator, expr),
       Expression::Arithmetic {
         left,
         operator,
         right,
         location: _,
-      } => self.eval_arithmetic_expr(*left, operator, *right),
+      } => self.eval_arithmetic_expr(left, operator, right),
       Expression::Index {
         name,
         index,
         location: _,
-      } => self.eval_index(name, *index),
+      } => self.eval_index(name, index),
       Expression::Identifier(value, _location) => self.eval_identifier_expr(value),
       #[allow(unreachable_patterns)]
       _ => Err(InterpreterError::EvalError(
         self.location.clone(),
         "Expressão não suportada".into(),
       )),
-    };
+    }
+  }
 
-    self.handle_input_pending(value?)
+  fn in_loop(
+    &mut self,
+    eval: impl FnOnce(&mut Self) -> Result<Value, InterpreterError>,
+  ) -> Result<Value, InterpreterError> {
+    self.loops += 1;
+    let result = eval(self);
+    self.loops -= 1;
+    result
   }
 
-  fn eval_block(&mut self, block: &Vec<Statement>) -> Result<Value, InterpreterError> {
+  fn eval_block(&mut self, block: &[Statement]) -> Result<Value, InterpreterError> {
     for stmt in block {
-      let result = self.eval_stmt(&stmt)?;
-
-      // If we hit a PendingInput, propagate it up
-      if let Value::InputPending(id) = result {
-        return Ok(Value::InputPending(id));
-      }
+      let result = self.exec_stmt(stmt)?;
 
       // If we hit a Return, propagate it up
       if let Value::Return(_) = result {
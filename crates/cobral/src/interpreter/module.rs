@@ -0,0 +1,619 @@
+use std::{collections::BTreeMap, fmt, sync::Arc};
+
+use crate::shared::ast::Location;
+
+use super::{
+  builtin,
+  config::{Limit, SizeLimits},
+  error::InterpreterError,
+  host::Host,
+  host::HostHandle,
+  value::{List, Value},
+  LibFn,
+};
+
+/// Converts a CobraL value into a Rust argument of a native function
+pub trait FromValue: Sized {
+  /// Name of the accepted type, shown in signatures and errors
+  const TYPE: &'static str;
+
+  fn from_value(value: Value) -> Option<Self>;
+}
+
+/// Converts what a native function returns into a CobraL value
+pub trait IntoValue {
+  fn into_value(self) -> Value;
+}
+
+/// A number that keeps whether it is an `inteiro` or a `real`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Number {
+  Integer(i64),
+  Float(f64),
+}
+
+impl Number {
+  pub fn as_f64(self) -> f64 {
+    match self {
+      Number::Integer(i) => i as f64,
+      Number::Float(f) => f,
+    }
+  }
+}
+
+impl FromValue for Value {
+  const TYPE: &'static str = "qualquer";
+
+  fn from_value(value: Value) -> Option<Self> {
+    Some(value)
+  }
+}
+
+impl FromValue for i64 {
+  const TYPE: &'static str = "inteiro";
+
+  fn from_value(value: Value) -> Option<Self> {
+    match value {
+      Value::Integer(i) => Some(i),
+      _ => None,
+    }
+  }
+}
+
+/// Accepts `inteiro` too, like the arithmetic operators do
+impl FromValue for f64 {
+  const TYPE: &'static str = "número";
+
+  fn from_value(value: Value) -> Option<Self> {
+    Number::from_value(value).map(Number::as_f64)
+  }
+}
+
+impl FromValue for Number {
+  const TYPE: &'static str = "número";
+
+  fn from_value(value: Value) -> Option<Self> {
+    match value {
+      Value::Integer(i) => Some(Number::Integer(i)),
+      Value::Float(f) => Some(Number::Float(f)),
+      _ => None,
+    }
+  }
+}
+
+impl FromValue for bool {
+  const TYPE: &'static str = "lógico";
+
+  fn from_value(value: Value) -> Option<Self> {
+    match value {
+      Value::Boolean(b) => Some(b),
+      _ => None,
+    }
+  }
+}
+
+impl FromValue for String {
+  const TYPE: &'static str = "texto";
+
+  fn from_value(value: Value) -> Option<Self> {
+    match value {
+      Value::String(s) => Some(s),
+      _ => None,
+    }
+  }
+}
+
+/// The list itself, so that changes made by the function are seen by the caller
+impl FromValue for List {
+  const TYPE: &'static str = "vetor";
+
+  fn from_value(value: Value) -> Option<Self> {
+    match value {
+      Value::List(list) => Some(list),
+      _ => None,
+    }
+  }
+}
+
+/// A `texto` or a `vetor`, for functions like `tamanho` that work with both
+#[derive(Debug, Clone)]
+pub enum Sequence {
+  Text(String),
+  List(List),
+}
+
+impl FromValue for Sequence {
+  const TYPE: &'static str = "texto ou vetor";
+
+  fn from_value(value: Value) -> Option<Self> {
+    match value {
+      Value::String(s) => Some(Sequence::Text(s)),
+      Value::List(list) => Some(Sequence::List(list)),
+      _ => None,
+    }
+  }
+}
+
+impl<T: FromValue> FromValue for Vec<T> {
+  const TYPE: &'static str = "vetor";
+
+  fn from_value(value: Value) -> Option<Self> {
+    match value {
+      Value::List(items) => items.items().into_iter().map(T::from_value).collect(),
+      _ => None,
+    }
+  }
+}
+
+impl IntoValue for Value {
+  fn into_value(self) -> Value {
+    self
+  }
+}
+
+impl IntoValue for () {
+  fn into_value(self) -> Value {
+    Value::None
+  }
+}
+
+impl IntoValue for i64 {
+  fn into_value(self) -> Value {
+    Value::Integer(self)
+  }
+}
+
+impl IntoValue for f64 {
+  fn into_value(self) -> Value {
+    Value::Float(self)
+  }
+}
+
+impl IntoValue for Number {
+  fn into_value(self) -> Value {
+    match self {
+      Number::Integer(i) => Value::Integer(i),
+      Number::Float(f) => Value::Float(f),
+    }
+  }
+}
+
+impl IntoValue for bool {
+  fn into_value(self) -> Value {
+    Value::Boolean(self)
+  }
+}
+
+impl IntoValue for String {
+  fn into_value(self) -> Value {
+    Value::String(self)
+  }
+}
+
+impl IntoValue for List {
+  fn into_value(self) -> Value {
+    Value::List(self)
+  }
+}
+
+impl<T: IntoValue> IntoValue for Vec<T> {
+  fn into_value(self) -> Value {
+    Value::list(self.into_iter().map(IntoValue::into_value).collect())
+  }
+}
+
+/// Why a native function failed
+#[derive(Debug, Clone, PartialEq)]
+pub enum NativeError {
+  /// Becomes a runtime error with this message
+  Message(String),
+  /// What the function would build goes over a size limit
+  Limit(Limit),
+}
+
+impl NativeError {
+  fn at(self, location: Location) -> InterpreterError {
+    match self {
+      NativeError::Message(message) => InterpreterError::RuntimeError(location, message),
+      NativeError::Limit(limit) => InterpreterError::LimitExceeded(location, limit),
+    }
+  }
+}
+
+impl From<String> for NativeError {
+  fn from(message: String) -> Self {
+    NativeError::Message(message)
+  }
+}
+
+impl From<Limit> for NativeError {
+  fn from(limit: Limit) -> Self {
+    NativeError::Limit(limit)
+  }
+}
+
+/// What a native function may return: a value, or an error message that
+/// becomes a runtime error at the call site
+pub trait NativeResult {
+  fn into_result(self) -> Result<Value, NativeError>;
+}
+
+impl<T: IntoValue> NativeResult for T {
+  fn into_result(self) -> Result<Value, NativeError> {
+    Ok(self.into_value())
+  }
+}
+
+impl<T: IntoValue> NativeResult for Result<T, String> {
+  fn into_result(self) -> Result<Value, NativeError> {
+    self
+      .map(IntoValue::into_value)
+      .map_err(NativeError::Message)
+  }
+}
+
+impl<T: IntoValue> NativeResult for Result<T, NativeError> {
+  fn into_result(self) -> Result<Value, NativeError> {
+    self.map(IntoValue::into_value)
+  }
+}
+
+/// Marks native functions whose first parameter is the interpreter's
+/// `&dyn Host`, e.g. to draw random numbers or read the clock
+pub struct WithHost;
+
+/// Marks native functions whose first parameter is the interpreter's
+/// `&SizeLimits`, to check what they build before allocating it
+pub struct WithLimits;
+
+/// A Rust closure callable from CobraL. `Args` is the tuple of its parameter
+/// types, which are checked before each call
+pub trait NativeFn<Args>: Send + Sync + 'static {
+  fn params() -> Vec<&'static str>;
+
+  fn call(
+    &self,
+    host: &dyn Host,
+    limits: &SizeLimits,
+    name: &str,
+    args: Vec<Value>,
+    location: Location,
+  ) -> Result<Value, InterpreterError>;
+}
+
+/// Checks the number of arguments and converts each one to its parameter type
+macro_rules! convert_args {
+  ($name:ident, $args:ident, $location:ident, $($arg:ident),*) => {
+    let expected = <[&str]>::len(&[$($arg::TYPE),*]);
+    if $args.len() != expected {
+      return Err(InterpreterError::ArgumentMismatchError(
+        $location,
+        format!("{} espera {} argumento(s), recebeu {}", $name, expected, $args.len()),
+      ));
+    }
+
+    let mut $args = $args.into_iter().enumerate();
+    $(
+      let (index, value) = $args.next().expect("arity checked above");
+      let found = value.type_name();
+      let $arg = $arg::from_value(value).ok_or_else(|| {
+        InterpreterError::TypeError(
+          $location.clone(),
+          format!(
+            "O argumento {} de {} deve ser {}, recebeu {}",
+            index + 1,
+            $name,
+            $arg::TYPE,
+            found
+          ),
+        )
+      })?;
+    )*
+  };
+}
+
+macro_rules! native_fn {
+  ($($arg:ident),*) => {
+    impl<F, R, $($arg),*> NativeFn<($($arg,)*)> for F
+    where
+      F: Fn($($arg),*) -> R + Send + Sync + 'static,
+      R: NativeResult,
+      $($arg: FromValue,)*
+    {
+      fn params() -> Vec<&'static str> {
+        vec![$($arg::TYPE),*]
+      }
+
+      #[allow(non_snake_case, unused_mut, unused_variables)]
+      fn call(
+        &self,
+        _host: &dyn Host,
+        _limits: &SizeLimits,
+        name: &str,
+        args: Vec<Value>,
+        location: Location,
+      ) -> Result<Value, InterpreterError> {
+        convert_args!(name, args, location, $($arg),*);
+
+        self($($arg),*)
+          .into_result()
+          .map_err(|error| error.at(location))
+      }
+    }
+
+    impl<F, R, $($arg),*> NativeFn<(WithHost, $($arg,)*)> for F
+    where
+      F: Fn(&dyn Host, $($arg),*) -> R + Send + Sync + 'static,
+      R: NativeResult,
+      $($arg: FromValue,)*
+    {
+      fn params() -> Vec<&'static str> {
+        vec![$($arg::TYPE),*]
+      }
+
+      #[allow(non_snake_case, unused_mut, unused_variables)]
+      fn call(
+        &self,
+        host: &dyn Host,
+        _limits: &SizeLimits,
+        name: &str,
+        args: Vec<Value>,
+        location: Location,
+      ) -> Result<Value, InterpreterError> {
+        convert_args!(name, args, location, $($arg),*);
+
+        self(host, $($arg),*)
+          .into_result()
+          .map_err(|error| error.at(location))
+      }
+    }
+
+    impl<F, R, $($arg),*> NativeFn<(WithLimits, $($arg,)*)> for F
+    where
+      F: Fn(&SizeLimits, $($arg),*) -> R + Send + Sync + 'static,
+      R: NativeResult,
+      $($arg: FromValue,)*
+    {
+      fn params() -> Vec<&'static str> {
+        vec![$($arg::TYPE),*]
+      }
+
+      #[allow(non_snake_case, unused_mut, unused_variables)]
+      fn call(
+        &self,
+        _host: &dyn Host,
+        limits: &SizeLimits,
+        name: &str,
+        args: Vec<Value>,
+        location: Location,
+      ) -> Result<Value, InterpreterError> {
+        convert_args!(name, args, location, $($arg),*);
+
+        self(limits, $($arg),*)
+          .into_result()
+          .map_err(|error| error.at(location))
+      }
+    }
+  };
+}
+
+native_fn!();
+native_fn!(A);
+native_fn!(A, B);
+native_fn!(A, B, C);
+native_fn!(A, B, C, D);
+
+type NativeCall = Arc<
+  dyn Fn(&dyn Host, &SizeLimits, Vec<Value>, Location) -> Result<Value, InterpreterError>
+    + Send
+    + Sync,
+>;
+
+/// A function of a native module, with the signature shown to users
+#[derive(Clone)]
+pub struct NativeFunction {
+  pub name: String,
+  pub params: Vec<&'static str>,
+  /// Takes any number of values instead of `params`
+  pub variadic: bool,
+  pub docs: String,
+  call: NativeCall,
+}
+
+impl NativeFunction {
+  /// Number of parameters. A variadic function takes any number
+  pub fn arity(&self) -> usize {
+    self.params.len()
+  }
+
+  /// E.g. `potencia(número, número)`, or `escreva(...)` if variadic
+  pub fn signature(&self) -> String {
+    match self.variadic {
+      true => format!("{}(...)", self.name),
+      false => format!("{}({})", self.name, self.params.join(", ")),
+    }
+  }
+
+  pub fn call(
+    &self,
+    host: &dyn Host,
+    limits: &SizeLimits,
+    args: Vec<Value>,
+    location: Location,
+  ) -> Result<Value, InterpreterError> {
+    (self.call)(host, limits, args, location)
+  }
+
+  /// The function bound to the host and the size limits of one interpreter
+  pub(crate) fn lib_fn(&self, host: HostHandle, limits: SizeLimits) -> LibFn {
+    let call = self.call.clone();
+    Arc::new(move |args, location| call(&*host, &limits, args, location))
+  }
+}
+
+impl fmt::Debug for NativeFunction {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("NativeFunction")
+      .field("name", &self.name)
+      .field("params", &self.params)
+      .field("variadic", &self.variadic)
+      .finish_non_exhaustive()
+  }
+}
+
+/// A library that programs load with `importe "nome"`
+#[derive(Debug, Clone)]
+pub struct Module {
+  pub name: String,
+  pub docs: String,
+  pub functions: Vec<NativeFunction>,
+}
+
+impl Module {
+  pub fn function(&self, name: &str) -> Option<&NativeFunction> {
+    self.functions.iter().find(|function| function.name == name)
+  }
+}
+
+/// Builds a `Module` out of ordinary Rust closures:
+///
+/// ```
+/// use cobral::interpreter::module::ModuleBuilder;
+///
+/// let module = ModuleBuilder::new("saudacao")
+///   .docs("Cumprimenta pessoas")
+///   .function("ola", "Diz olá para `nome`", |nome: String| format!("Olá, {}!", nome))
+///   .build();
+///
+/// assert_eq!(module.functions[0].signature(), "ola(texto)");
+/// ```
+pub struct ModuleBuilder {
+  module: Module,
+}
+
+impl ModuleBuilder {
+  pub fn new(name: impl Into<String>) -> Self {
+    ModuleBuilder {
+      module: Module {
+        name: name.into(),
+        docs: String::new(),
+        functions: Vec::new(),
+      },
+    }
+  }
+
+  pub fn docs(mut self, docs: impl Into<String>) -> Self {
+    self.module.docs = docs.into();
+    self
+  }
+
+  /// Adds a function whose arity and parameter types come from `function`.
+  /// If its first parameter is `&dyn Host`, it receives the interpreter's host,
+  /// and if it is `&SizeLimits`, the interpreter's size limits
+  pub fn function<Args, F: NativeFn<Args>>(
+    mut self,
+    name: impl Into<String>,
+    docs: impl Into<String>,
+    function: F,
+  ) -> Self {
+    let name = name.into();
+    let call_name = name.clone();
+
+    self.module.functions.push(NativeFunction {
+      name,
+      params: F::params(),
+      variadic: false,
+      docs: docs.into(),
+      call: Arc::new(move |host, limits, args, location| {
+        function.call(host, limits, &call_name, args, location)
+      }),
+    });
+
+    self
+  }
+
+  /// Adds a function that takes any number of values of any type, like
+  /// `escrever`. It receives the interpreter's host and the values as given
+  pub fn variadic<R: NativeResult>(
+    mut self,
+    name: impl Into<String>,
+    docs: impl Into<String>,
+    function: impl Fn(&dyn Host, Vec<Value>) -> R + Send + Sync + 'static,
+  ) -> Self {
+    self.module.functions.push(NativeFunction {
+      name: name.into(),
+      params: Vec::new(),
+      variadic: true,
+      docs: docs.into(),
+      call: Arc::new(move |host, _limits, args, location| {
+        function(host, args)
+          .into_result()
+          .map_err(|error| error.at(location))
+      }),
+    });
+
+    self
+  }
+
+  pub fn build(self) -> Module {
+    self.module
+  }
+}
+
+/// The modules programs can import. Both the parser and the interpreter need
+/// the same registry, since calls are checked while parsing
+#[derive(Clone)]
+pub struct ModuleRegistry {
+  modules: Arc<BTreeMap<String, Arc<Module>>>,
+}
+
+impl ModuleRegistry {
+  /// A registry without any module, not even the builtin ones
+  pub fn empty() -> Self {
+    ModuleRegistry {
+      modules: Arc::default(),
+    }
+  }
+
+  /// Adds `module`, replacing a module with the same name
+  pub fn register(&mut self, module: Module) {
+    Arc::make_mut(&mut self.modules).insert(module.name.clone(), Arc::new(module));
+  }
+
+  pub fn get(&self, name: &str) -> Option<&Module> {
+    self.modules.get(name).map(|module| &**module)
+  }
+
+  /// The module that has a function called `function`
+  pub fn module_of(&self, function: &str) -> Option<&Module> {
+    self
+      .modules
+      .values()
+      .find(|module| module.function(function).is_some())
+      .map(|module| &**module)
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = &Module> {
+    self.modules.values().map(|module| &**module)
+  }
+}
+
+/// The builtin modules: `matematica`, `conversao`, `aleatorio`, `texto`,
+/// `lista`, `saida` and `tempo`
+impl Default for ModuleRegistry {
+  fn default() -> Self {
+    let mut registry = ModuleRegistry::empty();
+
+    for module in builtin::modules() {
+      registry.register(module);
+    }
+
+    registry
+  }
+}
+
+impl fmt::Debug for ModuleRegistry {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_list().entries(self.modules.keys()).finish()
+  }
+}
@@ -0,0 +1,397 @@
+use std::{
+  collections::HashMap,
+  fmt::Write,
+  sync::Arc,
+  time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+use serde::Serialize;
+
+use crate::shared::ast::{Location, Statement};
+
+use super::{
+  config::InterpreterConfig, debugger::MAIN_FRAME, error::InterpreterError, vm::Backend,
+  Interpreter,
+};
+
+/// Executions of one source line and the time spent in it, not counting the
+/// statements nested in it, like the body of a loop or of a called function
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LineProfile {
+  pub file: String,
+  pub line: usize,
+  pub count: u64,
+  pub time: Duration,
+}
+
+/// Calls of one user function. `inclusive` counts the functions it calls and
+/// `exclusive` doesn't
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FunctionProfile {
+  pub name: String,
+  pub calls: u64,
+  pub inclusive: Duration,
+  pub exclusive: Duration,
+}
+
+/// Where a run spent its time, not counting the time waiting for input or
+/// stopped in the debugger
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Profile {
+  pub total: Duration,
+
+  /// Slowest first
+  pub lines: Vec<LineProfile>,
+
+  /// Slowest first, by inclusive time
+  pub functions: Vec<FunctionProfile>,
+
+  /// Exclusive time of each call stack, e.g. `principal;fatorial;fatorial`
+  pub stacks: Vec<(String, Duration)>,
+}
+
+impl Profile {
+  /// Lines and functions sorted by time, as a table
+  pub fn to_report(&self) -> String {
+    let mut report = format!("Tempo total: {}\n", millis(self.total));
+
+    let _ = write!(
+      report,
+      "\n{:<12} {:>10} {:>12} {:>8}\n",
+      "Linha", "Execuções", "Tempo (ms)", "%"
+    );
+
+    for line in &self.lines {
+      let label = match line.file.is_empty() {
+        true => line.line.to_string(),
+        false => format!("{}:{}", line.file, line.line),
+      };
+
+      let _ = writeln!(
+        report,
+        "{:<12} {:>10} {:>12.3} {:>7.1}%",
+        label,
+        line.count,
+        line.time.as_secs_f64() * 1000.0,
+        percent(line.time, self.total)
+      );
+    }
+
+    if self.functions.is_empty() {
+      return report;
+    }
+
+    let _ = write!(
+      report,
+      "\n{:<12} {:>10} {:>12} {:>12}\n",
+      "Função", "Chamadas", "Total (ms)", "Própria (ms)"
+    );
+
+    for function in &self.functions {
+      let _ = writeln!(
+        report,
+        "{:<12} {:>10} {:>12.3} {:>12.3}",
+        function.name,
+        function.calls,
+        function.inclusive.as_secs_f64() * 1000.0,
+        function.exclusive.as_secs_f64() * 1000.0
+      );
+    }
+
+    report
+  }
+
+  /// One `stack microseconds` line per call stack, the folded format read by
+  /// flamegraph.pl and inferno
+  pub fn to_folded(&self) -> String {
+    self
+      .stacks
+      .iter()
+      .filter(|(_, time)| time.as_micros() > 0)
+      .map(|(stack, time)| format!("{} {}\n", stack, time.as_micros()))
+      .collect()
+  }
+}
+
+fn millis(duration: Duration) -> String {
+  format!("{:.3} ms", duration.as_secs_f64() * 1000.0)
+}
+
+fn percent(part: Duration, total: Duration) -> f64 {
+  match total.is_zero() {
+    true => 0.0,
+    false => part.as_secs_f64() / total.as_secs_f64() * 100.0,
+  }
+}
+
+/// A statement or call that hasn't finished yet
+struct Open<K> {
+  key: K,
+  start: Instant,
+
+  /// Time of the statements or calls nested in it
+  nested: Duration,
+}
+
+impl<K> Open<K> {
+  fn new(key: K) -> Self {
+    Open {
+      key,
+      start: Instant::now(),
+      nested: Duration::ZERO,
+    }
+  }
+}
+
+#[derive(Default)]
+struct FunctionTotals {
+  calls: u64,
+  inclusive: Duration,
+  exclusive: Duration,
+}
+
+/// Measures the run while the evaluator executes it
+pub(crate) struct Profiler {
+  lines: HashMap<(String, usize), (u64, Duration)>,
+  functions: HashMap<String, FunctionTotals>,
+  stacks: HashMap<String, Duration>,
+  total: Duration,
+  statements: Vec<Open<(String, usize)>>,
+
+  /// The main program at the bottom, then the user functions being called
+  calls: Vec<Open<String>>,
+  paused_at: Option<Instant>,
+}
+
+impl Profiler {
+  pub(crate) fn new() -> Self {
+    Profiler {
+      lines: HashMap::new(),
+      functions: HashMap::new(),
+      stacks: HashMap::new(),
+      total: Duration::ZERO,
+      statements: Vec::new(),
+      calls: vec![Open::new(MAIN_FRAME.to_string())],
+      paused_at: None,
+    }
+  }
+
+  fn enter(&mut self, location: &Location) {
+    self
+      .statements
+      .push(Open::new((location.file.clone(), location.line)));
+  }
+
+  fn exit(&mut self) {
+    let Some(statement) = self.statements.pop() else {
+      return;
+    };
+
+    let elapsed = statement.start.elapsed();
+    let line = self.lines.entry(statement.key).or_default();
+    line.0 += 1;
+    line.1 += elapsed.saturating_sub(statement.nested);
+
+    if let Some(parent) = self.statements.last_mut() {
+      parent.nested += elapsed;
+    }
+  }
+
+  fn call(&mut self, name: &str) {
+    self.calls.push(Open::new(name.to_string()));
+  }
+
+  fn ret(&mut self) {
+    // The main program is only closed by `finish`
+    if self.calls.len() < 2 {
+      return;
+    }
+
+    let Some(call) = self.calls.pop() else {
+      return;
+    };
+
+    let elapsed = call.start.elapsed();
+    let exclusive = elapsed.saturating_sub(call.nested);
+    *self.stacks.entry(self.stack_of(&call.key)).or_default() += exclusive;
+
+    // Recursive calls are already inside the outermost one
+    let recursive = self.calls.iter().any(|outer| outer.key == call.key);
+
+    let totals = self.functions.entry(call.key).or_default();
+    totals.calls += 1;
+    totals.exclusive += exclusive;
+    if !recursive {
+      totals.inclusive += elapsed;
+    }
+
+    if let Some(caller) = self.calls.last_mut() {
+      caller.nested += elapsed;
+    }
+  }
+
+  /// The open calls followed by `name`, separated by `;`
+  fn stack_of(&self, name: &str) -> String {
+    let mut stack: Vec<&str> = self.calls.iter().map(|call| call.key.as_str()).collect();
+    stack.push(name);
+    stack.join(";")
+  }
+
+  /// Closes whatever is still open, e.g. after an error
+  fn finish(&mut self) {
+    while !self.statements.is_empty() {
+      self.exit();
+    }
+
+    while self.calls.len() > 1 {
+      self.ret();
+    }
+
+    if let Some(main) = self.calls.pop() {
+      let elapsed = main.start.elapsed();
+      self.total += elapsed;
+      *self.stacks.entry(main.key).or_default() += elapsed.saturating_sub(main.nested);
+    }
+  }
+
+  fn pause(&mut self) {
+    self.paused_at.get_or_insert_with(Instant::now);
+  }
+
+  /// Moves the open measurements forward by the time spent paused
+  fn resume(&mut self) {
+    let Some(paused_at) = self.paused_at.take() else {
+      return;
+    };
+
+    let paused = paused_at.elapsed();
+    for statement in &mut self.statements {
+      statement.start += paused;
+    }
+    for call in &mut self.calls {
+      call.start += paused;
+    }
+  }
+
+  fn profile(&self) -> Profile {
+    let mut lines: Vec<LineProfile> = self
+      .lines
+      .iter()
+      .map(|((file, line), (count, time))| LineProfile {
+        file: file.clone(),
+        line: *line,
+        count: *count,
+        time: *time,
+      })
+      .collect();
+    lines.sort_by(|a, b| b.time.cmp(&a.time).then(a.line.cmp(&b.line)));
+
+    let mut functions: Vec<FunctionProfile> = self
+      .functions
+      .iter()
+      .map(|(name, totals)| FunctionProfile {
+        name: name.clone(),
+        calls: totals.calls,
+        inclusive: totals.inclusive,
+        exclusive: totals.exclusive,
+      })
+      .collect();
+    functions.sort_by(|a, b| b.inclusive.cmp(&a.inclusive).then(a.name.cmp(&b.name)));
+
+    let mut stacks: Vec<(String, Duration)> = self
+      .stacks
+      .iter()
+      .map(|(stack, time)| (stack.clone(), *time))
+      .collect();
+    stacks.sort();
+
+    Profile {
+      total: self.total,
+      lines,
+      functions,
+      stacks,
+    }
+  }
+}
+
+impl Interpreter {
+  /// Creates an interpreter that measures time per line and per function.
+  /// Profiling uses the tree-walking backend, which visits every statement
+  pub fn with_profiler(
+    stmts: Vec<Statement>,
+    config: InterpreterConfig,
+  ) -> Result<Self, InterpreterError> {
+    let mut interpreter = Interpreter::profiling(stmts, config);
+
+    interpreter.run()?;
+
+    Ok(interpreter)
+  }
+
+  /// Like `with_profiler`, without starting the program. The profile is still there
+  /// when `run` fails, up to the failing statement
+  pub fn profiling(stmts: Vec<Statement>, config: InterpreterConfig) -> Self {
+    let config = InterpreterConfig {
+      backend: Backend::TreeWalker,
+      ..config
+    };
+
+    Interpreter {
+      profiler: Some(Arc::new(Mutex::new(Profiler::new()))),
+      ..Interpreter::build(stmts, config)
+    }
+  }
+
+  /// What was measured so far, if profiling is on. The total is only known
+  /// once the program ends
+  pub fn profile(&self) -> Option<Profile> {
+    self
+      .profiler
+      .as_ref()
+      .map(|profiler| profiler.lock().profile())
+  }
+
+  pub(crate) fn profile_enter(&mut self, stmt: &Statement) {
+    if let Some(profiler) = &self.profiler {
+      profiler.lock().enter(&stmt.location());
+    }
+  }
+
+  pub(crate) fn profile_exit(&mut self) {
+    if let Some(profiler) = &self.profiler {
+      profiler.lock().exit();
+    }
+  }
+
+  pub(crate) fn profile_call(&mut self, name: &str) {
+    if let Some(profiler) = &self.profiler {
+      profiler.lock().call(name);
+    }
+  }
+
+  pub(crate) fn profile_return(&mut self) {
+    if let Some(profiler) = &self.profiler {
+      profiler.lock().ret();
+    }
+  }
+
+  pub(crate) fn profile_finish(&mut self) {
+    if let Some(profiler) = &self.profiler {
+      profiler.lock().finish();
+    }
+  }
+
+  /// Stops counting time while the program waits for the host
+  pub(crate) fn profile_pause(&mut self) {
+    if let Some(profiler) = &self.profiler {
+      profiler.lock().pause();
+    }
+  }
+
+  pub(crate) fn profile_resume(&mut self) {
+    if let Some(profiler) = &self.profiler {
+      profiler.lock().resume();
+    }
+  }
+}
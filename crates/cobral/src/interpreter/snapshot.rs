@@ -0,0 +1,406 @@
+//! Saves a program waiting for `ler()` input so it can resume later, e.g.
+//! after the app restarts or on another worker of a grading server
+
+use std::{
+  collections::{BTreeMap, HashMap, HashSet},
+  sync::atomic::Ordering,
+};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::shared::ast::{Location, Statement};
+
+use super::{
+  config::{InterpreterConfig, Usage},
+  environment::{Changes, Symbol},
+  host::DeterministicState,
+  value::{List, Value},
+  vm::{compile, Backend, Machine, MachineSnapshot},
+  Interpreter, InterpreterState,
+};
+
+/// Version of the snapshot format. Snapshots of other versions are rejected
+pub const SNAPSHOT_VERSION: u32 = 4;
+
+#[derive(Debug, Clone, Error, PartialEq)]
+pub enum SnapshotError {
+  #[error("Só é possível salvar um programa que está aguardando uma entrada")]
+  NotWaiting,
+  #[error("Só é possível salvar programas executados em bytecode")]
+  UnsupportedBackend,
+  #[error("Versão de snapshot não suportada: {0} (esperada: {SNAPSHOT_VERSION})")]
+  Version(u32),
+  #[error("O snapshot foi gerado para outro programa")]
+  ProgramMismatch,
+  #[error("Snapshot inválido: {0}")]
+  Invalid(String),
+}
+
+/// A suspended session: variables, functions, imported libraries and files,
+/// the position in the program and the pending `ler()`. The program is not
+/// included; it is restored with the same statements, which are checked
+/// against a fingerprint. The host and the config stay with the caller
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+  version: u32,
+  program: u64,
+  location: Location,
+  waiting: u32,
+  input_requests: u32,
+  symbols: SavedSymbols,
+  /// What each function call still running changed, undone when it returns
+  calls: Vec<SavedChanges>,
+  function_depth: usize,
+  libraries: Vec<String>,
+  machine: MachineSnapshot,
+  usage: Usage,
+  /// Random numbers drawn and virtual time of a run with `config.seed`
+  deterministic: Option<DeterministicState>,
+  /// Elements of every list, which values refer to by index
+  lists: Vec<Vec<SavedValue>>,
+}
+
+/// A value as saved in a snapshot. Lists are saved once, in `Snapshot::lists`,
+/// so a list shared by several variables, parameters or lists is still shared
+/// after a restore
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) enum SavedValue {
+  Float(f64),
+  Integer(i64),
+  Boolean(bool),
+  String(String),
+  List(usize),
+  Return(Box<SavedValue>),
+  None,
+  InputPending(u32),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) enum SavedSymbol {
+  Variable(SavedValue),
+  Constant(SavedValue),
+}
+
+pub(crate) type SavedSymbols = BTreeMap<String, SavedSymbol>;
+
+pub(crate) type SavedChanges = BTreeMap<String, Option<SavedSymbol>>;
+
+/// Gives each list an index the first time it is saved
+#[derive(Default)]
+pub(crate) struct ListSaver {
+  indices: HashMap<usize, usize>,
+  lists: Vec<Vec<SavedValue>>,
+}
+
+impl ListSaver {
+  pub(crate) fn value(&mut self, value: &Value) -> SavedValue {
+    match value {
+      Value::Float(n) => SavedValue::Float(*n),
+      Value::Integer(n) => SavedValue::Integer(*n),
+      Value::Boolean(b) => SavedValue::Boolean(*b),
+      Value::String(s) => SavedValue::String(s.clone()),
+      Value::List(list) => SavedValue::List(self.list(list)),
+      Value::Return(value) => SavedValue::Return(Box::new(self.value(value))),
+      Value::None => SavedValue::None,
+      Value::InputPending(id) => SavedValue::InputPending(*id),
+    }
+  }
+
+  pub(crate) fn symbols(&mut self, symbols: &BTreeMap<String, Symbol>) -> SavedSymbols {
+    symbols
+      .iter()
+      .map(|(name, symbol)| (name.clone(), self.symbol(symbol)))
+      .collect()
+  }
+
+  fn changes(&mut self, changes: &Changes) -> SavedChanges {
+    changes
+      .iter()
+      .map(|(name, old)| (name.clone(), old.as_ref().map(|symbol| self.symbol(symbol))))
+      .collect()
+  }
+
+  fn symbol(&mut self, symbol: &Symbol) -> SavedSymbol {
+    match symbol {
+      Symbol::Variable(value) => SavedSymbol::Variable(self.value(value)),
+      Symbol::Constant(value) => SavedSymbol::Constant(self.value(value)),
+    }
+  }
+
+  fn list(&mut self, list: &List) -> usize {
+    if let Some(index) = self.indices.get(&list.address()) {
+      return *index;
+    }
+
+    let index = self.lists.len();
+    self.indices.insert(list.address(), index);
+    self.lists.push(Vec::new());
+
+    let items = list.read().iter().map(|item| self.value(item)).collect();
+    self.lists[index] = items;
+
+    index
+  }
+}
+
+/// The lists of a snapshot, rebuilt before the values that refer to them
+pub(crate) struct ListLoader(Vec<List>);
+
+impl ListLoader {
+  fn new(saved: Vec<Vec<SavedValue>>) -> Result<Self, SnapshotError> {
+    let loader = ListLoader(saved.iter().map(|_| List::default()).collect());
+
+    for (list, items) in loader.0.iter().zip(saved) {
+      let items = items
+        .into_iter()
+        .map(|item| loader.value(item))
+        .collect::<Result<_, _>>()?;
+
+      *list.write() = items;
+    }
+
+    // A list that contains itself would never finish printing
+    let (mut visiting, mut done) = (HashSet::new(), HashSet::new());
+    if let Some(index) =
+      (0..loader.0.len()).find(|index| contains_itself(&loader.0[*index], &mut visiting, &mut done))
+    {
+      return Err(SnapshotError::Invalid(format!(
+        "vetor dentro de si mesmo: {}",
+        index
+      )));
+    }
+
+    Ok(loader)
+  }
+
+  pub(crate) fn symbols(
+    &self,
+    saved: SavedSymbols,
+  ) -> Result<BTreeMap<String, Symbol>, SnapshotError> {
+    saved
+      .into_iter()
+      .map(|(name, symbol)| Ok((name, self.symbol(symbol)?)))
+      .collect()
+  }
+
+  fn changes(&self, saved: SavedChanges) -> Result<Changes, SnapshotError> {
+    saved
+      .into_iter()
+      .map(|(name, old)| Ok((name, old.map(|symbol| self.symbol(symbol)).transpose()?)))
+      .collect()
+  }
+
+  fn symbol(&self, saved: SavedSymbol) -> Result<Symbol, SnapshotError> {
+    Ok(match saved {
+      SavedSymbol::Variable(value) => Symbol::Variable(self.value(value)?),
+      SavedSymbol::Constant(value) => Symbol::Constant(self.value(value)?),
+    })
+  }
+
+  pub(crate) fn value(&self, saved: SavedValue) -> Result<Value, SnapshotError> {
+    Ok(match saved {
+      SavedValue::Float(n) => Value::Float(n),
+      SavedValue::Integer(n) => Value::Integer(n),
+      SavedValue::Boolean(b) => Value::Boolean(b),
+      SavedValue::String(s) => Value::String(s),
+      SavedValue::List(index) => match self.0.get(index) {
+        Some(list) => Value::List(list.clone()),
+        None => {
+          return Err(SnapshotError::Invalid(format!(
+            "vetor inexistente: {}",
+            index
+          )))
+        }
+      },
+      SavedValue::Return(value) => Value::Return(Box::new(self.value(*value)?)),
+      SavedValue::None => Value::None,
+      SavedValue::InputPending(id) => Value::InputPending(id),
+    })
+  }
+}
+
+impl Snapshot {
+  pub fn version(&self) -> u32 {
+    self.version
+  }
+
+  pub fn to_json(&self) -> String {
+    serde_json::to_string(self).expect("snapshots are always serializable")
+  }
+
+  /// Reads a snapshot, checking its version before the rest of the format
+  pub fn from_json(json: &str) -> Result<Self, SnapshotError> {
+    let value: serde_json::Value =
+      serde_json::from_str(json).map_err(|e| SnapshotError::Invalid(e.to_string()))?;
+
+    match value.get("version").and_then(serde_json::Value::as_u64) {
+      Some(version) if version == SNAPSHOT_VERSION as u64 => {}
+      Some(version) => return Err(SnapshotError::Version(version as u32)),
+      None => return Err(SnapshotError::Invalid("versão ausente".into())),
+    }
+
+    serde_json::from_value(value).map_err(|e| SnapshotError::Invalid(e.to_string()))
+  }
+}
+
+/// Whether `list` is nested in itself, searching each list only once
+fn contains_itself(list: &List, visiting: &mut HashSet<usize>, done: &mut HashSet<usize>) -> bool {
+  if done.contains(&list.address()) {
+    return false;
+  }
+
+  if !visiting.insert(list.address()) {
+    return true;
+  }
+
+  let found = list.read().iter().any(|item| match item {
+    Value::List(inner) => contains_itself(inner, visiting, done),
+    _ => false,
+  });
+
+  visiting.remove(&list.address());
+  done.insert(list.address());
+
+  found
+}
+
+impl Interpreter {
+  /// Saves the session while it waits for input
+  pub fn snapshot(&self) -> Result<Snapshot, SnapshotError> {
+    let InterpreterState::Waiting(waiting) = self.state else {
+      return Err(SnapshotError::NotWaiting);
+    };
+
+    let Some(machine) = self.machine.as_ref() else {
+      return Err(SnapshotError::UnsupportedBackend);
+    };
+
+    let libs = self.environment.libs.read();
+    let libraries = self
+      .config
+      .modules
+      .iter()
+      .filter(|module| {
+        !module.functions.is_empty()
+          && module
+            .functions
+            .iter()
+            .all(|function| libs.contains_key(&function.name))
+      })
+      .map(|module| module.name.clone())
+      .collect();
+
+    let mut lists = ListSaver::default();
+    let symbols = self.environment.symbols.read();
+    let calls = symbols
+      .calls()
+      .iter()
+      .map(|changes| lists.changes(changes))
+      .collect();
+    let symbols = lists.symbols(&symbols);
+    let machine = machine.snapshot(&self.functions, &mut lists);
+
+    Ok(Snapshot {
+      version: SNAPSHOT_VERSION,
+      program: fingerprint(&self.statements),
+      location: self.location.clone(),
+      waiting,
+      input_requests: self.environment.input_requests.load(Ordering::Relaxed),
+      symbols,
+      calls,
+      function_depth: self.environment.function_depth(),
+      libraries,
+      machine,
+      usage: self.usage.clone(),
+      deterministic: self.deterministic.as_ref().map(|host| host.state()),
+      lists: lists.lists,
+    })
+  }
+
+  /// Resumes a saved session of the program `stmts`. The interpreter starts
+  /// waiting for the same input, to be given with `provide_input`. Imported
+  /// files are read again
+  pub fn restore(
+    stmts: Vec<Statement>,
+    snapshot: Snapshot,
+    config: InterpreterConfig,
+  ) -> Result<Self, SnapshotError> {
+    if snapshot.version != SNAPSHOT_VERSION {
+      return Err(SnapshotError::Version(snapshot.version));
+    }
+
+    if config.backend != Backend::Bytecode {
+      return Err(SnapshotError::UnsupportedBackend);
+    }
+
+    if snapshot.program != fingerprint(&stmts) {
+      return Err(SnapshotError::ProgramMismatch);
+    }
+
+    let mut interpreter = Interpreter::build(stmts, config);
+
+    for library in &snapshot.libraries {
+      if !interpreter.load_library(library) {
+        return Err(SnapshotError::Invalid(format!(
+          "biblioteca desconhecida: {}",
+          library
+        )));
+      }
+    }
+
+    let modules = snapshot
+      .machine
+      .modules()
+      .iter()
+      .map(|file| interpreter.read_module(file).map(|stmts| compile(&stmts)))
+      .collect::<Result<_, _>>()
+      .map_err(|e| SnapshotError::Invalid(e.to_string()))?;
+
+    let lists = ListLoader::new(snapshot.lists)?;
+    let program = compile(&interpreter.statements);
+    let (machine, functions) = Machine::restore(snapshot.machine, program, modules, &lists)?;
+
+    let environment = &mut interpreter.environment;
+    environment.functions.write().extend(
+      functions
+        .iter()
+        .map(|(name, function)| (name.clone(), function.definition.clone())),
+    );
+    let calls = snapshot
+      .calls
+      .into_iter()
+      .map(|changes| lists.changes(changes))
+      .collect::<Result<_, _>>()?;
+    environment
+      .symbols
+      .write()
+      .replace(lists.symbols(snapshot.symbols)?, calls);
+    environment.set_function_depth(snapshot.function_depth);
+    environment
+      .input_requests
+      .store(snapshot.input_requests, Ordering::Relaxed);
+
+    interpreter.functions = functions;
+    interpreter.machine = Some(machine);
+    interpreter.location = snapshot.location;
+    interpreter.usage = snapshot.usage;
+    if let (Some(host), Some(state)) = (&interpreter.deterministic, snapshot.deterministic) {
+      host.set_state(state);
+    }
+    interpreter.state = InterpreterState::Waiting(snapshot.waiting);
+
+    Ok(interpreter)
+  }
+}
+
+/// FNV-1a hash of the program's JSON encoding, which only changes with the
+/// program or the AST types, not with the build
+fn fingerprint(stmts: &[Statement]) -> u64 {
+  serde_json::to_vec(stmts)
+    .expect("programs are always serializable")
+    .into_iter()
+    .fold(0xcbf29ce484222325, |hash, byte| {
+      (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
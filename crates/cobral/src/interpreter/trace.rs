@@ -0,0 +1,385 @@
+use std::{str::FromStr, sync::Arc};
+
+use parking_lot::Mutex;
+use serde_json::json;
+
+use crate::shared::ast::Statement;
+
+use super::{
+  config::InterpreterConfig,
+  environment::Symbols,
+  error::InterpreterError,
+  value::Value,
+  vm::Backend,
+  watch::{Io, Watch},
+  Interpreter,
+};
+
+/// How a desk-check table is written out
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TraceFormat {
+  Csv,
+  Markdown,
+  Json,
+}
+
+impl FromStr for TraceFormat {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "csv" => Ok(TraceFormat::Csv),
+      "md" | "markdown" => Ok(TraceFormat::Markdown),
+      "json" => Ok(TraceFormat::Json),
+      _ => Err(format!(
+        "Formato desconhecido: {} (use csv, markdown ou json)",
+        s
+      )),
+    }
+  }
+}
+
+/// A variable that changed during a step. `old` is empty when it was declared
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+  pub name: String,
+  pub old: Option<Value>,
+  pub new: Value,
+}
+
+/// One line of the desk-check table
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceRow {
+  pub step: usize,
+  pub line: usize,
+  pub kind: &'static str,
+  pub changes: Vec<Change>,
+  pub output: Vec<String>,
+  pub input: Vec<String>,
+}
+
+impl TraceRow {
+  fn change_of(&self, name: &str) -> Option<&Change> {
+    self.changes.iter().find(|change| change.name == name)
+  }
+}
+
+/// Desk-check ("teste de mesa") table of a run, one row per executed statement
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Trace {
+  pub rows: Vec<TraceRow>,
+}
+
+impl Trace {
+  pub fn export(&self, format: TraceFormat) -> String {
+    match format {
+      TraceFormat::Csv => self.to_csv(),
+      TraceFormat::Markdown => self.to_markdown(),
+      TraceFormat::Json => self.to_json(),
+    }
+  }
+
+  /// Every variable that appears in the table, in the order it first changed
+  pub fn variables(&self) -> Vec<&str> {
+    let mut names: Vec<&str> = Vec::new();
+
+    for change in self.rows.iter().flat_map(|row| &row.changes) {
+      if !names.contains(&change.name.as_str()) {
+        names.push(&change.name);
+      }
+    }
+
+    names
+  }
+
+  pub fn to_csv(&self) -> String {
+    self
+      .table()
+      .iter()
+      .map(|cells| {
+        cells
+          .iter()
+          .map(|cell| csv_field(cell))
+          .collect::<Vec<_>>()
+          .join(",")
+      })
+      .map(|line| line + "\n")
+      .collect()
+  }
+
+  pub fn to_markdown(&self) -> String {
+    let table = self.table();
+    let mut markdown = String::new();
+
+    for (index, cells) in table.iter().enumerate() {
+      let cells: Vec<String> = cells.iter().map(|cell| markdown_cell(cell)).collect();
+      markdown += &format!("| {} |\n", cells.join(" | "));
+
+      if index == 0 {
+        markdown += &format!("|{}\n", "---|".repeat(cells.len()));
+      }
+    }
+
+    markdown
+  }
+
+  pub fn to_json(&self) -> String {
+    let rows: Vec<serde_json::Value> = self
+      .rows
+      .iter()
+      .map(|row| {
+        let changes: Vec<serde_json::Value> = row
+          .changes
+          .iter()
+          .map(|change| {
+            json!({
+              "variavel": change.name,
+              "anterior": change.old.as_ref().map(json_of),
+              "novo": json_of(&change.new),
+            })
+          })
+          .collect();
+
+        json!({
+          "passo": row.step,
+          "linha": row.line,
+          "instrucao": row.kind,
+          "alteracoes": changes,
+          "saida": row.output,
+          "entrada": row.input,
+        })
+      })
+      .collect();
+
+    serde_json::to_string_pretty(&json!({
+      "variaveis": self.variables(),
+      "passos": rows,
+    }))
+    .unwrap_or_default()
+  }
+
+  /// Header and rows with one column per variable, shared by CSV and Markdown
+  fn table(&self) -> Vec<Vec<String>> {
+    let variables = self.variables();
+
+    let mut header = vec!["Passo".to_string(), "Linha".into(), "Instrução".into()];
+    header.extend(variables.iter().map(|name| name.to_string()));
+    header.extend(["Saída".to_string(), "Entrada".into()]);
+
+    let mut table = vec![header];
+
+    for row in &self.rows {
+      let mut cells = vec![row.step.to_string(), row.line.to_string(), row.kind.into()];
+
+      cells.extend(variables.iter().map(|name| {
+        row
+          .change_of(name)
+          .map_or_else(String::new, |change| match &change.old {
+            Some(old) => format!("{} → {}", display(old), display(&change.new)),
+            None => display(&change.new),
+          })
+      }));
+
+      cells.push(row.output.join("\n"));
+      cells.push(row.input.join("\n"));
+      table.push(cells);
+    }
+
+    table
+  }
+}
+
+/// Records the desk-check table while the evaluator runs. Changes between two
+/// statements belong to the innermost statement still running, so a `para`
+/// update shows up as its own row after the body
+pub(crate) struct Tracer {
+  trace: Trace,
+
+  /// Statements being executed with the index of their latest row
+  open: Vec<(usize, usize, &'static str)>,
+  pub(crate) watch: Watch,
+}
+
+impl Tracer {
+  fn new(config: &mut InterpreterConfig) -> Self {
+    Tracer {
+      trace: Trace::default(),
+      open: Vec::new(),
+      watch: Watch::new(config),
+    }
+  }
+
+  /// Moves what happened since the last statement boundary into the table
+  fn flush(&mut self, symbols: &Symbols) {
+    let (writes, io) = self.watch.flush(symbols);
+
+    // Symbols going out of scope don't show in the table
+    let changes: Vec<Change> = writes
+      .into_iter()
+      .filter_map(|write| {
+        Some(Change {
+          name: write.name,
+          old: write.old,
+          new: write.new?,
+        })
+      })
+      .collect();
+
+    let (mut output, mut input) = (Vec::new(), Vec::new());
+    for io in io {
+      match io {
+        Io::Output(text) => output.push(text),
+        Io::Input(text) => input.push(text),
+      }
+    }
+
+    if changes.is_empty() && output.is_empty() && input.is_empty() {
+      return;
+    }
+
+    let Some(&mut (ref mut row, line, kind)) = self.open.last_mut() else {
+      return;
+    };
+
+    // Anything after a nested statement gets a row of its own
+    if *row + 1 != self.trace.rows.len() {
+      *row = self.trace.rows.len();
+      self.trace.rows.push(TraceRow {
+        step: *row + 1,
+        line,
+        kind,
+        changes: Vec::new(),
+        output: Vec::new(),
+        input: Vec::new(),
+      });
+    }
+
+    let row = &mut self.trace.rows[*row];
+
+    for change in changes {
+      match row.changes.iter_mut().find(|c| c.name == change.name) {
+        Some(existing) => existing.new = change.new,
+        None => row.changes.push(change),
+      }
+    }
+
+    row.output.append(&mut output);
+    row.input.append(&mut input);
+  }
+}
+
+impl Interpreter {
+  /// Creates an interpreter that records a desk-check table while it runs.
+  /// Tracing uses the tree-walking backend, which visits every statement
+  pub fn with_trace(
+    stmts: Vec<Statement>,
+    config: InterpreterConfig,
+  ) -> Result<Self, InterpreterError> {
+    let mut interpreter = Interpreter::tracing(stmts, config);
+
+    interpreter.run()?;
+
+    Ok(interpreter)
+  }
+
+  /// Like `with_trace`, without starting the program. The table is still there
+  /// when `run` fails, up to the failing statement
+  pub fn tracing(stmts: Vec<Statement>, config: InterpreterConfig) -> Self {
+    let mut config = InterpreterConfig {
+      backend: Backend::TreeWalker,
+      ..config
+    };
+    let tracer = Tracer::new(&mut config);
+
+    let interpreter = Interpreter {
+      tracer: Some(Arc::new(Mutex::new(tracer))),
+      ..Interpreter::build(stmts, config)
+    };
+    interpreter.environment.symbols.write().watch();
+
+    interpreter
+  }
+
+  /// The desk-check table recorded so far, if tracing is on
+  pub fn trace(&self) -> Option<Trace> {
+    self
+      .tracer
+      .as_ref()
+      .map(|tracer| tracer.lock().trace.clone())
+  }
+
+  pub(crate) fn trace_enter(&mut self, stmt: &Statement) {
+    let Some(tracer) = &self.tracer else {
+      return;
+    };
+
+    self.collect_writes();
+    let mut tracer = tracer.lock();
+    tracer.flush(&self.environment.symbols.read());
+
+    let step = tracer.trace.rows.len();
+    tracer.trace.rows.push(TraceRow {
+      step: step + 1,
+      line: stmt.location().line,
+      kind: stmt.kind(),
+      changes: Vec::new(),
+      output: Vec::new(),
+      input: Vec::new(),
+    });
+    tracer.open.push((step, stmt.location().line, stmt.kind()));
+  }
+
+  pub(crate) fn trace_exit(&mut self) {
+    let Some(tracer) = &self.tracer else {
+      return;
+    };
+
+    self.collect_writes();
+    let mut tracer = tracer.lock();
+    tracer.flush(&self.environment.symbols.read());
+    tracer.open.pop();
+  }
+
+  /// Closes every statement left open, e.g. when the program fails
+  pub(crate) fn trace_finish(&mut self) {
+    let Some(tracer) = &self.tracer else {
+      return;
+    };
+
+    self.collect_writes();
+    let mut tracer = tracer.lock();
+    tracer.flush(&self.environment.symbols.read());
+    tracer.open.clear();
+  }
+}
+
+fn display(value: &Value) -> String {
+  match value {
+    Value::String(s) => format!("\"{}\"", s),
+    _ => value.to_string(),
+  }
+}
+
+fn json_of(value: &Value) -> serde_json::Value {
+  match value {
+    Value::Integer(n) => json!(n),
+    Value::Float(n) => json!(n),
+    Value::Boolean(b) => json!(b),
+    Value::String(s) => json!(s),
+    Value::List(values) => values.read().iter().map(json_of).collect(),
+    Value::Return(value) => json_of(value),
+    Value::None | Value::InputPending(_) => serde_json::Value::Null,
+  }
+}
+
+fn csv_field(cell: &str) -> String {
+  if cell.contains([',', '"', '\n']) {
+    format!("\"{}\"", cell.replace('"', "\"\""))
+  } else {
+    cell.to_string()
+  }
+}
+
+fn markdown_cell(cell: &str) -> String {
+  cell.replace('|', "\\|").replace('\n', "<br>")
+}
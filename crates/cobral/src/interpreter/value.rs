@@ -1,10 +1,15 @@
-#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+use std::{fmt, sync::Arc};
+
+use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum Value {
   Float(f64),
   Integer(i64),
   Boolean(bool),
   String(String),
-  List(Vec<Value>),
+  List(List),
   Return(Box<Value>),
   None,
 
@@ -12,6 +17,34 @@ pub enum Value {
   InputPending(u32),
 }
 
+impl Value {
+  pub fn list(items: Vec<Value>) -> Self {
+    Value::List(List::new(items))
+  }
+
+  /// A value that shares nothing with this one, like `copiar()` returns
+  pub fn deep_copy(&self) -> Value {
+    match self {
+      Value::List(list) => Value::list(list.read().iter().map(Value::deep_copy).collect()),
+      Value::Return(value) => Value::Return(Box::new(value.deep_copy())),
+      value => value.clone(),
+    }
+  }
+
+  /// The CobraL name of the value's type, as used in error messages
+  pub fn type_name(&self) -> &'static str {
+    match self {
+      Value::Integer(_) => "inteiro",
+      Value::Float(_) => "real",
+      Value::String(_) => "texto",
+      Value::Boolean(_) => "lógico",
+      Value::List(_) => "vetor",
+      Value::Return(value) => value.type_name(),
+      Value::None | Value::InputPending(_) => "vazio",
+    }
+  }
+}
+
 impl std::fmt::Display for Value {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     match self {
@@ -25,6 +58,7 @@ impl std::fmt::Display for Value {
         f,
         "[{}]",
         values
+          .read()
           .iter()
           .map(|value| {
             if let Value::String(s) = value {
@@ -40,3 +74,85 @@ impl std::fmt::Display for Value {
     }
   }
 }
+
+/// The elements of a `vetor`. Copies of a list share its elements, so a change
+/// made through one variable or parameter is seen through all of them
+#[derive(Clone, Default)]
+pub struct List(Arc<RwLock<Vec<Value>>>);
+
+impl List {
+  pub fn new(items: Vec<Value>) -> Self {
+    List(Arc::new(RwLock::new(items)))
+  }
+
+  pub fn len(&self) -> usize {
+    self.0.read().len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.0.read().is_empty()
+  }
+
+  pub fn get(&self, index: usize) -> Option<Value> {
+    self.0.read().get(index).cloned()
+  }
+
+  /// The elements as they are now. Lists inside are still shared
+  pub fn items(&self) -> Vec<Value> {
+    self.0.read().clone()
+  }
+
+  pub fn read(&self) -> RwLockReadGuard<'_, Vec<Value>> {
+    self.0.read()
+  }
+
+  pub fn write(&self) -> RwLockWriteGuard<'_, Vec<Value>> {
+    self.0.write()
+  }
+
+  /// Whether both are the same list, not just equal ones
+  pub fn ptr_eq(&self, other: &List) -> bool {
+    Arc::ptr_eq(&self.0, &other.0)
+  }
+
+  /// Tells lists apart while they exist, e.g. to save a shared list only once
+  pub(crate) fn address(&self) -> usize {
+    Arc::as_ptr(&self.0) as *const () as usize
+  }
+
+  /// Whether `other` is this list or is nested in it, which would make a list
+  /// that contains itself
+  pub fn contains(&self, other: &List) -> bool {
+    self.ptr_eq(other)
+      || self.read().iter().any(|item| match item {
+        Value::List(list) => list.contains(other),
+        _ => false,
+      })
+  }
+}
+
+impl PartialEq for List {
+  fn eq(&self, other: &Self) -> bool {
+    self.ptr_eq(other) || *self.read() == *other.read()
+  }
+}
+
+impl fmt::Debug for List {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_list().entries(self.read().iter()).finish()
+  }
+}
+
+/// Only the elements are saved, so lists shared before come back as copies.
+/// Snapshots keep lists in a table instead, which keeps them shared
+impl Serialize for List {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    self.read().serialize(serializer)
+  }
+}
+
+impl<'de> Deserialize<'de> for List {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    Vec::deserialize(deserializer).map(List::new)
+  }
+}
@@ -0,0 +1,113 @@
+use std::sync::Arc;
+
+use crate::{
+  interpreter::{environment::FunctionDef, value::Value},
+  lexer::token::Token,
+  shared::ast::Location,
+};
+
+/// A single VM instruction. Operands are indices into the `constants`,
+/// `names` and `functions` tables of the chunk, or jump targets.
+#[derive(Debug, Clone)]
+pub enum Instruction {
+  /// Pushes `constants[i]`
+  Constant(usize),
+  /// Pushes the value of a symbol
+  Load(usize),
+  /// Pops a value and pushes the element at that index of a list symbol
+  LoadIndex(usize),
+  /// Pops a value into a new or existing variable (`declare x = ...`)
+  Define(usize),
+  /// Pops a value into a new constant
+  DefineConstant(usize),
+  /// Pops a value into an existing variable (`x = ...`)
+  Store(usize),
+  /// Pops an index and a value into an element of a list symbol (`v[i] = ...`)
+  StoreIndex(usize),
+  /// Pops a list and spreads it over several variables
+  Destructure {
+    names: Vec<String>,
+    declare: bool,
+  },
+  /// Adds `delta` to a numeric symbol and pushes its new (prefix) or original value
+  Step {
+    name: usize,
+    delta: i64,
+    prefix: bool,
+  },
+
+  Arithmetic(Token),
+  Comparison(Token),
+  Logical(Token),
+  /// Fails unless the value on top of the stack is a boolean. Checks the left
+  /// side of `e` and `ou` before the right side runs
+  Boolean,
+  Unary(Token),
+  /// Pops `n` values into a list
+  List(usize),
+
+  /// Calls a library or user function with the `argc` values on top of the stack
+  Call {
+    name: usize,
+    argc: usize,
+  },
+  /// Like `Call`, but a user function replaces the function that is running.
+  /// Compiled for `retorne f(...)` outside loops, always followed by `Return`
+  TailCall {
+    name: usize,
+    argc: usize,
+  },
+  /// Fails outside a function. Checked before `retorne` computes its value
+  InFunction,
+  /// Leaves the current function with the value on top of the stack
+  Return,
+  /// Registers `functions[i]`
+  Function(usize),
+  /// Imports a builtin library or a file, named by `constants[i]`
+  Import(usize),
+
+  Jump(usize),
+  /// Jumps backwards to the start of a loop, counting an iteration
+  Loop(usize),
+  /// Pops a condition and jumps when it's false. The message is used when it isn't a boolean.
+  JumpIfFalse(usize, &'static str),
+  /// Pops a condition and jumps when it's true
+  JumpIfTrue(usize),
+  /// Pops a case value and a copy of the `escolha` value, pushing whether they match
+  CaseEquals,
+
+  /// Snapshots the symbols before a loop
+  EnterScope,
+  /// Drops the symbols declared inside a loop, restoring shadowed loop variables
+  ExitScope(Vec<String>),
+  /// Replaces the value on top of the stack by the items to visit and a cursor
+  Iterate,
+  /// Pushes the next item of the iteration below, or jumps when it is over
+  Next(usize),
+
+  /// Counts a statement against the step budget
+  Tick,
+  Dup,
+  Pop,
+  /// Fails with a runtime error, used for code the tree-walker also rejects at runtime
+  Fail(&'static str),
+}
+
+/// Compiled code of a program, module or function body
+#[derive(Debug, Default)]
+pub struct Chunk {
+  pub code: Vec<Instruction>,
+  /// Source location of each instruction, used for errors
+  pub locations: Vec<Location>,
+  pub constants: Vec<Value>,
+  pub names: Vec<String>,
+  pub functions: Vec<Arc<Function>>,
+}
+
+#[derive(Debug)]
+pub struct Function {
+  pub name: String,
+  pub chunk: Arc<Chunk>,
+  /// Kept so that `Environment::functions` stays the same for both backends
+  pub definition: Arc<FunctionDef>,
+}
@@ -0,0 +1,516 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+  interpreter::{environment::FunctionDef, value::Value},
+  shared::ast::{Expression, Location, Statement},
+};
+
+use super::bytecode::{Chunk, Function, Instruction};
+
+/// Compiles a program into a chunk. Compilation never fails: code that the
+/// tree-walker rejects at runtime compiles to an `Instruction::Fail`.
+pub fn compile(stmts: &[Statement]) -> Chunk {
+  let mut compiler = Compiler::default();
+
+  compiler.block(stmts);
+  compiler.chunk
+}
+
+/// Compiles a function body. Without a `retorne`, the function returns the
+/// value of its last statement, like in the tree-walker
+fn compile_function(body: &[Statement]) -> Chunk {
+  let mut compiler = Compiler::default();
+
+  let Some((last, rest)) = body.split_last() else {
+    return compiler.chunk;
+  };
+
+  compiler.block(rest);
+  compiler.location = last.location();
+  compiler.emit(Instruction::Tick);
+
+  if compiler.value_statement(last, true) {
+    compiler.emit(Instruction::Return);
+  } else {
+    compiler.statement(last);
+  }
+
+  compiler.chunk
+}
+
+#[derive(Default)]
+struct Compiler {
+  chunk: Chunk,
+  names: HashMap<String, usize>,
+  location: Location,
+  /// Loops being compiled. A `retorne` inside one is never a tail call
+  loops: usize,
+}
+
+impl Compiler {
+  fn statement(&mut self, stmt: &Statement) {
+    self.location = stmt.location();
+
+    match stmt {
+      Statement::Expression(..)
+      | Statement::Variable { .. }
+      | Statement::Constant { .. }
+      | Statement::Assignment { .. }
+      | Statement::Destructure { .. } => {
+        self.value_statement(stmt, false);
+      }
+      Statement::If {
+        condition,
+        true_block,
+        else_if_blocks,
+        else_block,
+        ..
+      } => self.if_statement(condition, true_block, else_if_blocks, else_block),
+      Statement::While {
+        condition, body, ..
+      } => {
+        let start = self.chunk.code.len();
+        self.expression(condition);
+        let exit = self.emit_jump(Instruction::JumpIfFalse(
+          0,
+          "Condição do loop deve ser booleana",
+        ));
+        self.loop_body(body);
+        self.emit(Instruction::Loop(start));
+        self.patch(exit);
+      }
+      Statement::For {
+        initializer,
+        condition,
+        update,
+        body,
+        ..
+      } => {
+        let loop_variables = match initializer.as_ref() {
+          Statement::Variable { name, .. } => vec![name.clone()],
+          _ => Vec::new(),
+        };
+
+        self.emit(Instruction::EnterScope);
+        self.statement(initializer);
+
+        let start = self.chunk.code.len();
+        self.expression(condition);
+        let exit = self.emit_jump(Instruction::JumpIfFalse(0, "Condição de laço inválida"));
+        self.loop_body(body);
+        self.statement(update);
+        self.emit(Instruction::Loop(start));
+
+        self.patch(exit);
+        self.emit(Instruction::ExitScope(loop_variables));
+      }
+      Statement::ForEach {
+        names,
+        iterable,
+        body,
+        ..
+      } => {
+        self.expression(iterable);
+        self.location = stmt.location(); // Like the tree-walker, errors point to the statement
+        self.emit(Instruction::Iterate);
+        self.emit(Instruction::EnterScope);
+
+        let start = self.chunk.code.len();
+        let exit = self.emit_jump(Instruction::Next(0));
+        match names.as_slice() {
+          [name] => {
+            let name = self.name(name);
+            self.emit(Instruction::Define(name));
+          }
+          _ => self.emit(Instruction::Destructure {
+            names: names.clone(),
+            declare: true,
+          }),
+        }
+        self.loop_body(body);
+        self.emit(Instruction::Loop(start));
+
+        self.patch(exit);
+        self.emit(Instruction::ExitScope(names.clone()));
+        self.emit(Instruction::Pop); // Cursor
+        self.emit(Instruction::Pop); // Items
+      }
+      Statement::Switch {
+        expression,
+        cases,
+        default,
+        ..
+      } => self.switch_statement(expression, cases, default),
+      Statement::Return { value, .. } => {
+        self.emit(Instruction::InFunction);
+
+        match value.as_deref() {
+          Some(Expression::Call {
+            callee,
+            arguments,
+            location,
+          }) if self.loops == 0 => self.call(callee, arguments, location, true),
+          Some(value) => self.expression(value),
+          // Default to 0 instead of None when no return value is provided
+          None => self.constant(Value::Integer(0)),
+        }
+        self.emit(Instruction::Return);
+      }
+      Statement::Function {
+        name, params, body, ..
+      } => {
+        let function = Function {
+          name: name.clone(),
+          chunk: Arc::new(compile_function(body)),
+          definition: Arc::new(FunctionDef {
+            params: params.clone(),
+            body: body.clone(),
+          }),
+        };
+
+        self.chunk.functions.push(Arc::new(function));
+        self.emit(Instruction::Function(self.chunk.functions.len() - 1));
+      }
+      Statement::Import(path, _) => {
+        self.chunk.constants.push(Value::String(path.clone()));
+        self.emit(Instruction::Import(self.chunk.constants.len() - 1));
+      }
+    }
+  }
+
+  fn if_statement(
+    &mut self,
+    condition: &Option<Expression>,
+    true_block: &[Statement],
+    else_if_blocks: &[(Box<Option<Expression>>, Vec<Statement>)],
+    else_block: &Option<Vec<Statement>>,
+  ) {
+    let mut exits = Vec::new();
+
+    let branches = std::iter::once((
+      condition,
+      true_block,
+      "Condição deve ser verdadeiro ou falso",
+    ))
+    .chain(else_if_blocks.iter().map(|(condition, block)| {
+      (
+        condition.as_ref(),
+        block.as_slice(),
+        "Condição em um 'senao se' deve ser verdadeiro ou falso",
+      )
+    }));
+
+    for (condition, block, message) in branches {
+      match condition {
+        Some(condition) => self.expression(condition),
+        None => self.emit(Instruction::Fail("Condição não encontrada")),
+      }
+
+      let next = self.emit_jump(Instruction::JumpIfFalse(0, message));
+      self.block(block);
+      exits.push(self.emit_jump(Instruction::Jump(0)));
+      self.patch(next);
+    }
+
+    if let Some(else_block) = else_block {
+      self.block(else_block);
+    }
+
+    for exit in exits {
+      self.patch(exit);
+    }
+  }
+
+  /// Cases are tested in order and jump to their body. Bodies are laid out in
+  /// the same order, so a case without `pare` falls through to the next one.
+  fn switch_statement(
+    &mut self,
+    expression: &Expression,
+    cases: &[(Box<Expression>, Vec<Statement>, bool)],
+    default: &Option<(Vec<Statement>, bool)>,
+  ) {
+    self.expression(expression);
+
+    let mut bodies = Vec::new();
+    for (value, _, _) in cases {
+      self.emit(Instruction::Dup);
+      self.expression(value);
+      self.emit(Instruction::CaseEquals);
+      bodies.push(self.emit_jump(Instruction::JumpIfTrue(0)));
+    }
+
+    let no_match = self.emit_jump(Instruction::Jump(0));
+
+    let mut exits = Vec::new();
+    for ((_, block, has_break), body) in cases.iter().zip(bodies) {
+      self.patch(body);
+      self.block(block);
+
+      if *has_break {
+        exits.push(self.emit_jump(Instruction::Jump(0)));
+      }
+    }
+    // The default case only runs when nothing matched
+    exits.push(self.emit_jump(Instruction::Jump(0)));
+
+    self.patch(no_match);
+    if let Some((block, _)) = default {
+      self.block(block);
+    }
+
+    for exit in exits {
+      self.patch(exit);
+    }
+    self.emit(Instruction::Pop);
+  }
+
+  /// Statements of a block are counted by the step budget, like in the
+  /// tree-walker. The parts of a `para` header are not
+  /// Compiles the statements that have a value, leaving it on the stack when
+  /// `keep` is set. Returns false, compiling nothing, for the others
+  fn value_statement(&mut self, stmt: &Statement, keep: bool) -> bool {
+    match stmt {
+      Statement::Expression(expr, _) => {
+        self.expression(expr);
+
+        if !keep {
+          self.emit(Instruction::Pop);
+        }
+      }
+      Statement::Variable {
+        name, initializer, ..
+      } => {
+        self.expression(initializer);
+        self.keep(keep);
+        let name = self.name(name);
+        self.emit(Instruction::Define(name));
+      }
+      Statement::Constant {
+        name, initializer, ..
+      } => {
+        self.expression(initializer);
+        self.keep(keep);
+        let name = self.name(name);
+        self.emit(Instruction::DefineConstant(name));
+      }
+      Statement::Assignment {
+        target,
+        index,
+        value,
+        ..
+      } => {
+        let Expression::Identifier(name, _) = target.as_ref() else {
+          self.emit(Instruction::Fail("Atribuição inválida"));
+          return true;
+        };
+
+        self.expression(value);
+        self.keep(keep);
+        let name = self.name(name);
+
+        match index {
+          Some(index) => {
+            self.expression(index);
+            self.emit(Instruction::StoreIndex(name));
+          }
+          None => self.emit(Instruction::Store(name)),
+        }
+      }
+      Statement::Destructure {
+        names,
+        value,
+        declare,
+        location,
+      } => {
+        self.expression(value);
+        self.keep(keep);
+        self.location = location.clone(); // Arity errors point to the statement
+        self.emit(Instruction::Destructure {
+          names: names.clone(),
+          declare: *declare,
+        });
+      }
+      _ => return false,
+    }
+
+    true
+  }
+
+  fn keep(&mut self, keep: bool) {
+    if keep {
+      self.emit(Instruction::Dup);
+    }
+  }
+
+  fn block(&mut self, stmts: &[Statement]) {
+    for stmt in stmts {
+      self.location = stmt.location();
+      self.emit(Instruction::Tick);
+      self.statement(stmt);
+    }
+  }
+
+  fn expression(&mut self, expr: &Expression) {
+    self.location = expr.location();
+
+    match expr {
+      Expression::Integer(n, _) => self.constant(Value::Integer(*n)),
+      Expression::Float(n, _) => self.constant(Value::Float(*n)),
+      Expression::String(s, _) => self.constant(Value::String(s.clone())),
+      Expression::Boolean(b, _) => self.constant(Value::Boolean(*b)),
+      Expression::Nil(_) => self.emit(Instruction::Fail("Expressão não suportada")),
+      Expression::Identifier(name, _) => {
+        let name = self.name(name);
+        self.emit(Instruction::Load(name));
+      }
+      Expression::Arithmetic {
+        left,
+        operator,
+        right,
+        ..
+      } => {
+        self.expression(left);
+        self.expression(right);
+        self.emit(Instruction::Arithmetic(operator.clone()));
+      }
+      Expression::Comparison {
+        left,
+        operator,
+        right,
+        ..
+      } => {
+        self.expression(left);
+        self.expression(right);
+        self.emit(Instruction::Comparison(operator.clone()));
+      }
+      Expression::Logical {
+        left,
+        operator,
+        right,
+        ..
+      } => {
+        self.expression(left);
+        self.emit(Instruction::Boolean);
+        self.expression(right);
+        self.emit(Instruction::Logical(operator.clone()));
+      }
+      Expression::Unary { operator, expr, .. } => {
+        self.expression(expr);
+        self.emit(Instruction::Unary(operator.clone()));
+      }
+      Expression::Call {
+        callee,
+        arguments,
+        location,
+      } => self.call(callee, arguments, location, false),
+      Expression::List(elements, _) => {
+        for element in elements {
+          self.expression(element);
+        }
+
+        self.emit(Instruction::List(elements.len()));
+      }
+      Expression::Index { name, index, .. } => {
+        self.expression(index);
+        let name = self.name(name);
+        self.emit(Instruction::LoadIndex(name));
+      }
+      Expression::PostfixIncrement(expr, _) => self.step(expr, 1, false),
+      Expression::PostfixDecrement(expr, _) => self.step(expr, -1, false),
+      Expression::PrefixIncrement(expr, _) => self.step(expr, 1, true),
+      Expression::PrefixDecrement(expr, _) => self.step(expr, -1, true),
+    }
+  }
+
+  fn call(
+    &mut self,
+    callee: &Expression,
+    arguments: &[Expression],
+    location: &Location,
+    tail: bool,
+  ) {
+    let Expression::Identifier(name, _) = callee else {
+      self.emit(Instruction::Fail("Chamada de função inválida"));
+      return;
+    };
+
+    for argument in arguments {
+      self.expression(argument);
+    }
+
+    self.location = location.clone();
+    let name = self.name(name);
+    let argc = arguments.len();
+
+    match tail {
+      true => self.emit(Instruction::TailCall { name, argc }),
+      false => self.emit(Instruction::Call { name, argc }),
+    }
+  }
+
+  fn loop_body(&mut self, body: &[Statement]) {
+    self.loops += 1;
+    self.block(body);
+    self.loops -= 1;
+  }
+
+  fn step(&mut self, expr: &Expression, delta: i64, prefix: bool) {
+    match expr {
+      Expression::Identifier(name, _) => {
+        let name = self.name(name);
+        self.emit(Instruction::Step {
+          name,
+          delta,
+          prefix,
+        });
+      }
+      _ if delta < 0 => self.emit(Instruction::Fail(
+        "Operador de decremento deve ser aplicado a um símbolo",
+      )),
+      _ => self.emit(Instruction::Fail(
+        "Operador de incremento deve ser aplicado a um símbolo",
+      )),
+    }
+  }
+
+  fn constant(&mut self, value: Value) {
+    self.chunk.constants.push(value);
+    self.emit(Instruction::Constant(self.chunk.constants.len() - 1));
+  }
+
+  fn name(&mut self, name: &str) -> usize {
+    if let Some(index) = self.names.get(name) {
+      return *index;
+    }
+
+    self.chunk.names.push(name.to_string());
+    self
+      .names
+      .insert(name.to_string(), self.chunk.names.len() - 1);
+    self.chunk.names.len() - 1
+  }
+
+  fn emit(&mut self, instruction: Instruction) {
+    self.chunk.code.push(instruction);
+    self.chunk.locations.push(self.location.clone());
+  }
+
+  /// Emits a jump whose target is filled in later by `patch`
+  fn emit_jump(&mut self, instruction: Instruction) -> usize {
+    self.emit(instruction);
+    self.chunk.code.len() - 1
+  }
+
+  /// Points a jump emitted by `emit_jump` to the next instruction
+  fn patch(&mut self, jump: usize) {
+    let target = self.chunk.code.len();
+
+    match &mut self.chunk.code[jump] {
+      Instruction::Jump(to)
+      | Instruction::JumpIfFalse(to, _)
+      | Instruction::JumpIfTrue(to)
+      | Instruction::Next(to) => *to = target,
+      _ => unreachable!("only jumps can be patched"),
+    }
+  }
+}
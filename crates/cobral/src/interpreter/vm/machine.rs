@@ -0,0 +1,716 @@
+use std::{
+  collections::{BTreeMap, HashMap},
+  sync::Arc,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::interpreter::{
+  environment::Symbol,
+  error::InterpreterError,
+  snapshot::{ListLoader, ListSaver, SavedSymbols, SavedValue, SnapshotError},
+  value::Value,
+  Interpreter,
+};
+
+use super::{
+  bytecode::{Chunk, Function, Instruction},
+  compiler,
+};
+
+/// Execution state of the bytecode backend, kept between `run` calls so a
+/// program suspended by `ler()` resumes at the exact instruction
+pub struct Machine {
+  frames: Vec<Frame>,
+  stack: Vec<Value>,
+  /// Symbols saved by `EnterScope`
+  scopes: Vec<BTreeMap<String, Symbol>>,
+  /// Files imported so far, with their compiled code
+  modules: Vec<(String, Arc<Chunk>)>,
+}
+
+struct Frame {
+  chunk: Arc<Chunk>,
+  ip: usize,
+  stack_base: usize,
+  scope_base: usize,
+  kind: FrameKind,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+enum FrameKind {
+  Main,
+  Module,
+  /// Functions see the caller's symbols and undo their changes when they return
+  Function,
+}
+
+/// What the interpreter must do after an instruction
+enum Flow {
+  Continue,
+  Call(Arc<Function>, Vec<Value>),
+  TailCall(Arc<Function>, Vec<Value>),
+  Return(Value),
+  Module(String, Arc<Chunk>),
+  Suspend(u32),
+}
+
+/// Why execution stopped
+pub enum Exit {
+  Completed,
+  Waiting(u32),
+}
+
+impl Machine {
+  pub fn new(chunk: Chunk) -> Self {
+    Machine {
+      frames: vec![Frame {
+        chunk: Arc::new(chunk),
+        ip: 0,
+        stack_base: 0,
+        scope_base: 0,
+        kind: FrameKind::Main,
+      }],
+      stack: Vec::new(),
+      scopes: Vec::new(),
+      modules: Vec::new(),
+    }
+  }
+
+  /// Delivers the result of the `ler()` call the machine is suspended at
+  pub fn resume_with(&mut self, value: Value) {
+    self.stack.push(value);
+  }
+}
+
+/// Where a chunk is: the program itself or an imported file, followed by the
+/// indices of the nested functions that lead to it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct ChunkPath {
+  module: Option<usize>,
+  functions: Vec<usize>,
+}
+
+/// The machine's state without its compiled code, which is compiled again
+/// from the program and the imported files on restore
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct MachineSnapshot {
+  modules: Vec<String>,
+  frames: Vec<FrameSnapshot>,
+  stack: Vec<SavedValue>,
+  scopes: Vec<SavedSymbols>,
+  /// User functions registered so far
+  functions: BTreeMap<String, ChunkPath>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct FrameSnapshot {
+  chunk: ChunkPath,
+  ip: usize,
+  stack_base: usize,
+  scope_base: usize,
+  kind: FrameKind,
+}
+
+impl MachineSnapshot {
+  /// Files to compile again, in import order
+  pub(crate) fn modules(&self) -> &[String] {
+    &self.modules
+  }
+}
+
+impl Machine {
+  pub(crate) fn snapshot(
+    &self,
+    functions: &HashMap<String, Arc<Function>>,
+    lists: &mut ListSaver,
+  ) -> MachineSnapshot {
+    let frames = self
+      .frames
+      .iter()
+      .map(|frame| FrameSnapshot {
+        chunk: self.locate(&frame.chunk),
+        ip: frame.ip,
+        stack_base: frame.stack_base,
+        scope_base: frame.scope_base,
+        kind: frame.kind,
+      })
+      .collect();
+
+    MachineSnapshot {
+      modules: self.modules.iter().map(|(file, _)| file.clone()).collect(),
+      frames,
+      stack: self.stack.iter().map(|value| lists.value(value)).collect(),
+      scopes: self
+        .scopes
+        .iter()
+        .map(|scope| lists.symbols(scope))
+        .collect(),
+      functions: functions
+        .iter()
+        .map(|(name, function)| (name.clone(), self.locate(&function.chunk)))
+        .collect(),
+    }
+  }
+
+  /// Rebuilds a machine from a snapshot, the compiled program and the
+  /// compiled files it had imported, in the same order. Also returns the user
+  /// functions
+  pub(crate) fn restore(
+    mut snapshot: MachineSnapshot,
+    program: Chunk,
+    modules: Vec<Chunk>,
+    lists: &ListLoader,
+  ) -> Result<(Self, HashMap<String, Arc<Function>>), SnapshotError> {
+    let stack = std::mem::take(&mut snapshot.stack)
+      .into_iter()
+      .map(|value| lists.value(value))
+      .collect::<Result<_, _>>()?;
+    let scopes = std::mem::take(&mut snapshot.scopes)
+      .into_iter()
+      .map(|scope| lists.symbols(scope))
+      .collect::<Result<_, _>>()?;
+
+    let (mut machine, functions) =
+      Machine::rebuild(snapshot, program, modules).ok_or(SnapshotError::ProgramMismatch)?;
+    machine.stack = stack;
+    machine.scopes = scopes;
+
+    Ok((machine, functions))
+  }
+
+  /// The frames and functions of a snapshot over the compiled code. `None` if
+  /// the code doesn't match the snapshot
+  fn rebuild(
+    snapshot: MachineSnapshot,
+    program: Chunk,
+    modules: Vec<Chunk>,
+  ) -> Option<(Self, HashMap<String, Arc<Function>>)> {
+    if snapshot.modules.len() != modules.len() {
+      return None;
+    }
+
+    let mut machine = Machine::new(program);
+    machine.modules = snapshot
+      .modules
+      .into_iter()
+      .zip(modules)
+      .map(|(file, chunk)| (file, Arc::new(chunk)))
+      .collect();
+
+    machine.frames = snapshot
+      .frames
+      .into_iter()
+      .map(|frame| {
+        let chunk = match frame.chunk.functions.is_empty() {
+          true => machine.root(frame.chunk.module)?,
+          false => machine.function(&frame.chunk)?.chunk.clone(),
+        };
+
+        (frame.ip <= chunk.code.len()).then_some(Frame {
+          chunk,
+          ip: frame.ip,
+          stack_base: frame.stack_base,
+          scope_base: frame.scope_base,
+          kind: frame.kind,
+        })
+      })
+      .collect::<Option<_>>()?;
+
+    if !matches!(machine.frames.first()?.kind, FrameKind::Main) {
+      return None;
+    }
+
+    let functions = snapshot
+      .functions
+      .iter()
+      .map(|(name, path)| Some((name.clone(), machine.function(path)?)))
+      .collect::<Option<_>>()?;
+
+    Some((machine, functions))
+  }
+
+  fn root(&self, module: Option<usize>) -> Option<Arc<Chunk>> {
+    match module {
+      None => Some(self.frames[0].chunk.clone()),
+      Some(index) => self.modules.get(index).map(|(_, chunk)| chunk.clone()),
+    }
+  }
+
+  fn function(&self, path: &ChunkPath) -> Option<Arc<Function>> {
+    let (last, parents) = path.functions.split_last()?;
+
+    let mut chunk = self.root(path.module)?;
+    for index in parents {
+      chunk = chunk.functions.get(*index)?.chunk.clone();
+    }
+
+    chunk.functions.get(*last).cloned()
+  }
+
+  fn locate(&self, chunk: &Arc<Chunk>) -> ChunkPath {
+    let roots = std::iter::once((None, &self.frames[0].chunk)).chain(
+      self
+        .modules
+        .iter()
+        .enumerate()
+        .map(|(index, (_, chunk))| (Some(index), chunk)),
+    );
+
+    for (module, root) in roots {
+      let mut functions = Vec::new();
+
+      if find(root, chunk, &mut functions) {
+        return ChunkPath { module, functions };
+      }
+    }
+
+    unreachable!("every chunk comes from the program or an imported file")
+  }
+}
+
+/// Searches `target` among `chunk` and its nested functions, leaving the
+/// indices that lead to it in `path`
+fn find(chunk: &Arc<Chunk>, target: &Arc<Chunk>, path: &mut Vec<usize>) -> bool {
+  if Arc::ptr_eq(chunk, target) {
+    return true;
+  }
+
+  for (index, function) in chunk.functions.iter().enumerate() {
+    path.push(index);
+
+    if find(&function.chunk, target, path) {
+      return true;
+    }
+
+    path.pop();
+  }
+
+  false
+}
+
+impl Interpreter {
+  /// Runs the bytecode backend until the program ends or waits for input
+  pub(crate) fn execute(&mut self) -> Result<Exit, InterpreterError> {
+    let mut machine = match self.machine.take() {
+      Some(machine) => machine,
+      None => Machine::new(compiler::compile(&self.statements)),
+    };
+
+    self.start_clock();
+    let result = self.execute_machine(&mut machine);
+    self.stop_clock();
+
+    self.machine = Some(machine);
+
+    result
+  }
+
+  fn execute_machine(&mut self, machine: &mut Machine) -> Result<Exit, InterpreterError> {
+    let mut chunk = self.enter_chunk(machine);
+
+    loop {
+      let frame = machine
+        .frames
+        .last_mut()
+        .expect("the main frame is never popped");
+
+      if frame.ip >= chunk.code.len() {
+        match frame.kind {
+          FrameKind::Main => return Ok(Exit::Completed),
+          FrameKind::Module => {
+            machine.frames.pop();
+          }
+          // Functions without `retorne` return nothing
+          FrameKind::Function => self.return_from_function(machine, Value::None),
+        }
+
+        chunk = self.enter_chunk(machine);
+        continue;
+      }
+
+      let ip = frame.ip;
+      frame.ip += 1;
+
+      let location = &chunk.locations[ip];
+      self.location.line = location.line;
+      self.location.column = location.column;
+
+      match self.step(machine, &chunk, ip)? {
+        Flow::Continue => continue,
+        Flow::Call(function, args) => self.call_function(machine, function, args)?,
+        Flow::TailCall(function, args) => self.replace_function(machine, function, args)?,
+        Flow::Return(value) => self.return_from_function(machine, value),
+        Flow::Module(file, chunk) => {
+          machine.modules.push((file, chunk.clone()));
+          machine.frames.push(Frame {
+            chunk,
+            ip: 0,
+            stack_base: machine.stack.len(),
+            scope_base: machine.scopes.len(),
+            kind: FrameKind::Module,
+          });
+        }
+        Flow::Suspend(callback_id) => return Ok(Exit::Waiting(callback_id)),
+      }
+
+      // The current frame changed
+      chunk = self.enter_chunk(machine);
+    }
+  }
+
+  fn step(
+    &mut self,
+    machine: &mut Machine,
+    chunk: &Chunk,
+    ip: usize,
+  ) -> Result<Flow, InterpreterError> {
+    let stack = &mut machine.stack;
+
+    match &chunk.code[ip] {
+      Instruction::Constant(index) => stack.push(chunk.constants[*index].clone()),
+      Instruction::Load(name) => {
+        stack.push(self.load_symbol(&chunk.names[*name])?);
+      }
+      Instruction::LoadIndex(name) => {
+        let index = pop(stack);
+        stack.push(self.index_symbol(&chunk.names[*name], index)?);
+      }
+      Instruction::Define(name) => {
+        let value = pop(stack);
+        self
+          .environment
+          .define_variable(chunk.names[*name].clone(), value)?;
+      }
+      Instruction::DefineConstant(name) => {
+        let value = pop(stack);
+        self
+          .environment
+          .define_constant(chunk.names[*name].clone(), value)?;
+      }
+      Instruction::Store(name) => {
+        let value = pop(stack);
+        self.assign_symbol(&chunk.names[*name], None, value)?;
+      }
+      Instruction::StoreIndex(name) => {
+        let index = pop(stack);
+        let value = pop(stack);
+        self.assign_symbol(&chunk.names[*name], Some(index), value)?;
+      }
+      Instruction::Destructure { names, declare } => {
+        let value = pop(stack);
+        let location = self.location.clone();
+        self.bind_values(names, value, *declare, &location)?;
+      }
+      Instruction::Step {
+        name,
+        delta,
+        prefix,
+      } => {
+        let (original_value, new_value) = self.step_symbol(&chunk.names[*name], *delta)?;
+        stack.push(if *prefix { new_value } else { original_value });
+      }
+
+      Instruction::Arithmetic(op) => {
+        let (lhs, rhs) = pop_pair(stack);
+        stack.push(self.apply_arithmetic(lhs, op, rhs)?);
+      }
+      Instruction::Comparison(op) => {
+        let (lhs, rhs) = pop_pair(stack);
+        stack.push(self.apply_comparison(lhs, op, rhs)?);
+      }
+      Instruction::Logical(op) => {
+        let (lhs, rhs) = pop_pair(stack);
+        stack.push(self.apply_logical(lhs, op, rhs)?);
+      }
+      Instruction::Boolean => {
+        self.expect_boolean(stack.last().expect("stack underflow"))?;
+      }
+      Instruction::Unary(op) => {
+        let value = pop(stack);
+        stack.push(self.apply_unary(op, value)?);
+      }
+      Instruction::List(count) => {
+        let list = Value::list(stack.split_off(stack.len() - count));
+        self.check_size(&list)?;
+        stack.push(list);
+      }
+
+      Instruction::Call { name, argc } | Instruction::TailCall { name, argc } => {
+        let tail = matches!(chunk.code[ip], Instruction::TailCall { .. });
+        let name = &chunk.names[*name];
+        let args = stack.split_off(stack.len() - argc);
+
+        // Case 1: Built-in library function
+        if let Some(func) = self.environment.get_lib(name) {
+          self.check_output(name, &args)?;
+          let lists = self.lists_to_check(&args);
+          let result = func(args, self.location.clone())?;
+
+          for list in &lists {
+            self.check_size(list)?;
+          }
+
+          return match result {
+            Value::InputPending(callback_id) => Ok(Flow::Suspend(callback_id)),
+            value => {
+              self.check_size(&value)?;
+              stack.push(value);
+              Ok(Flow::Continue)
+            }
+          };
+        }
+
+        // Case 2: User-defined function
+        if let Some(function) = self.functions.get(name).cloned() {
+          if args.len() != function.definition.params.len() {
+            return Err(InterpreterError::ArgumentMismatchError(
+              self.location.clone(),
+              name.clone(),
+            ));
+          }
+
+          // Only a function can be replaced, not the program or an imported file
+          let in_function = machine
+            .frames
+            .last()
+            .is_some_and(|frame| matches!(frame.kind, FrameKind::Function));
+
+          return match tail && in_function {
+            true => Ok(Flow::TailCall(function, args)),
+            false => Ok(Flow::Call(function, args)),
+          };
+        }
+
+        return Err(self.unknown_function_error(name));
+      }
+      Instruction::InFunction => {
+        if !self.environment.is_in_function_scope() {
+          return Err(InterpreterError::EvalError(
+            self.location.clone(),
+            "Comando 'retorne' só pode ser usado dentro de funções".to_string(),
+          ));
+        }
+      }
+      Instruction::Return => return Ok(Flow::Return(pop(stack))),
+      Instruction::Function(index) => {
+        let function = chunk.functions[*index].clone();
+
+        self
+          .environment
+          .functions
+          .write()
+          .insert(function.name.clone(), function.definition.clone());
+        self.functions.insert(function.name.clone(), function);
+      }
+      Instruction::Import(index) => {
+        let Value::String(file) = &chunk.constants[*index] else {
+          unreachable!("imports are compiled with a text constant");
+        };
+
+        if !self.load_library(file) {
+          let stmts = self.read_module(file)?;
+          let chunk = Arc::new(compiler::compile(&stmts));
+          return Ok(Flow::Module(file.clone(), chunk));
+        }
+      }
+
+      Instruction::Jump(target) => self.jump(machine, *target),
+      Instruction::Loop(target) => {
+        self.tick()?;
+
+        self.jump(machine, *target);
+      }
+      Instruction::JumpIfFalse(target, message) => match pop(stack) {
+        Value::Boolean(true) => {}
+        Value::Boolean(false) => self.jump(machine, *target),
+        _ => {
+          return Err(InterpreterError::EvalError(
+            self.location.clone(),
+            message.to_string(),
+          ))
+        }
+      },
+      Instruction::JumpIfTrue(target) => {
+        if let Value::Boolean(true) = pop(stack) {
+          self.jump(machine, *target);
+        }
+      }
+      Instruction::CaseEquals => {
+        let (switch_value, case_value) = pop_pair(stack);
+
+        let matches = match (&switch_value, &case_value) {
+          (Value::Integer(n1), Value::Integer(n2)) => n1 == n2,
+          (Value::Float(f1), Value::Float(f2)) => f1 == f2,
+          (Value::String(s1), Value::String(s2)) => s1 == s2,
+          (Value::Boolean(b1), Value::Boolean(b2)) => b1 == b2,
+          _ => {
+            return Err(InterpreterError::EvalError(
+              self.location.clone(),
+              "Tipos incompatíveis na comparação do switch".to_string(),
+            ))
+          }
+        };
+
+        stack.push(Value::Boolean(matches));
+      }
+
+      Instruction::EnterScope => {
+        let symbols = self.environment.symbols.read().clone();
+        machine.scopes.push(symbols);
+      }
+      Instruction::ExitScope(loop_variables) => {
+        let state = machine.scopes.pop().expect("scopes are balanced");
+        self.restore_loop_scope(loop_variables, state);
+      }
+      Instruction::Iterate => {
+        let iterable = pop(stack);
+        let location = self.location.clone();
+        let items = self.iteration_items(iterable, &location)?;
+
+        stack.push(Value::list(items));
+        stack.push(Value::Integer(0));
+      }
+      Instruction::Next(target) => {
+        let len = stack.len();
+        let (Value::List(items), Value::Integer(cursor)) = (&stack[len - 2], &stack[len - 1])
+        else {
+          unreachable!("`Next` always follows `Iterate`");
+        };
+
+        let cursor = *cursor as usize;
+        match items.get(cursor) {
+          Some(item) => {
+            stack[len - 1] = Value::Integer(cursor as i64 + 1);
+            stack.push(item);
+          }
+          None => self.jump(machine, *target),
+        }
+      }
+
+      Instruction::Tick => self.tick()?,
+      Instruction::Dup => {
+        let value = stack.last().expect("stack underflow").clone();
+        stack.push(value);
+      }
+      Instruction::Pop => {
+        stack.pop();
+      }
+      Instruction::Fail(message) => {
+        return Err(InterpreterError::EvalError(
+          self.location.clone(),
+          message.to_string(),
+        ))
+      }
+    }
+
+    Ok(Flow::Continue)
+  }
+
+  /// The chunk of the current frame. All its instructions come from the same
+  /// file, so only their line and column are copied while it runs
+  fn enter_chunk(&mut self, machine: &Machine) -> Arc<Chunk> {
+    let chunk = current_chunk(machine);
+
+    if let Some(location) = chunk.locations.first() {
+      self.location.file.clone_from(&location.file);
+    }
+
+    chunk
+  }
+
+  fn jump(&self, machine: &mut Machine, target: usize) {
+    machine.frames.last_mut().expect("no frame to jump in").ip = target;
+  }
+
+  fn call_function(
+    &mut self,
+    machine: &mut Machine,
+    function: Arc<Function>,
+    args: Vec<Value>,
+  ) -> Result<(), InterpreterError> {
+    self.check_cancelled()?;
+    self.check_call_depth(self.environment.function_depth() + 1)?;
+
+    // Keep what the call changes, to undo it on return
+    self.environment.symbols.write().enter_call();
+
+    // Set up argument bindings
+    for (param, arg_value) in function.definition.params.iter().zip(args) {
+      self.environment.define_variable(param.clone(), arg_value)?;
+    }
+
+    self.environment.enter_function_scope();
+
+    machine.frames.push(Frame {
+      chunk: function.chunk.clone(),
+      ip: 0,
+      stack_base: machine.stack.len(),
+      scope_base: machine.scopes.len(),
+      kind: FrameKind::Function,
+    });
+
+    Ok(())
+  }
+
+  /// Runs `function` in the frame of the function that is returning its
+  /// result. The frame keeps the caller's symbols, restored when it returns
+  fn replace_function(
+    &mut self,
+    machine: &mut Machine,
+    function: Arc<Function>,
+    args: Vec<Value>,
+  ) -> Result<(), InterpreterError> {
+    self.check_cancelled()?;
+
+    let frame = machine
+      .frames
+      .last_mut()
+      .expect("tail calls happen inside a function");
+    machine.stack.truncate(frame.stack_base);
+    machine.scopes.truncate(frame.scope_base);
+
+    for (param, arg_value) in function.definition.params.iter().zip(args) {
+      self.environment.define_variable(param.clone(), arg_value)?;
+    }
+
+    frame.chunk = function.chunk.clone();
+    frame.ip = 0;
+
+    Ok(())
+  }
+
+  /// Unwinds to the innermost function frame and pushes its result
+  fn return_from_function(&mut self, machine: &mut Machine, value: Value) {
+    while let Some(frame) = machine.frames.pop() {
+      machine.stack.truncate(frame.stack_base);
+      machine.scopes.truncate(frame.scope_base);
+
+      if let FrameKind::Function = frame.kind {
+        self.environment.symbols.write().exit_call();
+        self.environment.exit_function_scope();
+        break;
+      }
+    }
+
+    machine.stack.push(value);
+  }
+}
+
+fn current_chunk(machine: &Machine) -> Arc<Chunk> {
+  let frame = machine
+    .frames
+    .last()
+    .expect("the main frame is never popped");
+  frame.chunk.clone()
+}
+
+fn pop(stack: &mut Vec<Value>) -> Value {
+  stack.pop().expect("stack underflow")
+}
+
+fn pop_pair(stack: &mut Vec<Value>) -> (Value, Value) {
+  let rhs = pop(stack);
+  let lhs = pop(stack);
+  (lhs, rhs)
+}
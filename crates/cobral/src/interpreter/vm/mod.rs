@@ -0,0 +1,20 @@
+//! Bytecode backend. Programs are compiled to a flat list of instructions
+//! and run on a stack machine that shares the `Environment` and library
+//! functions with the tree-walking evaluator.
+
+pub mod bytecode;
+pub mod compiler;
+mod machine;
+
+pub use compiler::compile;
+pub(crate) use machine::{Exit, Machine, MachineSnapshot};
+
+/// How an `Interpreter` executes programs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Backend {
+  /// Compile to bytecode and run it on the stack machine
+  #[default]
+  Bytecode,
+  /// Walk the AST directly, kept for differential testing
+  TreeWalker,
+}
@@ -0,0 +1,212 @@
+//! What the program changed between two statements, for the desk-check trace
+//! and the history
+
+use std::{
+  collections::{BTreeMap, BTreeSet},
+  sync::Arc,
+  time::SystemTime,
+};
+
+use parking_lot::Mutex;
+
+use crate::shared::ast::Expression;
+
+use super::{
+  config::InterpreterConfig,
+  environment::Symbols,
+  host::{Host, HostHandle},
+  value::Value,
+  Interpreter,
+};
+
+/// Something the program printed or read
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Io {
+  Output(String),
+  Input(String),
+}
+
+/// A symbol that took a different value. `new` is empty when it went out of
+/// scope, `old` when it was declared
+pub(crate) struct Write {
+  pub name: String,
+  pub old: Option<Value>,
+  pub new: Option<Value>,
+}
+
+/// Compares only the symbols written since the last statement, instead of
+/// every symbol at every statement
+pub(crate) struct Watch {
+  /// Copies of the values seen last, since lists keep changing afterwards
+  values: BTreeMap<String, Value>,
+  written: BTreeSet<String>,
+  io: Arc<Mutex<Vec<Io>>>,
+}
+
+impl Watch {
+  /// Replaces the host of `config` by one that also keeps what the program
+  /// prints
+  pub(crate) fn new(config: &mut InterpreterConfig) -> Self {
+    let io = Arc::new(Mutex::new(Vec::new()));
+
+    config.host = HostHandle::from(Arc::new(Transcript {
+      inner: config.host.clone(),
+      io: io.clone(),
+    }));
+
+    Watch {
+      values: BTreeMap::new(),
+      written: BTreeSet::new(),
+      io,
+    }
+  }
+
+  /// The symbols that changed since the last call, and what was printed and
+  /// read in the meantime
+  pub(crate) fn flush(&mut self, symbols: &Symbols) -> (Vec<Write>, Vec<Io>) {
+    self.add_aliases(symbols);
+
+    let writes = std::mem::take(&mut self.written)
+      .into_iter()
+      .filter_map(|name| {
+        let new = symbols
+          .get(&name)
+          .map(|symbol| symbol.get_value().deep_copy());
+        let old = match &new {
+          Some(value) => self.values.insert(name.clone(), value.deep_copy()),
+          None => self.values.remove(&name),
+        };
+
+        (old != new).then_some(Write { name, old, new })
+      })
+      .collect();
+
+    (writes, std::mem::take(&mut *self.io.lock()))
+  }
+
+  /// A list changed through one variable also changed for the variables that
+  /// share it or hold it inside theirs
+  fn add_aliases(&mut self, symbols: &Symbols) {
+    let lists: Vec<_> = self
+      .written
+      .iter()
+      .filter_map(|name| match symbols.get(name)?.get_value() {
+        Value::List(list) => Some(list.clone()),
+        _ => None,
+      })
+      .collect();
+
+    if lists.is_empty() {
+      return;
+    }
+
+    for (name, symbol) in symbols.iter() {
+      if let Value::List(list) = symbol.get_value() {
+        if lists.iter().any(|written| list.contains(written)) {
+          self.written.insert(name.clone());
+        }
+      }
+    }
+  }
+}
+
+/// Passes everything to the host, keeping a copy of the output
+struct Transcript {
+  inner: HostHandle,
+  io: Arc<Mutex<Vec<Io>>>,
+}
+
+impl Host for Transcript {
+  fn write_stdout(&self, text: &str) {
+    self.io.lock().push(Io::Output(text.to_string()));
+    self.inner.write_stdout(text);
+  }
+
+  fn write_stderr(&self, text: &str) {
+    self.io.lock().push(Io::Output(text.to_string()));
+    self.inner.write_stderr(text);
+  }
+
+  fn write(&self, text: &str) {
+    self.io.lock().push(Io::Output(text.to_string()));
+    self.inner.write(text);
+  }
+
+  fn request_input(&self, prompt: &str) -> Result<(), String> {
+    self.inner.request_input(prompt)
+  }
+
+  fn now(&self) -> SystemTime {
+    self.inner.now()
+  }
+
+  fn random(&self) -> f64 {
+    self.inner.random()
+  }
+
+  fn flush(&self) {
+    self.inner.flush();
+  }
+}
+
+impl Interpreter {
+  fn is_watched(&self) -> bool {
+    self.tracer.is_some() || self.history.is_some()
+  }
+
+  /// Hands the names written since the last statement to the trace and the
+  /// history
+  pub(crate) fn collect_writes(&self) {
+    if !self.is_watched() {
+      return;
+    }
+
+    let written = self.environment.symbols.write().take_written();
+    if written.is_empty() {
+      return;
+    }
+
+    if let Some(tracer) = &self.tracer {
+      tracer.lock().watch.written.extend(written.iter().cloned());
+    }
+
+    if let Some(recorder) = &self.history {
+      recorder.lock().watch.written.extend(written);
+    }
+  }
+
+  /// Notes the answer given to a `ler()`
+  pub(crate) fn note_input(&self, value: &Value) {
+    let input = Io::Input(value.to_string());
+
+    if let Some(tracer) = &self.tracer {
+      tracer.lock().watch.io.lock().push(input.clone());
+    }
+
+    if let Some(recorder) = &self.history {
+      recorder.lock().watch.io.lock().push(input);
+    }
+  }
+
+  /// Notes that the list in `name` may have changed without an assignment to
+  /// the variable, e.g. one of its elements
+  pub(crate) fn touch(&self, name: &str) {
+    if self.is_watched() {
+      self.environment.symbols.write().touch(name);
+    }
+  }
+
+  /// Notes the variables whose lists are given to a library function, which
+  /// may change them, like `adicionar`
+  pub(crate) fn touch_list_args(&self, args: &[Expression], values: &[Value]) {
+    if !self.is_watched() {
+      return;
+    }
+
+    for (arg, value) in args.iter().zip(values) {
+      if let (Expression::Identifier(name, _), Value::List(_)) = (arg, value) {
+        self.touch(name);
+      }
+    }
+  }
+}
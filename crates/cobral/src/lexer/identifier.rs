@@ -1,3 +1,8 @@
+use unicode_ident::is_xid_continue;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::shared::{ast::Type, dialect::Dialect};
+
 use super::{
   error::LexerError,
   token::{LabeledToken, Token},
@@ -7,6 +12,17 @@ use super::{
 impl<'a> Lexer<'a> {
   pub fn read_identifier(&mut self) -> Result<LabeledToken, LexerError> {
     let id = self.lookup();
+
+    if self.dialect == Dialect::VisuAlg {
+      return Ok(self.visualg_keyword(id));
+    }
+
+    if self.dialect == Dialect::Portugol {
+      if let Some(token) = self.portugol_keyword(&id)? {
+        return Ok(token);
+      }
+    }
+
     match id.as_str() {
       "declare" => {
         self.skip_whitespace(); // Skip any whitespace
@@ -20,23 +36,23 @@ impl<'a> Lexer<'a> {
         }
       }
 
-      "se" => Ok(self.token(Token::If)),          // Keyword "if"
-      "senao" => Ok(self.token(Token::Else)),     // Keyword "else"
+      "se" => Ok(self.token(Token::If)), // Keyword "if"
+      "senao" | "senão" => Ok(self.token(Token::Else)), // Keyword "else"
       "escolha" => Ok(self.token(Token::Switch)), // Keyword "switch"
-      "caso" => Ok(self.token(Token::Case)),      // Keyword "case"
-      "padrao" => Ok(self.token(Token::Default)), // Keyword "default"
+      "caso" => Ok(self.token(Token::Case)), // Keyword "case"
+      "padrao" | "padrão" => Ok(self.token(Token::Default)), // Keyword "default"
 
       "para" => Ok(self.token(Token::For)), // Keyword "for"
       "enquanto" => Ok(self.token(Token::While)), // Keyword "while"
 
-      "nao" => Ok(self.token(Token::Not)), // Logical NOT operator
-      "ou" => Ok(self.token(Token::Or)),   // Logical OR operator
-      "e" => Ok(self.token(Token::And)),   // Logical AND operator
+      "nao" | "não" => Ok(self.token(Token::Not)), // Logical NOT operator
+      "ou" => Ok(self.token(Token::Or)),           // Logical OR operator
+      "e" => Ok(self.token(Token::And)),           // Logical AND operator
 
-      "funcao" => Ok(self.token(Token::Function)), // Keyword "function"
-      "retorne" => Ok(self.token(Token::Return)),  // Keyword "return"
-      "pare" => Ok(self.token(Token::Break)),      // Keyword "break"
-      "importe" => Ok(self.token(Token::Import)),  // Keyword "import"
+      "funcao" | "função" => Ok(self.token(Token::Function)), // Keyword "function"
+      "retorne" => Ok(self.token(Token::Return)),             // Keyword "return"
+      "pare" => Ok(self.token(Token::Break)),                 // Keyword "break"
+      "importe" => Ok(self.token(Token::Import)),             // Keyword "import"
 
       "verdadeiro" => Ok(self.token(Token::True)), // Boolean literal
       "falso" => Ok(self.token(Token::False)),     // Boolean literal
@@ -45,12 +61,102 @@ impl<'a> Lexer<'a> {
     }
   }
 
+  fn portugol_keyword(&mut self, id: &str) -> Result<Option<LabeledToken>, LexerError> {
+    let token = match id {
+      "programa" => Token::Program,
+      "inclua" => Token::Include,
+      "biblioteca" => Token::Library,
+      "const" => Token::Const,
+
+      "inteiro" => Token::Type(Type::Integer),
+      "real" => Token::Type(Type::Float),
+      "cadeia" | "caracter" => Token::Type(Type::String),
+      "logico" | "lógico" => Token::Type(Type::Boolean),
+      "vazio" => Token::Type(Type::Nil),
+
+      "caso" => {
+        self.skip_whitespace();
+
+        match self.peek_identifier().as_str() {
+          "contrario" | "contrário" => {
+            self.next_token()?; // Move past "contrario"
+            Token::Default
+          }
+          _ => Token::Case,
+        }
+      }
+
+      _ => return Ok(None),
+    };
+
+    Ok(Some(self.token(token)))
+  }
+
+  /// VisuAlg keywords and identifiers are case-insensitive
+  fn visualg_keyword(&mut self, id: String) -> LabeledToken {
+    let id = id.to_lowercase();
+
+    let token = match id.as_str() {
+      "algoritmo" => Token::Algorithm,
+      "var" => Token::Var,
+      "inicio" | "início" => Token::Begin,
+      "fimalgoritmo" => Token::EndAlgorithm,
+
+      "se" => Token::If,
+      "entao" | "então" => Token::Then,
+      "senao" | "senão" => Token::Else,
+      "fimse" => Token::EndIf,
+      "escolha" => Token::Switch,
+      "caso" => Token::Case,
+      "outrocaso" => Token::Default,
+      "fimescolha" => Token::EndSwitch,
+
+      "para" => Token::For,
+      "de" => Token::From,
+      "ate" | "até" => Token::To,
+      "passo" => Token::Step,
+      "faca" | "faça" => Token::Do,
+      "fimpara" => Token::EndFor,
+      "enquanto" => Token::While,
+      "fimenquanto" => Token::EndWhile,
+      "repita" => Token::Repeat,
+      "interrompa" => Token::Break,
+
+      "funcao" | "função" => Token::Function,
+      "fimfuncao" | "fimfunção" => Token::EndFunction,
+      "procedimento" => Token::Procedure,
+      "fimprocedimento" => Token::EndProcedure,
+      "retorne" => Token::Return,
+
+      "e" => Token::And,
+      "ou" => Token::Or,
+      "nao" | "não" => Token::Not,
+      "div" => Token::Div,
+      "mod" => Token::Rem,
+
+      "verdadeiro" => Token::True,
+      "falso" => Token::False,
+
+      "vetor" => Token::Vector,
+      "inteiro" => Token::Type(Type::Integer),
+      "real" => Token::Type(Type::Float),
+      "caractere" | "caracter" | "literal" => Token::Type(Type::String),
+      "logico" | "lógico" => Token::Type(Type::Boolean),
+
+      _ => Token::Identifier(id),
+    };
+
+    self.token(token)
+  }
+
+  /// Identifiers are compared in NFC, so `não` typed as `na` followed by a
+  /// combining tilde is still the keyword
   fn lookup(&mut self) -> String {
     let mut id = String::new();
 
     // Only advance while we have valid identifier characters
     while let Some(c) = self.current_char {
-      if c.is_alphanumeric() || c == '_' {
+      if is_xid_continue(c) {
         id.push(c);
         self.advance(); // Advance to the next character
       } else {
@@ -58,7 +164,7 @@ impl<'a> Lexer<'a> {
       }
     }
 
-    id
+    id.nfc().collect()
   }
 
   fn peek_identifier(&mut self) -> String {
@@ -70,7 +176,7 @@ impl<'a> Lexer<'a> {
 
     // Peek ahead without actually consuming characters
     while let Some(c) = self.current_char {
-      if c.is_alphanumeric() || c == '_' {
+      if is_xid_continue(c) {
         id.push(c);
         self.advance(); // Advance while peeking
       } else {
@@ -82,6 +188,6 @@ impl<'a> Lexer<'a> {
     self.pos = saved_position;
     self.current_char = saved_char;
 
-    id
+    id.nfc().collect()
   }
 }
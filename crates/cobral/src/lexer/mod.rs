@@ -4,9 +4,10 @@ mod number;
 mod string;
 pub mod token;
 
-use crate::shared::ast::Location;
+use crate::shared::{ast::Location, dialect::Dialect};
 use error::LexerError;
 use token::{LabeledToken, Token};
+use unicode_ident::is_xid_start;
 
 #[derive(Clone)]
 pub struct Lexer<'a> {
@@ -14,15 +15,21 @@ pub struct Lexer<'a> {
   pos: usize,
   current_char: Option<char>,
   location: Location,
+  dialect: Dialect,
 }
 
 impl<'a> Lexer<'a> {
   pub fn new(input: &'a str) -> Result<Vec<LabeledToken>, LexerError> {
+    Self::with_dialect(input, Dialect::detect(input))
+  }
+
+  pub fn with_dialect(input: &'a str, dialect: Dialect) -> Result<Vec<LabeledToken>, LexerError> {
     Lexer {
       input,
       pos: 0,
       current_char: input.chars().next(),
       location: Location::default(),
+      dialect,
     }
     .run()
   }
@@ -51,6 +58,13 @@ impl<'a> Lexer<'a> {
     }
   }
 
+  fn peek_char(&self) -> Option<char> {
+    self
+      .current_char
+      .and_then(|c| self.input.get(self.pos + c.len_utf8()..))
+      .and_then(|s| s.chars().next())
+  }
+
   fn skip_whitespace(&mut self) {
     while let Some(c) = self.current_char {
       if c.is_whitespace() {
@@ -76,7 +90,8 @@ impl<'a> Lexer<'a> {
 
         '0'..='9' => return Ok(self.read_number()?),
         '"' => return Ok(self.read_string()?),
-        'a'..='z' | 'A'..='Z' | '_' => return Ok(self.read_identifier()?),
+        '\'' if self.dialect == Dialect::Portugol => return self.read_string(),
+        c if c == '_' || is_xid_start(c) => return Ok(self.read_identifier()?),
 
         '[' => {
           self.advance();
@@ -104,12 +119,21 @@ impl<'a> Lexer<'a> {
         }
         ':' => {
           self.advance();
+          if self.dialect == Dialect::VisuAlg && self.current_char == Some('=') {
+            self.advance();
+            return Ok(self.token(Token::Assign));
+          }
+
           return Ok(self.token(Token::Colon));
         }
         ';' => {
           self.advance();
           return Ok(self.token(Token::Semicolon));
         }
+        '=' if self.dialect == Dialect::VisuAlg => {
+          self.advance();
+          return Ok(self.token(Token::Equals));
+        }
         '=' => {
           self.advance();
           if self.current_char == Some('=') {
@@ -123,6 +147,19 @@ impl<'a> Lexer<'a> {
           self.advance();
           return Ok(self.token(Token::Comma));
         }
+        '.' => {
+          self.advance();
+          if self.current_char == Some('.') {
+            self.advance();
+            return Ok(self.token(Token::Range));
+          }
+
+          return Ok(self.token(Token::Dot));
+        }
+        '^' if self.dialect == Dialect::VisuAlg => {
+          self.advance();
+          return Ok(self.token(Token::Caret));
+        }
         '>' => {
           self.advance();
           if self.current_char == Some('=') {
@@ -139,6 +176,20 @@ impl<'a> Lexer<'a> {
             return Ok(self.token(Token::LessEquals));
           }
 
+          if self.dialect == Dialect::VisuAlg {
+            match self.current_char {
+              Some('-') => {
+                self.advance();
+                return Ok(self.token(Token::Assign));
+              }
+              Some('>') => {
+                self.advance();
+                return Ok(self.token(Token::NotEquals));
+              }
+              _ => {}
+            }
+          }
+
           return Ok(self.token(Token::Less));
         }
         '!' => {
@@ -161,6 +212,11 @@ impl<'a> Lexer<'a> {
           self.advance();
           if self.current_char == Some('-') {
             self.advance();
+            if self.dialect == Dialect::Portugol && self.current_char == Some('>') {
+              self.advance();
+              return Ok(self.token(Token::Arrow));
+            }
+
             return Ok(self.token(Token::Decrement));
           }
 
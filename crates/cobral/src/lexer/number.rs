@@ -13,6 +13,11 @@ impl<'a> Lexer<'a> {
         num_str.push(c);
         self.advance();
       } else if c == '.' {
+        // `1..10` is a range, not a real number
+        if self.peek_char() == Some('.') {
+          break;
+        }
+
         if num_str.matches(".").count() > 1 {
           return Err(LexerError::FloatParseError(
             self.location.line,
@@ -7,11 +7,12 @@ use super::{
 impl<'a> Lexer<'a> {
   pub fn read_string(&mut self) -> Result<LabeledToken, LexerError> {
     let mut string = String::new();
+    let quote = self.current_char;
     self.advance(); // Consume the opening quote
 
     while let Some(c) = self.current_char {
       match c {
-        '"' => {
+        c if Some(c) == quote => {
           self.advance(); // Consume the closing quote
           break;
         }
@@ -20,6 +21,7 @@ impl<'a> Lexer<'a> {
           // Handle escape sequences if needed
           match self.current_char {
             Some('"') => string.push('"'),
+            Some('\'') => string.push('\''),
             Some('\\') => string.push('\\'),
             Some('n') => string.push('\n'),
             Some('t') => string.push('\t'),
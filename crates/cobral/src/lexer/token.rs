@@ -1,8 +1,8 @@
 use std::fmt;
 
-use crate::shared::ast::Location;
+use crate::shared::ast::{Location, Type};
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
 pub enum Token {
   // Keywords
   Let,
@@ -19,8 +19,38 @@ pub enum Token {
   Break,
   Import,
 
+  // Portugol Studio keywords
+  Program,
+  Include,
+  Library,
+  Type(Type),
+
+  // VisuAlg keywords
+  Algorithm,
+  Var,
+  Begin,
+  Then,
+  Do,
+  From,
+  To,
+  Step,
+  Repeat,
+  Procedure,
+  Vector,
+  EndAlgorithm,
+  EndIf,
+  EndFor,
+  EndWhile,
+  EndSwitch,
+  EndFunction,
+  EndProcedure,
+
   // Symbols
   Equal,
+  Arrow,  // For `-->`
+  Assign, // For `<-` and `:=`
+  Range,  // For `..`
+  Dot,
 
   // Punctuation
   Semicolon,
@@ -64,6 +94,8 @@ pub enum Token {
   Asterisk,
   Slash,
   Rem,
+  Div,   // Integer division
+  Caret, // Exponentiation
   Increment, // For `++`
   Decrement, // For `--`
 
@@ -102,7 +134,33 @@ impl fmt::Display for Token {
       Token::Return => write!(f, "retorne"),
       Token::Break => write!(f, "pare"),
       Token::Import => write!(f, "importar"),
+      Token::Program => write!(f, "programa"),
+      Token::Include => write!(f, "inclua"),
+      Token::Library => write!(f, "biblioteca"),
+      Token::Type(t) => write!(f, "{}", t),
+      Token::Algorithm => write!(f, "algoritmo"),
+      Token::Var => write!(f, "var"),
+      Token::Begin => write!(f, "inicio"),
+      Token::Then => write!(f, "entao"),
+      Token::Do => write!(f, "faca"),
+      Token::From => write!(f, "de"),
+      Token::To => write!(f, "ate"),
+      Token::Step => write!(f, "passo"),
+      Token::Repeat => write!(f, "repita"),
+      Token::Procedure => write!(f, "procedimento"),
+      Token::Vector => write!(f, "vetor"),
+      Token::EndAlgorithm => write!(f, "fimalgoritmo"),
+      Token::EndIf => write!(f, "fimse"),
+      Token::EndFor => write!(f, "fimpara"),
+      Token::EndWhile => write!(f, "fimenquanto"),
+      Token::EndSwitch => write!(f, "fimescolha"),
+      Token::EndFunction => write!(f, "fimfuncao"),
+      Token::EndProcedure => write!(f, "fimprocedimento"),
       Token::Equal => write!(f, "="),
+      Token::Arrow => write!(f, "-->"),
+      Token::Assign => write!(f, "<-"),
+      Token::Range => write!(f, ".."),
+      Token::Dot => write!(f, "."),
       Token::Semicolon => write!(f, ";"),
       Token::Colon => write!(f, ":"),
       Token::ParenL => write!(f, "("),
@@ -132,6 +190,8 @@ impl fmt::Display for Token {
       Token::Asterisk => write!(f, "*"),
       Token::Slash => write!(f, "/"),
       Token::Rem => write!(f, "%"),
+      Token::Div => write!(f, "div"),
+      Token::Caret => write!(f, "^"),
       Token::Increment => write!(f, "++"),
       Token::Decrement => write!(f, "--"),
       Token::EOF => write!(f, "EOF"),
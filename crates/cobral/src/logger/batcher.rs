@@ -1,12 +1,11 @@
-use once_cell::sync::Lazy;
 use parking_lot::RwLock;
 use std::sync::Arc;
 
-use crate::event::GLOBAL_EVENT_SYSTEM;
+use crate::event::EventSystem;
 
 use super::Payload;
 
-#[derive(Clone)]
+#[derive(Debug, Clone)]
 pub struct LogBatchConfig {
   pub process_threshold: usize,
 }
@@ -19,41 +18,52 @@ impl Default for LogBatchConfig {
   }
 }
 
-static LOG_BATCH: Lazy<Arc<RwLock<Vec<Payload>>>> = Lazy::new(|| Arc::new(RwLock::new(Vec::new())));
-
-static BATCH_CONFIG: Lazy<RwLock<LogBatchConfig>> =
-  Lazy::new(|| RwLock::new(LogBatchConfig::default()));
-
-pub struct LogBatchManager;
+/// Collects the logs of one interpreter and sends them as `process_logs`
+#[derive(Clone, Default)]
+pub struct LogBatchManager {
+  config: LogBatchConfig,
+  batch: Arc<RwLock<Vec<Payload>>>,
+  events: EventSystem,
+}
 
 impl LogBatchManager {
-  pub fn init(config: LogBatchConfig) {
-    *BATCH_CONFIG.write() = config;
+  pub fn new(config: LogBatchConfig, events: EventSystem) -> Self {
+    LogBatchManager {
+      config,
+      batch: Arc::default(),
+      events,
+    }
+  }
+
+  pub fn events(&self) -> &EventSystem {
+    &self.events
   }
 
   pub fn clear(&self) {
-    let mut batch = LOG_BATCH.write();
+    let mut batch = self.batch.write();
     batch.clear();
   }
 
   pub fn add(&self, payload: Payload) {
-    let config = BATCH_CONFIG.read();
-    let mut batch = LOG_BATCH.write();
+    let mut batch = self.batch.write();
 
     batch.push(payload);
 
-    if batch.len() >= config.process_threshold {
+    if batch.len() >= self.config.process_threshold {
       drop(batch);
       self.process_batch();
     }
   }
 
   pub fn process_batch(&self) {
-    let batch_data = LOG_BATCH.read().clone();
+    let batch_data = std::mem::take(&mut *self.batch.write());
+
+    if batch_data.is_empty() {
+      return;
+    }
+
     let batch_data_str =
       serde_json::to_string(&batch_data).expect("Failed to serialize batch data");
-    GLOBAL_EVENT_SYSTEM.emit("process_logs", batch_data_str);
-
-    self.clear();
+    self.events.emit("process_logs", batch_data_str);
   }
 }
@@ -1,32 +1,86 @@
 use super::environment::Environment;
 use super::error::ParserError;
+use super::grammar::{portugol, visualg};
 use super::utils::ParserUtils;
+use crate::interpreter::module::ModuleRegistry;
 use crate::lexer::token::{LabeledToken, Token};
-use crate::shared::ast::Statement;
+use crate::shared::{ast::Statement, dialect::Dialect};
 
 #[allow(dead_code)]
 pub struct Parser {
   tokens: Vec<LabeledToken>,
   pub env: Environment,
   pub current_token: LabeledToken,
+  pub dialect: Dialect,
 }
 
 impl Parser {
   pub fn new(tokens: Vec<LabeledToken>) -> Result<Vec<Statement>, ParserError> {
+    Self::with_modules(tokens, ModuleRegistry::default())
+  }
+
+  /// Parses a program that can import `modules` besides local files
+  pub fn with_modules(
+    tokens: Vec<LabeledToken>,
+    modules: ModuleRegistry,
+  ) -> Result<Vec<Statement>, ParserError> {
+    // The lexer only emits `programa`/`algoritmo` as keywords in their dialects
+    let dialect = match tokens.first().map(|t| &t.token) {
+      Some(Token::Program) => Dialect::Portugol,
+      Some(Token::Algorithm) => Dialect::VisuAlg,
+      _ => Dialect::CobraL,
+    };
+
+    Self::build(tokens, dialect, modules)
+  }
+
+  pub fn with_dialect(
+    tokens: Vec<LabeledToken>,
+    dialect: Dialect,
+  ) -> Result<Vec<Statement>, ParserError> {
+    Self::build(tokens, dialect, ModuleRegistry::default())
+  }
+
+  /// Parses CobraL code that can use what earlier code declared in `env`, and
+  /// declares its own functions and variables there, like the REPL does
+  pub fn with_environment(
+    tokens: Vec<LabeledToken>,
+    env: Environment,
+  ) -> Result<Vec<Statement>, ParserError> {
     Parser {
       tokens: tokens.clone(),
-      env: Environment::new(),
+      env,
       current_token: tokens[0].clone(),
+      dialect: Dialect::CobraL,
+    }
+    .run()
+  }
+
+  fn build(
+    tokens: Vec<LabeledToken>,
+    dialect: Dialect,
+    modules: ModuleRegistry,
+  ) -> Result<Vec<Statement>, ParserError> {
+    Parser {
+      tokens: tokens.clone(),
+      env: Environment::new(modules),
+      current_token: tokens[0].clone(),
+      dialect,
     }
     .run()
   }
 
   fn run(&mut self) -> Result<Vec<Statement>, ParserError> {
+    match self.dialect {
+      Dialect::Portugol => return portugol::parse_program(self),
+      Dialect::VisuAlg => return visualg::parse_program(self),
+      Dialect::CobraL => {}
+    }
+
     let mut items = Vec::new();
 
     while self.current_token.token != Token::EOF {
-      let item = self.parse_statement()?;
-      items.push(item);
+      items.extend(self.parse_statements()?);
       self.try_eat(Token::Semicolon)?;
     }
 
@@ -61,6 +115,11 @@ impl Parser {
   pub fn peek_token(&mut self) -> LabeledToken {
     self.tokens[1].clone()
   }
+
+  /// Tokens that haven't been consumed yet, starting at the current one
+  pub fn remaining_tokens(&self) -> &[LabeledToken] {
+    &self.tokens
+  }
 }
 
 // Implement ParserUtils trait for Parser
@@ -69,7 +128,5 @@ impl ParserUtils for Parser {
     &self.current_token
   }
 
-  fn advance_token(&mut self) {
-    
-  }
+  fn advance_token(&mut self) {}
 }
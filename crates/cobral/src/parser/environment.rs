@@ -1,25 +1,76 @@
 use parking_lot::RwLock;
 use std::{collections::HashMap, sync::Arc};
 
-use crate::shared::ast::Expression;
+use crate::{
+  interpreter::module::ModuleRegistry,
+  shared::ast::{Expression, Type},
+};
 
+/// What the parser knows about a program. Clones share the same maps
+#[derive(Clone)]
 pub struct Environment {
   pub constants: Arc<RwLock<HashMap<String, Option<Expression>>>>,
   pub variables: Arc<RwLock<HashMap<String, Option<Expression>>>>,
   pub functions: Arc<RwLock<HashMap<String, Option<Vec<String>>>>>,
   pub libs: Arc<RwLock<HashMap<String, Vec<String>>>>,
+  // Declared types of typed dialects, e.g. `inteiro x` in Portugol
+  pub types: Arc<RwLock<HashMap<String, Type>>>,
+  // Library aliases, e.g. `inclua biblioteca Matematica --> mat`
+  pub aliases: Arc<RwLock<HashMap<String, String>>>,
+  // First index of vectors that don't start at 0, e.g. `vetor[1..10]` in VisuAlg
+  pub lower_bounds: Arc<RwLock<HashMap<String, i64>>>,
+  // Modules that `importe` can load
+  pub modules: ModuleRegistry,
 }
 
 impl Environment {
-  pub fn new() -> Self {
+  pub fn new(modules: ModuleRegistry) -> Self {
     Self {
       constants: Arc::new(RwLock::new(HashMap::new())),
       variables: Arc::new(RwLock::new(HashMap::new())),
       functions: Arc::new(RwLock::new(HashMap::new())),
       libs: Arc::new(RwLock::new(Self::default_libs())),
+      types: Arc::new(RwLock::new(HashMap::new())),
+      aliases: Arc::new(RwLock::new(HashMap::new())),
+      lower_bounds: Arc::new(RwLock::new(HashMap::new())),
+      modules,
     }
   }
 
+  /// A copy with maps of its own, e.g. to parse a REPL entry that may fail
+  /// halfway without keeping what it declared
+  pub fn fork(&self) -> Self {
+    Self {
+      constants: Arc::new(RwLock::new(self.constants.read().clone())),
+      variables: Arc::new(RwLock::new(self.variables.read().clone())),
+      functions: Arc::new(RwLock::new(self.functions.read().clone())),
+      libs: Arc::new(RwLock::new(self.libs.read().clone())),
+      types: Arc::new(RwLock::new(self.types.read().clone())),
+      aliases: Arc::new(RwLock::new(self.aliases.read().clone())),
+      lower_bounds: Arc::new(RwLock::new(self.lower_bounds.read().clone())),
+      modules: self.modules.clone(),
+    }
+  }
+
+  /// Makes the functions of a module visible to the parser, returning whether
+  /// the module exists
+  pub fn register_lib(&self, name: &str) -> bool {
+    let Some(module) = self.modules.get(name) else {
+      return false;
+    };
+
+    self.libs.write().insert(
+      name.to_string(),
+      module
+        .functions
+        .iter()
+        .map(|function| function.name.clone())
+        .collect(),
+    );
+
+    true
+  }
+
   fn default_libs() -> HashMap<String, Vec<String>> {
     HashMap::from([(
       "io".to_string(),
@@ -27,6 +78,7 @@ impl Environment {
         "escrever".to_string(),
         "erro".to_string(),
         "ler".to_string(),
+        "copiar".to_string(),
       ],
     )])
   }
@@ -1,5 +1,4 @@
 use crate::{
-  interpreter::builtin,
   lexer::token::Token,
   parser::{error::ParserError, expression::ExpressionParser, Parser},
   shared::ast::Expression,
@@ -17,25 +16,26 @@ pub fn parse_function_expr(
       .values()
       .any(|libs| libs.contains(&identifier))
   {
-    let msg = if !builtin::has(&identifier) {
-      &format!("Função desconhecida: {}", identifier)
-    } else {
-      &format!("Verifique se a biblioteca foi importada corretamente.\nEx.: importe \"matematica\"")
+    let msg = match parser.env.modules.module_of(&identifier) {
+      Some(module) => format!(
+        "Verifique se a biblioteca foi importada corretamente.\nEx.: importe \"{}\"",
+        module.name
+      ),
+      None => format!("Função desconhecida: {}", identifier),
     };
 
-    return Err(parser.invalid_expr(msg));
+    return Err(parser.invalid_expr(&msg));
   }
 
+  // The call is located at its '(', which is always on the line of the callee
+  let location = parser.current_token.location.clone();
   parser.eat(Token::ParenL)?; // Consume '('
 
   let args = parser.parse_arguments()?; // Parse function arguments
 
   Ok(Expression::Call {
     arguments: args,
-    callee: Box::new(Expression::Identifier(
-      identifier.clone(),
-      parser.current_token.location.clone(),
-    )),
-    location: parser.current_token.location.clone(),
+    callee: Box::new(Expression::Identifier(identifier.clone(), location.clone())),
+    location,
   })
 }
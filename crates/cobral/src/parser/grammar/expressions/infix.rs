@@ -1,6 +1,7 @@
 use crate::lexer::token::Token;
+use crate::parser::grammar::lowering::{self, MATH_LIB};
 use crate::parser::Parser;
-use crate::shared::ast::Expression;
+use crate::shared::{ast::Expression, dialect::Dialect};
 
 pub fn make_infix_expr(
   parser: &mut Parser,
@@ -27,12 +28,44 @@ pub fn make_infix_expr(
       right: Box::new(rhs),
       location: parser.current_token.location.clone(),
     },
+    // VisuAlg `/` always yields a real, `div` is the integer division
+    Token::Slash if parser.dialect == Dialect::VisuAlg => Expression::Arithmetic {
+      left: Box::new(Expression::Arithmetic {
+        left: Box::new(lhs),
+        operator: Token::Asterisk,
+        right: Box::new(Expression::Float(1.0, parser.current_token.location.clone())),
+        location: parser.current_token.location.clone(),
+      }),
+      operator: op,
+      right: Box::new(rhs),
+      location: parser.current_token.location.clone(),
+    },
+    Token::Div => Expression::Arithmetic {
+      left: Box::new(lhs),
+      operator: Token::Slash,
+      right: Box::new(rhs),
+      location: parser.current_token.location.clone(),
+    },
+    Token::Caret => {
+      parser.env.register_lib(MATH_LIB);
+      lowering::call(
+        "potencia",
+        vec![lhs, rhs],
+        parser.current_token.location.clone(),
+      )
+    }
     Token::Slash => Expression::Arithmetic {
       left: Box::new(lhs),
       operator: op,
       right: Box::new(rhs),
       location: parser.current_token.location.clone(),
     },
+    Token::Rem => Expression::Arithmetic {
+      left: Box::new(lhs),
+      operator: op,
+      right: Box::new(rhs),
+      location: parser.current_token.location.clone(),
+    },
     Token::Equals => Expression::Comparison {
       left: Box::new(lhs),
       operator: op,
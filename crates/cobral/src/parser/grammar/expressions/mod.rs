@@ -16,6 +16,26 @@ pub fn parse_expression(parser: &mut Parser) -> Result<Expression, ParserError>
   parse_expression_bp(parser, 0)
 }
 
+/// Parses `a, b, c` into a list holding every value. A single expression is
+/// returned as is.
+pub fn parse_expression_list(parser: &mut Parser) -> Result<Expression, ParserError> {
+  let first = parse_expression(parser)?;
+
+  if parser.current_token.token != Token::Comma {
+    return Ok(first);
+  }
+
+  let location = first.location();
+  let mut values = vec![first];
+
+  while parser.current_token.token == Token::Comma {
+    parser.eat(Token::Comma)?;
+    values.push(parse_expression(parser)?);
+  }
+
+  Ok(Expression::List(values, location))
+}
+
 fn parse_expression_bp(parser: &mut Parser, min_bp: u8) -> Result<Expression, ParserError> {
   // First parse a primary expression or prefix operator
   let mut lhs = parse_primary_expr(parser)?;
@@ -40,6 +60,9 @@ fn parse_expression_bp(parser: &mut Parser, min_bp: u8) -> Result<Expression, Pa
       | Token::Minus
       | Token::Asterisk
       | Token::Slash
+      | Token::Rem
+      | Token::Div
+      | Token::Caret
       | Token::Equals
       | Token::NotEquals
       | Token::Less
@@ -88,7 +111,8 @@ fn infix_binding_power(op: &Token) -> (u8, u8) {
     Token::Equals | Token::NotEquals => (5, 6),
     Token::Less | Token::Greater | Token::LessEquals | Token::GreaterEquals => (7, 8),
     Token::Plus | Token::Minus => (9, 10),
-    Token::Asterisk | Token::Slash => (11, 12),
+    Token::Asterisk | Token::Slash | Token::Rem | Token::Div => (11, 12),
+    Token::Caret => (14, 13),
     _ => (0, 0),
   }
 }
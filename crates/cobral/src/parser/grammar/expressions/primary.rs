@@ -1,6 +1,10 @@
 use crate::{
   lexer::token::Token,
-  parser::{error::ParserError, Parser},
+  parser::{
+    error::ParserError,
+    grammar::{lowering, portugol},
+    Parser,
+  },
   shared::ast::Expression,
 };
 
@@ -51,11 +55,30 @@ pub fn parse_primary_expr(parser: &mut Parser) -> Result<Expression, ParserError
     }
     Token::Identifier(name) => {
       parser.next_token();
+      // Check if this is a library member, e.g. `mat.raiz(4)`
+      if parser.current_token.token == Token::Dot {
+        return portugol::parse_member_expr(name, parser);
+      }
+
       // Check if this is a function call
       if parser.current_token.token == Token::ParenL {
+        let name = portugol::resolve_builtin(parser, name);
         return function::parse_function_expr(name, parser);
       }
 
+      // Check if this is an index access, e.g. `vetor[0]`
+      if parser.current_token.token == Token::BracketL {
+        parser.next_token();
+        let index = parse_expression(parser)?;
+        parser.eat(Token::BracketR)?;
+
+        return Ok(Expression::Index {
+          index: Box::new(lowering::vector_index(parser, &name, index)),
+          name,
+          location: parser.current_token.location.clone(),
+        });
+      }
+
       Ok(Expression::Identifier(
         name,
         parser.current_token.location.clone(),
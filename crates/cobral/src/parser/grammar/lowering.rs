@@ -0,0 +1,115 @@
+//! Helpers shared by the dialect front-ends to lower typed code into the
+//! CobraL AST.
+
+use crate::{
+  lexer::token::Token,
+  parser::{error::ParserError, Parser},
+  shared::ast::{Expression, Location, Statement, Type},
+};
+
+pub const CONVERSION_LIB: &str = "conversao";
+pub const MATH_LIB: &str = "matematica";
+pub const OUTPUT_LIB: &str = "saida";
+
+/// Lowers `leia(a, b)` into `a = ler()` and `b = ler()`, converting the input
+/// to the declared type of each variable
+pub fn parse_read_stmt(parser: &mut Parser) -> Result<Vec<Statement>, ParserError> {
+  parser.next_token(); // Consume `leia`
+  parser.eat(Token::ParenL)?;
+
+  let mut stmts = Vec::new();
+
+  while parser.current_token.token != Token::ParenR {
+    let (name, index) = match parser.parse_expression()? {
+      Expression::Identifier(name, _) => (name, None),
+      Expression::Index { name, index, .. } => (name, Some(index)),
+      _ => return Err(parser.invalid_expr("'leia' só aceita variáveis")),
+    };
+
+    let location = parser.current_token.location.clone();
+    let declared_type = parser.env.types.read().get(&name).cloned();
+    let value = match (declared_type, &index) {
+      (Some(Type::List(element_type)), Some(_)) => read_expr(parser, &element_type, &location),
+      (Some(declared_type), _) => read_expr(parser, &declared_type, &location),
+      (None, _) => read_expr(parser, &Type::String, &location),
+    };
+
+    stmts.push(Statement::Assignment {
+      target: Box::new(Expression::Identifier(name, location.clone())),
+      index,
+      value: Box::new(value),
+      location,
+    });
+
+    if parser.current_token.token != Token::Comma {
+      break;
+    }
+    parser.eat(Token::Comma)?;
+  }
+
+  parser.eat(Token::ParenR)?;
+
+  Ok(stmts)
+}
+
+/// Builds a call to a builtin function, e.g. `ler()`
+pub fn call(name: &str, arguments: Vec<Expression>, location: Location) -> Expression {
+  Expression::Call {
+    callee: Box::new(Expression::Identifier(name.to_string(), location.clone())),
+    arguments,
+    location,
+  }
+}
+
+/// Value of a variable declared without an initializer
+pub fn scalar_default(declared_type: &Type, location: Location) -> Option<Expression> {
+  match declared_type {
+    Type::Integer => Some(Expression::Integer(0, location)),
+    Type::Float => Some(Expression::Float(0.0, location)),
+    Type::String => Some(Expression::String(String::new(), location)),
+    Type::Boolean => Some(Expression::Boolean(false, location)),
+    _ => None,
+  }
+}
+
+/// Shifts the index of vectors that don't start at 0, e.g. `vetor[1..10]`
+pub fn vector_index(parser: &Parser, name: &str, index: Expression) -> Expression {
+  match parser.env.lower_bounds.read().get(name) {
+    Some(0) | None => index,
+    Some(lower_bound) => Expression::Arithmetic {
+      location: index.location(),
+      right: Box::new(Expression::Integer(*lower_bound, index.location())),
+      left: Box::new(index),
+      operator: Token::Minus,
+    },
+  }
+}
+
+/// Imports for the libraries that lowered code depends on but the source
+/// program never imported explicitly
+pub fn implicit_imports(parser: &Parser, items: &[Statement]) -> Vec<Statement> {
+  [CONVERSION_LIB, MATH_LIB, OUTPUT_LIB]
+    .into_iter()
+    .filter(|lib| parser.env.libs.read().contains_key(*lib))
+    .filter(|lib| {
+      !items
+        .iter()
+        .any(|item| matches!(item, Statement::Import(name, _) if name == lib))
+    })
+    .map(|lib| Statement::Import(lib.to_string(), parser.current_token.location.clone()))
+    .collect()
+}
+
+fn read_expr(parser: &mut Parser, declared_type: &Type, location: &Location) -> Expression {
+  let read = call("ler", Vec::new(), location.clone());
+
+  let conversion = match declared_type {
+    Type::Integer => "int",
+    Type::Float => "real",
+    _ => return read,
+  };
+
+  parser.env.register_lib(CONVERSION_LIB);
+
+  call(conversion, vec![read], location.clone())
+}
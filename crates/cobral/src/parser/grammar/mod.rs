@@ -1,15 +1,45 @@
 use super::{error::ParserError, Parser};
-use crate::shared::ast::{Expression, Statement};
+use crate::shared::{
+  ast::{Expression, Statement},
+  dialect::Dialect,
+};
 
 pub mod expressions;
+pub mod lowering;
+pub mod portugol;
 pub mod statements;
+pub mod visualg;
 
 impl Parser {
   pub fn parse_expression(&mut self) -> Result<Expression, ParserError> {
     expressions::parse_expression(self)
   }
 
+  /// Statements are located where they start, so that a breakpoint on a line
+  /// stops at the statement written there
   pub fn parse_statement(&mut self) -> Result<Statement, ParserError> {
-    statements::parse_statement(self)
+    let location = self.current_token.location.clone();
+    let mut stmt = statements::parse_statement(self)?;
+    stmt.set_location(location);
+
+    Ok(stmt)
+  }
+
+  /// Parses a single source statement, which some dialects lower into
+  /// several AST statements (e.g. `inteiro a, b` in Portugol)
+  pub fn parse_statements(&mut self) -> Result<Vec<Statement>, ParserError> {
+    let location = self.current_token.location.clone();
+    let mut stmts = match self.dialect {
+      Dialect::Portugol => portugol::parse_statements(self)?,
+      Dialect::VisuAlg => visualg::parse_statements(self)?,
+      Dialect::CobraL => vec![statements::parse_statement(self)?],
+    };
+
+    // Lowered groups, like `repita`, keep the locations of their own parts
+    if let [stmt] = stmts.as_mut_slice() {
+      stmt.set_location(location);
+    }
+
+    Ok(stmts)
   }
 }
@@ -0,0 +1,201 @@
+use crate::{
+  lexer::token::Token,
+  parser::{
+    error::ParserError,
+    grammar::lowering::scalar_default,
+    Parser,
+  },
+  shared::ast::{Expression, Statement, Type},
+};
+
+/// Parses `inteiro x`, `real a = 1.5, b`, `cadeia v[3]`, `inteiro v[] = {1, 2}`
+/// and `const inteiro X = 10`. Each declared name becomes its own statement.
+pub fn parse_typed_declarations(parser: &mut Parser) -> Result<Vec<Statement>, ParserError> {
+  let is_constant = parser.current_token.token == Token::Const;
+  if is_constant {
+    parser.eat(Token::Const)?;
+  }
+
+  let base_type = match parse_type_annotation(parser)? {
+    Some(base_type) => base_type,
+    None => return Err(ParserError::UnexpectedToken(parser.current_token.clone())),
+  };
+
+  let mut stmts = Vec::new();
+
+  loop {
+    let name = match &parser.current_token.token {
+      Token::Identifier(name) => name.clone(),
+      _ if is_constant => {
+        return Err(ParserError::ExpectedConstantName(
+          parser.current_token.clone(),
+        ))
+      }
+      _ => {
+        return Err(ParserError::ExpectedVariableName(
+          parser.current_token.clone(),
+        ))
+      }
+    };
+
+    if parser.env.constants.read().contains_key(&name) {
+      return Err(ParserError::ConstantRedeclarationError(
+        parser.current_token.clone(),
+      ));
+    }
+
+    parser.next_token(); // Consume variable name
+
+    let sizes = parse_vector_sizes(parser)?;
+    let declared_type = sizes
+      .iter()
+      .fold(base_type.clone(), |inner, _| Type::List(Box::new(inner)));
+
+    let initializer = if parser.current_token.token == Token::Equal {
+      parser.eat(Token::Equal)?;
+
+      if parser.current_token.token == Token::BraceL {
+        parse_vector_literal(parser)?
+      } else {
+        parser.parse_expression()?
+      }
+    } else {
+      default_value(parser, &base_type, &sizes)?
+    };
+
+    parser.env.types.write().insert(name.clone(), declared_type);
+
+    let location = parser.current_token.location.clone();
+    if is_constant {
+      parser
+        .env
+        .constants
+        .write()
+        .insert(name.clone(), Some(initializer.clone()));
+
+      stmts.push(Statement::Constant {
+        name,
+        initializer: Box::new(initializer),
+        location,
+      });
+    } else {
+      parser
+        .env
+        .variables
+        .write()
+        .insert(name.clone(), Some(initializer.clone()));
+
+      stmts.push(Statement::Variable {
+        name,
+        initializer: Box::new(initializer),
+        location,
+      });
+    }
+
+    if parser.current_token.token != Token::Comma {
+      break;
+    }
+    parser.eat(Token::Comma)?;
+  }
+
+  Ok(stmts)
+}
+
+/// Single typed declaration, used where only one statement fits (e.g. the
+/// initializer of `para (inteiro i = 0; ...)`)
+pub fn parse_variable_stmt(parser: &mut Parser) -> Result<Statement, ParserError> {
+  let mut stmts = parse_typed_declarations(parser)?;
+
+  if stmts.len() != 1 {
+    return Err(parser.invalid_stmt("Declare uma variável por vez neste contexto"));
+  }
+
+  Ok(stmts.remove(0))
+}
+
+/// Consumes a type keyword (`inteiro`, `real`, `cadeia`, ...) if there is one
+pub fn parse_type_annotation(parser: &mut Parser) -> Result<Option<Type>, ParserError> {
+  match &parser.current_token.token {
+    Token::Type(declared_type) => {
+      let declared_type = declared_type.clone();
+      parser.next_token();
+      Ok(Some(declared_type))
+    }
+    _ => Ok(None),
+  }
+}
+
+/// Consumes the `[]` of vector parameters, e.g. `funcao f(inteiro v[])`
+pub fn parse_vector_suffix(parser: &mut Parser, base_type: Type) -> Result<Type, ParserError> {
+  let sizes = parse_vector_sizes(parser)?;
+
+  Ok(
+    sizes
+      .iter()
+      .fold(base_type, |inner, _| Type::List(Box::new(inner))),
+  )
+}
+
+fn parse_vector_sizes(parser: &mut Parser) -> Result<Vec<Option<usize>>, ParserError> {
+  let mut sizes = Vec::new();
+
+  while parser.current_token.token == Token::BracketL {
+    parser.eat(Token::BracketL)?;
+
+    match parser.current_token.token {
+      Token::Integer(size) if size >= 0 => {
+        sizes.push(Some(size as usize));
+        parser.next_token();
+      }
+      Token::BracketR => sizes.push(None),
+      _ => return Err(parser.invalid_expr("Tamanho do vetor deve ser um número inteiro")),
+    }
+
+    parser.eat(Token::BracketR)?;
+  }
+
+  Ok(sizes)
+}
+
+fn parse_vector_literal(parser: &mut Parser) -> Result<Expression, ParserError> {
+  parser.eat(Token::BraceL)?;
+
+  let mut elements = Vec::new();
+  while parser.current_token.token != Token::BraceR {
+    if parser.current_token.token == Token::BraceL {
+      elements.push(parse_vector_literal(parser)?);
+    } else {
+      elements.push(parser.parse_expression()?);
+    }
+
+    if parser.current_token.token != Token::Comma {
+      break;
+    }
+    parser.eat(Token::Comma)?;
+  }
+
+  parser.eat(Token::BraceR)?;
+
+  Ok(Expression::List(
+    elements,
+    parser.current_token.location.clone(),
+  ))
+}
+
+fn default_value(
+  parser: &Parser,
+  base_type: &Type,
+  sizes: &[Option<usize>],
+) -> Result<Expression, ParserError> {
+  let location = parser.current_token.location.clone();
+
+  match sizes.split_first() {
+    Some((Some(size), rest)) => {
+      let element = default_value(parser, base_type, rest)?;
+      Ok(Expression::List(vec![element; *size], location))
+    }
+    Some((None, _)) => Err(parser.invalid_expr("Vetor sem tamanho precisa ser inicializado")),
+    None => scalar_default(base_type, location)
+      .ok_or_else(|| parser.invalid_stmt(&format!("Tipo inválido para variável: {}", base_type))),
+  }
+}
@@ -0,0 +1,130 @@
+use crate::{
+  lexer::token::Token,
+  parser::{
+    error::ParserError,
+    expression::ExpressionParser,
+    grammar::lowering::{CONVERSION_LIB, MATH_LIB, OUTPUT_LIB},
+    Parser,
+  },
+  shared::{ast::Expression, ast::Statement, dialect::Dialect},
+};
+
+/// Parses `inclua biblioteca Matematica --> mat` into an import of the
+/// equivalent CobraL library
+pub fn parse_include_stmt(parser: &mut Parser) -> Result<Statement, ParserError> {
+  parser.eat(Token::Include)?;
+  parser.eat(Token::Library)?;
+
+  let name = match &parser.current_token.token {
+    Token::Identifier(name) => name.clone(),
+    _ => return Err(ParserError::UnexpectedToken(parser.current_token.clone())),
+  };
+
+  let lib = library_name(&name)
+    .ok_or_else(|| parser.invalid_stmt(&format!("Biblioteca desconhecida: {}", name)))?;
+
+  parser.next_token(); // Consume library name
+
+  let alias = if parser.current_token.token == Token::Arrow {
+    parser.eat(Token::Arrow)?;
+
+    match &parser.current_token.token {
+      Token::Identifier(alias) => {
+        let alias = alias.clone();
+        parser.next_token();
+        alias
+      }
+      _ => return Err(ParserError::UnexpectedToken(parser.current_token.clone())),
+    }
+  } else {
+    name
+  };
+
+  parser.env.aliases.write().insert(alias, lib.to_string());
+  parser.env.register_lib(lib);
+
+  Ok(Statement::Import(
+    lib.to_string(),
+    parser.current_token.location.clone(),
+  ))
+}
+
+/// Parses `alias.funcao(...)` and `alias.CONSTANTE` into a library call
+pub fn parse_member_expr(alias: String, parser: &mut Parser) -> Result<Expression, ParserError> {
+  parser.eat(Token::Dot)?;
+
+  let lib = parser
+    .env
+    .aliases
+    .read()
+    .get(&alias)
+    .cloned()
+    .ok_or_else(|| parser.invalid_expr(&format!("Biblioteca desconhecida: {}", alias)))?;
+
+  let member = match &parser.current_token.token {
+    Token::Identifier(member) => member.clone(),
+    _ => return Err(ParserError::UnexpectedToken(parser.current_token.clone())),
+  };
+
+  let name = function_name(&lib, &member);
+  let exists = parser
+    .env
+    .modules
+    .get(&lib)
+    .is_some_and(|module| module.function(name).is_some());
+
+  if !exists {
+    return Err(parser.invalid_expr(&format!(
+      "Função desconhecida na biblioteca {}: {}",
+      alias, member
+    )));
+  }
+
+  parser.next_token(); // Consume member name
+
+  let arguments = if parser.current_token.token == Token::ParenL {
+    parser.eat(Token::ParenL)?;
+    parser.parse_arguments()?
+  } else {
+    Vec::new()
+  };
+
+  Ok(Expression::Call {
+    callee: Box::new(Expression::Identifier(
+      name.to_string(),
+      parser.current_token.location.clone(),
+    )),
+    arguments,
+    location: parser.current_token.location.clone(),
+  })
+}
+
+/// Maps Portugol Studio and VisuAlg builtins to their CobraL names. `escreva`
+/// and `escreval` come from "saida", since `escrever` puts spaces between the
+/// values and always ends the line
+pub fn resolve_builtin(parser: &Parser, name: String) -> String {
+  match (parser.dialect, name.as_str()) {
+    (Dialect::Portugol, "escreva") | (Dialect::VisuAlg, "escreva" | "escreval") => {
+      parser.env.register_lib(OUTPUT_LIB);
+      name
+    }
+    (Dialect::VisuAlg, "raizq") => "raiz".to_string(),
+    _ => name,
+  }
+}
+
+fn library_name(name: &str) -> Option<&'static str> {
+  match name {
+    "Matematica" => Some(MATH_LIB),
+    "Tipos" => Some(CONVERSION_LIB),
+    _ => None,
+  }
+}
+
+fn function_name<'a>(lib: &str, member: &'a str) -> &'a str {
+  match (lib, member) {
+    (CONVERSION_LIB, "cadeia_para_inteiro" | "real_para_inteiro") => "int",
+    (CONVERSION_LIB, "cadeia_para_real" | "inteiro_para_real") => "real",
+    _ => member,
+  }
+}
@@ -0,0 +1,28 @@
+//! Portugol Studio front-end. Programs are lowered to the regular CobraL
+//! AST, so the same `Interpreter` runs them.
+
+mod declarations;
+mod library;
+mod program;
+
+use crate::{
+  lexer::token::Token,
+  parser::{
+    error::ParserError,
+    grammar::{lowering, statements},
+    Parser,
+  },
+  shared::ast::Statement,
+};
+
+pub use declarations::{parse_type_annotation, parse_variable_stmt, parse_vector_suffix};
+pub use library::{parse_member_expr, resolve_builtin};
+pub use program::parse_program;
+
+pub fn parse_statements(parser: &mut Parser) -> Result<Vec<Statement>, ParserError> {
+  match &parser.current_token.token {
+    Token::Type(_) | Token::Const => declarations::parse_typed_declarations(parser),
+    Token::Identifier(name) if name == "leia" => lowering::parse_read_stmt(parser),
+    _ => Ok(vec![statements::parse_statement(parser)?]),
+  }
+}
@@ -0,0 +1,71 @@
+use crate::{
+  lexer::token::Token,
+  parser::{error::ParserError, grammar::lowering, Parser},
+  shared::ast::Statement,
+};
+
+use super::library;
+
+const ENTRY_POINT: &str = "inicio";
+
+/// Parses `programa { ... }`. Functions and global declarations keep their
+/// order, and the body of `funcao inicio()` is appended as top-level code.
+pub fn parse_program(parser: &mut Parser) -> Result<Vec<Statement>, ParserError> {
+  parser.eat(Token::Program)?;
+  parser.eat(Token::BraceL)?;
+
+  // Functions may be called before they are declared, e.g. from `inicio`
+  declare_functions(parser);
+
+  let mut items = Vec::new();
+  let mut entry_point = None;
+
+  while parser.current_token.token != Token::BraceR && parser.current_token.token != Token::EOF {
+    match parser.current_token.token {
+      Token::Include => items.push(library::parse_include_stmt(parser)?),
+      Token::Function => match parser.parse_statement()? {
+        Statement::Function { name, body, .. } if name == ENTRY_POINT => entry_point = Some(body),
+        stmt => items.push(stmt),
+      },
+      _ => items.extend(parser.parse_statements()?),
+    }
+
+    parser.try_eat(Token::Semicolon)?;
+  }
+
+  parser.eat(Token::BraceR)?;
+
+  if parser.current_token.token != Token::EOF {
+    return Err(ParserError::UnexpectedToken(parser.current_token.clone()));
+  }
+
+  let entry_point = entry_point
+    .ok_or_else(|| parser.invalid_stmt(&format!("Função '{}' não encontrada", ENTRY_POINT)))?;
+
+  // e.g. `leia` lowers to `int(ler())`, which lives in "conversao"
+  let imports = lowering::implicit_imports(parser, &items);
+  items.splice(0..0, imports);
+
+  items.extend(entry_point);
+
+  Ok(items)
+}
+
+fn declare_functions(parser: &mut Parser) {
+  let names = parser
+    .remaining_tokens()
+    .windows(3)
+    .filter_map(
+      |window| match (&window[0].token, &window[1].token, &window[2].token) {
+        (Token::Function, Token::Identifier(name), _) => Some(name.clone()),
+        (Token::Function, Token::Type(_), Token::Identifier(name)) => Some(name.clone()),
+        _ => None,
+      },
+    )
+    .collect::<Vec<_>>();
+
+  let mut functions = parser.env.functions.write();
+  for name in names {
+    functions.entry(name).or_insert(None);
+  }
+}
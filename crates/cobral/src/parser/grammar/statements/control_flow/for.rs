@@ -1,11 +1,22 @@
 use crate::{
   lexer::token::Token,
-  parser::{error::ParserError, statement::StatementParser, Parser},
+  parser::{
+    error::ParserError, grammar::statements::declarations::parse_name_list,
+    statement::StatementParser, Parser,
+  },
   shared::ast::{Expression, Statement},
 };
 
 pub fn parse_for_stmt(parser: &mut Parser) -> Result<Statement, ParserError> {
   parser.eat(Token::For)?;
+
+  // `cada` is only a keyword right after `para`
+  if let Token::Identifier(ref name) = parser.current_token.token {
+    if name == "cada" {
+      return parse_for_each_stmt(parser);
+    }
+  }
+
   parser.eat(Token::ParenL)?;
 
   // Parse initializer
@@ -52,3 +63,48 @@ pub fn parse_for_stmt(parser: &mut Parser) -> Result<Statement, ParserError> {
     location: parser.current_token.location.clone(),
   })
 }
+
+/// Parses `para cada (x em lista) { ... }` and `para cada (a, b em pares) { ... }`
+fn parse_for_each_stmt(parser: &mut Parser) -> Result<Statement, ParserError> {
+  parser.next_token(); // Consume `cada`
+  parser.eat(Token::ParenL)?;
+
+  let names = match &parser.current_token.token {
+    Token::Identifier(name) => {
+      let name = name.clone();
+      parser.next_token(); // Consume variable name
+      parse_name_list(name, parser)?
+    }
+    _ => {
+      return Err(ParserError::ExpectedVariableName(
+        parser.current_token.clone(),
+      ))
+    }
+  };
+
+  match &parser.current_token.token {
+    Token::Identifier(name) if name == "em" => parser.next_token(),
+    _ => return Err(parser.invalid_stmt("Era esperado 'em' após a variável do laço")),
+  }
+
+  let iterable = parser
+    .parse_expression()
+    .map_err(|_| parser.invalid_expr("Vetor do laço inválido"))?;
+  parser.eat(Token::ParenR)?;
+
+  {
+    let mut variables = parser.env.variables.write();
+    for name in &names {
+      variables.insert(name.clone(), None);
+    }
+  }
+
+  let body = parser.parse_block()?;
+
+  Ok(Statement::ForEach {
+    names,
+    iterable: Box::new(iterable),
+    body,
+    location: parser.current_token.location.clone(),
+  })
+}
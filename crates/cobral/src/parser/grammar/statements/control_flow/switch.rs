@@ -37,13 +37,12 @@ pub fn parse_switch_stmt(parser: &mut Parser) -> Result<Statement, ParserError>
           parser.current_token.token,
           Token::Case | Token::Default | Token::BraceR | Token::Break
         ) {
-          let item = parser.parse_statement()?;
-          block_items.push(item);
+          block_items.extend(parser.parse_statements()?);
         }
 
         if parser.current_token.token == Token::Break {
           parser.eat(Token::Break)?;
-          parser.eat(Token::Semicolon)?;
+          parser.try_eat(Token::Semicolon)?;
           has_break = true;
         }
 
@@ -60,13 +59,12 @@ pub fn parse_switch_stmt(parser: &mut Parser) -> Result<Statement, ParserError>
           parser.current_token.token,
           Token::Case | Token::Default | Token::BraceR | Token::Break
         ) {
-          let item = parser.parse_statement()?;
-          block_items.push(item);
+          block_items.extend(parser.parse_statements()?);
         }
 
         if parser.current_token.token == Token::Break {
           parser.eat(Token::Break)?;
-          parser.eat(Token::Semicolon)?;
+          parser.try_eat(Token::Semicolon)?;
           has_break = true;
         }
 
@@ -0,0 +1,62 @@
+use crate::{
+  lexer::token::{LabeledToken, Token},
+  parser::{error::ParserError, grammar::expressions::parse_expression_list, Parser},
+  shared::ast::Statement,
+};
+
+/// Parses the remaining names of `a, b, c`, given the first one was already consumed
+pub fn parse_name_list(first: String, parser: &mut Parser) -> Result<Vec<String>, ParserError> {
+  let mut names = vec![first];
+
+  while parser.current_token.token == Token::Comma {
+    parser.eat(Token::Comma)?;
+
+    match &parser.current_token.token {
+      Token::Identifier(name) => names.push(name.clone()),
+      _ => {
+        return Err(ParserError::ExpectedVariableName(
+          parser.current_token.clone(),
+        ))
+      }
+    }
+
+    parser.next_token(); // Consume variable name
+  }
+
+  Ok(names)
+}
+
+/// Parses `= valor` (or `= a, b`) after the names of `declare a, b` and `a, b`
+pub fn parse_destructure_stmt(
+  names: Vec<String>,
+  declare: bool,
+  parser: &mut Parser,
+) -> Result<Statement, ParserError> {
+  for name in &names {
+    if parser.env.constants.read().contains_key(name) {
+      return Err(ParserError::ConstantRedeclarationError(LabeledToken {
+        token: Token::Identifier(name.clone()),
+        location: parser.current_token.location.clone(),
+      }));
+    }
+  }
+
+  parser.eat(Token::Equal)?; // Consume `=`
+
+  let location = parser.current_token.location.clone();
+  let value = parse_expression_list(parser)?;
+
+  if declare {
+    let mut variables = parser.env.variables.write();
+    for name in &names {
+      variables.insert(name.clone(), None);
+    }
+  }
+
+  Ok(Statement::Destructure {
+    names,
+    value: Box::new(value),
+    declare,
+    location,
+  })
+}
@@ -1,11 +1,12 @@
 use crate::{
   lexer::token::Token,
-  parser::{error::ParserError, statement::StatementParser, Parser},
+  parser::{error::ParserError, grammar::portugol, statement::StatementParser, Parser},
   shared::ast::Statement,
 };
 
 pub fn parse_function_stmt(parser: &mut Parser) -> Result<Statement, ParserError> {
   parser.eat(Token::Function)?;
+  portugol::parse_type_annotation(parser)?; // Portugol return type, e.g. `funcao inteiro f()`
 
   let name = match &parser.current_token.token {
     Token::Identifier(name) => name.clone(),
@@ -20,11 +21,23 @@ pub fn parse_function_stmt(parser: &mut Parser) -> Result<Statement, ParserError
   // Parse parameters
   parser.eat(Token::ParenL)?;
   let mut params = Vec::new();
-  while let Token::Identifier(param) = &parser.current_token.token {
+  loop {
+    let param_type = portugol::parse_type_annotation(parser)?;
+
+    let Token::Identifier(param) = &parser.current_token.token else {
+      break;
+    };
+
+    let param = param.clone();
     params.push(param.clone());
     parser.env.variables.write().insert(param.clone(), None);
 
     parser.next_token();
+    if let Some(param_type) = param_type {
+      let param_type = portugol::parse_vector_suffix(parser, param_type)?;
+      parser.env.types.write().insert(param, param_type);
+    }
+
     if parser.current_token.token != Token::Comma {
       break;
     }
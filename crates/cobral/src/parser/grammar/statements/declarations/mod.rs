@@ -1,9 +1,11 @@
 mod assignment;
 mod constant;
+mod destructure;
 mod function;
 mod variable;
 
-pub use assignment::parse_assignment_stmt;
+pub use assignment::{parse_assignment_stmt, parse_index_assignment_stmt};
 pub use constant::parse_const_stmt;
+pub use destructure::{parse_destructure_stmt, parse_name_list};
 pub use function::parse_function_stmt;
 pub use variable::parse_variable_stmt;
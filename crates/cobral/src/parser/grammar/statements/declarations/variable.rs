@@ -4,6 +4,8 @@ use crate::{
   shared::ast::Statement,
 };
 
+use super::{parse_destructure_stmt, parse_name_list};
+
 pub fn parse_variable_stmt(parser: &mut Parser) -> Result<Statement, ParserError> {
   parser.eat(Token::Let)?; // Consume `let`
 
@@ -17,6 +19,13 @@ pub fn parse_variable_stmt(parser: &mut Parser) -> Result<Statement, ParserError
   };
 
   parser.next_token(); // Consume variable name
+
+  // `declare a, b = ...`
+  if parser.current_token.token == Token::Comma {
+    let names = parse_name_list(name, parser)?;
+    return parse_destructure_stmt(names, true, parser);
+  }
+
   parser.eat(Token::Equal)?; // Consume `=`
 
   let expr = parser.parse_expression()?;
@@ -1,10 +1,12 @@
 use crate::{
   lexer::token::Token,
   parser::{error::ParserError, grammar::expressions::parse_expression, Parser},
-  shared::ast::Statement,
+  shared::ast::{Expression, Statement},
 };
 
-use super::declarations::parse_assignment_stmt;
+use super::declarations::{
+  parse_assignment_stmt, parse_destructure_stmt, parse_index_assignment_stmt, parse_name_list,
+};
 
 pub fn parse_identifier_stmt(parser: &mut Parser) -> Result<Statement, ParserError> {
   match parser.current_token.token {
@@ -17,6 +19,25 @@ pub fn parse_identifier_stmt(parser: &mut Parser) -> Result<Statement, ParserErr
           parser.next_token(); // Now consume the identifier
           parse_assignment_stmt(identifier, parser)
         }
+        // `a, b = b, a`
+        Token::Comma => {
+          parser.next_token(); // Consume the first name
+          let names = parse_name_list(identifier, parser)?;
+          parse_destructure_stmt(names, false, parser)
+        }
+        Token::BracketL => {
+          let expr = parse_expression(parser)?;
+
+          match (expr, &parser.current_token.token) {
+            (Expression::Index { name, index, .. }, Token::Equal) => {
+              parse_index_assignment_stmt(name, *index, parser)
+            }
+            (expr, _) => Ok(Statement::Expression(
+              expr,
+              parser.current_token.location.clone(),
+            )),
+          }
+        }
         _ => parse_expression(parser)
           .map(|expr| Statement::Expression(expr, parser.current_token.location.clone())),
       }
@@ -1,7 +1,6 @@
 use std::path::Path;
 
 use crate::{
-  interpreter::builtin,
   lexer::token::Token,
   parser::{error::ParserError, Parser},
   shared::ast::Statement,
@@ -19,23 +18,13 @@ pub fn parse_import_stmt(parser: &mut Parser) -> Result<Statement, ParserError>
 
   let path = Path::new(&file_path);
 
-  if !path.exists() && builtin::load(&file_path).is_none() {
+  if !parser.env.register_lib(&file_path) && !path.exists() {
     return Err(parser.invalid_stmt(&format!(
       "Erro ao carregar o arquivo: \"{}\". Verifique o caminho ou as permissões.",
       file_path
     )));
   }
 
-  if !builtin::load(&file_path).is_none() {
-    parser.env.libs.write().insert(
-      file_path.clone().to_string(),
-      builtin::get_lib_funcs(&file_path)
-        .into_iter()
-        .map(String::from)
-        .collect(),
-    );
-  }
-
   parser.next_token(); // Move past the string token
 
   // Optional semicolon
@@ -4,7 +4,7 @@ use crate::{
   shared::ast::Statement,
 };
 
-use super::expressions::parse_expression;
+use super::{expressions::parse_expression, portugol};
 
 mod control_flow;
 mod declarations;
@@ -24,6 +24,7 @@ pub fn parse_statement(parser: &mut Parser) -> Result<Statement, ParserError> {
     Token::Import => import::parse_import_stmt(parser),
     Token::Return => r#return::parse_return_stmt(parser),
     Token::Identifier(_) => identifier::parse_identifier_stmt(parser),
+    Token::Type(_) => portugol::parse_variable_stmt(parser),
     _ => parse_expression(parser)
       .map(|expr| Statement::Expression(expr, parser.current_token.location.clone())),
   }
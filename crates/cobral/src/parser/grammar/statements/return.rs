@@ -1,6 +1,8 @@
 use crate::{
   lexer::token::Token,
-  parser::{error::ParserError, utils::ParserUtils, Parser},
+  parser::{
+    error::ParserError, grammar::expressions::parse_expression_list, utils::ParserUtils, Parser,
+  },
   shared::ast::Statement,
 };
 
@@ -12,8 +14,8 @@ pub fn parse_return_stmt(parser: &mut Parser) -> Result<Statement, ParserError>
     // No expression after 'retorne', it's just 'retorne;'
     None
   } else {
-    // There is an expression after 'retorne'
-    Some(Box::new(parser.parse_expression()?))
+    // There is an expression after 'retorne', or several (`retorne a, b`)
+    Some(Box::new(parse_expression_list(parser)?))
   };
 
   Ok(Statement::Return {
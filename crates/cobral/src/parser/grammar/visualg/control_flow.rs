@@ -0,0 +1,265 @@
+use crate::{
+  lexer::token::Token,
+  parser::{error::ParserError, Parser},
+  shared::ast::{Expression, Location, Statement},
+};
+
+use super::parse_block_until;
+
+/// `se cond entao ... [senao ...] fimse`
+pub fn parse_if_stmt(parser: &mut Parser) -> Result<Statement, ParserError> {
+  parser.eat(Token::If)?;
+  let condition = parser
+    .parse_expression()
+    .map_err(|_| parser.invalid_expr("Condição inválida"))?;
+  parser.eat(Token::Then)?;
+
+  let true_block = parse_block_until(parser, &[Token::Else, Token::EndIf])?;
+
+  let else_block = if parser.current_token.token == Token::Else {
+    parser.eat(Token::Else)?;
+    Some(parse_block_until(parser, &[Token::EndIf])?)
+  } else {
+    None
+  };
+
+  parser.eat(Token::EndIf)?;
+
+  Ok(Statement::If {
+    condition: Box::new(Some(condition)),
+    true_block,
+    else_if_blocks: Vec::new(),
+    else_block,
+    location: parser.current_token.location.clone(),
+  })
+}
+
+/// `para i de 1 ate 10 [passo 2] faca ... fimpara`. The counter must be
+/// declared in a `var` section and keeps its last value after the loop.
+pub fn parse_for_stmt(parser: &mut Parser) -> Result<Statement, ParserError> {
+  parser.eat(Token::For)?;
+
+  let counter = match &parser.current_token.token {
+    Token::Identifier(name) => name.clone(),
+    _ => {
+      return Err(ParserError::ExpectedVariableName(
+        parser.current_token.clone(),
+      ))
+    }
+  };
+  parser.next_token();
+
+  parser.eat(Token::From)?;
+  let start = parser.parse_expression()?;
+  parser.eat(Token::To)?;
+  let end = parser.parse_expression()?;
+
+  let step = if parser.current_token.token == Token::Step {
+    parser.eat(Token::Step)?;
+    Some(parser.parse_expression()?)
+  } else {
+    None
+  };
+
+  parser.eat(Token::Do)?;
+  let body = parse_block_until(parser, &[Token::EndFor])?;
+  parser.eat(Token::EndFor)?;
+
+  let location = parser.current_token.location.clone();
+  let counter_expr = Expression::Identifier(counter.clone(), location.clone());
+  let step = step.unwrap_or_else(|| Expression::Integer(1, location.clone()));
+
+  let condition = match step_direction(&step) {
+    Some(true) => comparison(counter_expr.clone(), Token::LessEquals, end, &location),
+    Some(false) => comparison(counter_expr.clone(), Token::GreaterEquals, end, &location),
+    // The sign of the step is only known at runtime
+    None => Expression::Logical {
+      left: Box::new(Expression::Logical {
+        left: Box::new(comparison(
+          step.clone(),
+          Token::GreaterEquals,
+          Expression::Integer(0, location.clone()),
+          &location,
+        )),
+        operator: Token::And,
+        right: Box::new(comparison(
+          counter_expr.clone(),
+          Token::LessEquals,
+          end.clone(),
+          &location,
+        )),
+        location: location.clone(),
+      }),
+      operator: Token::Or,
+      right: Box::new(Expression::Logical {
+        left: Box::new(comparison(
+          step.clone(),
+          Token::Less,
+          Expression::Integer(0, location.clone()),
+          &location,
+        )),
+        operator: Token::And,
+        right: Box::new(comparison(
+          counter_expr.clone(),
+          Token::GreaterEquals,
+          end,
+          &location,
+        )),
+        location: location.clone(),
+      }),
+      location: location.clone(),
+    },
+  };
+
+  let initializer = assignment(&counter, start, &location);
+  let update = assignment(
+    &counter,
+    Expression::Arithmetic {
+      left: Box::new(counter_expr),
+      operator: Token::Plus,
+      right: Box::new(step),
+      location: location.clone(),
+    },
+    &location,
+  );
+
+  Ok(Statement::For {
+    initializer: Box::new(initializer),
+    condition: Box::new(condition),
+    update: Box::new(update),
+    body,
+    location,
+  })
+}
+
+/// `enquanto cond faca ... fimenquanto`
+pub fn parse_while_stmt(parser: &mut Parser) -> Result<Statement, ParserError> {
+  parser.eat(Token::While)?;
+  let condition = parser
+    .parse_expression()
+    .map_err(|_| parser.invalid_expr("Condição de laço inválida"))?;
+  parser.eat(Token::Do)?;
+
+  let body = parse_block_until(parser, &[Token::EndWhile])?;
+  parser.eat(Token::EndWhile)?;
+
+  Ok(Statement::While {
+    condition: Box::new(condition),
+    body,
+    location: parser.current_token.location.clone(),
+  })
+}
+
+/// `repita ... ate cond`, lowered to the body followed by
+/// `enquanto (nao cond) { body }`
+pub fn parse_repeat_stmt(parser: &mut Parser) -> Result<Vec<Statement>, ParserError> {
+  parser.eat(Token::Repeat)?;
+  let body = parse_block_until(parser, &[Token::To])?;
+  parser.eat(Token::To)?;
+
+  let condition = parser
+    .parse_expression()
+    .map_err(|_| parser.invalid_expr("Condição de laço inválida"))?;
+  let location = parser.current_token.location.clone();
+
+  let mut stmts = body.clone();
+  stmts.push(Statement::While {
+    condition: Box::new(Expression::Unary {
+      operator: Token::Not,
+      expr: Box::new(condition),
+      location: location.clone(),
+    }),
+    body,
+    location,
+  });
+
+  Ok(stmts)
+}
+
+/// `escolha x caso 1, 2 ... outrocaso ... fimescolha`. Cases never fall
+/// through, so each value becomes its own case ending with a break.
+pub fn parse_switch_stmt(parser: &mut Parser) -> Result<Statement, ParserError> {
+  parser.eat(Token::Switch)?;
+  let switch_expr = parser.parse_expression()?;
+
+  let mut cases = Vec::new();
+  while parser.current_token.token == Token::Case {
+    parser.eat(Token::Case)?;
+
+    let mut values = Vec::new();
+    loop {
+      let value = parser
+        .parse_expression()
+        .map_err(|_| parser.invalid_expr("Valor de caso inválido"))?;
+
+      if !value.is_literal() {
+        return Err(parser.invalid_expr("Valor de caso deve ser literal"));
+      }
+
+      values.push(value);
+
+      if parser.current_token.token != Token::Comma {
+        break;
+      }
+      parser.eat(Token::Comma)?;
+    }
+
+    let block = parse_block_until(parser, &[Token::Case, Token::Default, Token::EndSwitch])?;
+    for value in values {
+      cases.push((Box::new(value), block.clone(), true));
+    }
+  }
+
+  let default_case = if parser.current_token.token == Token::Default {
+    parser.eat(Token::Default)?;
+    Some((parse_block_until(parser, &[Token::EndSwitch])?, true))
+  } else {
+    None
+  };
+
+  parser.eat(Token::EndSwitch)?;
+
+  Ok(Statement::Switch {
+    expression: Box::new(switch_expr),
+    cases,
+    default: default_case,
+    location: parser.current_token.location.clone(),
+  })
+}
+
+/// Whether a literal step counts up (`Some(true)`) or down (`Some(false)`)
+fn step_direction(step: &Expression) -> Option<bool> {
+  match step {
+    Expression::Integer(n, _) => Some(*n >= 0),
+    Expression::Float(n, _) => Some(*n >= 0.0),
+    Expression::Unary {
+      operator: Token::Minus,
+      expr,
+      ..
+    } => step_direction(expr).map(|up| !up),
+    _ => None,
+  }
+}
+
+fn comparison(
+  left: Expression,
+  operator: Token,
+  right: Expression,
+  location: &Location,
+) -> Expression {
+  Expression::Comparison {
+    left: Box::new(left),
+    operator,
+    right: Box::new(right),
+    location: location.clone(),
+  }
+}
+
+fn assignment(name: &str, value: Expression, location: &Location) -> Statement {
+  Statement::Assignment {
+    target: Box::new(Expression::Identifier(name.to_string(), location.clone())),
+    index: None,
+    value: Box::new(value),
+    location: location.clone(),
+  }
+}
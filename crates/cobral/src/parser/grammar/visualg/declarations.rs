@@ -0,0 +1,197 @@
+use crate::{
+  lexer::token::Token,
+  parser::{error::ParserError, grammar::lowering, Parser},
+  shared::ast::{Expression, Statement, Type},
+};
+
+use super::parse_block_until;
+
+/// Parses a `var` section (`x, y: inteiro`, `v: vetor[1..10] de real`).
+/// Every variable is initialized with the default value of its type.
+pub fn parse_var_section(parser: &mut Parser) -> Result<Vec<Statement>, ParserError> {
+  parser.eat(Token::Var)?;
+
+  let mut stmts = Vec::new();
+
+  while let Token::Identifier(_) = parser.current_token.token {
+    let names = parse_names(parser)?;
+    parser.eat(Token::Colon)?;
+    let (declared_type, bounds) = parse_declared_type(parser)?;
+
+    for name in names {
+      let location = parser.current_token.location.clone();
+      let initializer = match (&declared_type, bounds) {
+        (Type::List(element_type), Some((lower, upper))) => {
+          let element = default_value(parser, element_type)?;
+          let size = (upper - lower + 1).max(0) as usize;
+
+          parser.env.lower_bounds.write().insert(name.clone(), lower);
+          Expression::List(vec![element; size], location.clone())
+        }
+        _ => default_value(parser, &declared_type)?,
+      };
+
+      parser
+        .env
+        .types
+        .write()
+        .insert(name.clone(), declared_type.clone());
+      parser
+        .env
+        .variables
+        .write()
+        .insert(name.clone(), Some(initializer.clone()));
+
+      stmts.push(Statement::Variable {
+        name,
+        initializer: Box::new(initializer),
+        location,
+      });
+    }
+
+    parser.try_eat(Token::Semicolon)?;
+  }
+
+  Ok(stmts)
+}
+
+/// Parses `funcao nome(a, b: inteiro): inteiro` and `procedimento nome(...)`,
+/// with an optional `var` section for locals
+pub fn parse_function_stmt(parser: &mut Parser) -> Result<Statement, ParserError> {
+  let end = match parser.current_token.token {
+    Token::Procedure => Token::EndProcedure,
+    _ => Token::EndFunction,
+  };
+  parser.next_token(); // Consume `funcao`/`procedimento`
+
+  let name = match &parser.current_token.token {
+    Token::Identifier(name) => name.clone(),
+    _ => {
+      return Err(ParserError::ExpectedFunctionName(
+        parser.current_token.clone(),
+      ))
+    }
+  };
+  parser.next_token();
+
+  let mut params = Vec::new();
+  if parser.current_token.token == Token::ParenL {
+    parser.eat(Token::ParenL)?;
+
+    while parser.current_token.token != Token::ParenR {
+      parser.try_eat(Token::Var)?; // Parameters by reference are passed by value
+
+      let names = parse_names(parser)?;
+      parser.eat(Token::Colon)?;
+      let (declared_type, _) = parse_declared_type(parser)?;
+
+      for name in names {
+        parser.env.variables.write().insert(name.clone(), None);
+        parser
+          .env
+          .types
+          .write()
+          .insert(name.clone(), declared_type.clone());
+        params.push(name);
+      }
+
+      parser.try_eat(Token::Semicolon)?;
+    }
+
+    parser.eat(Token::ParenR)?;
+  }
+
+  if end == Token::EndFunction {
+    parser.eat(Token::Colon)?;
+    parse_declared_type(parser)?; // Return type
+  }
+
+  // Add function to environment before parsing body
+  parser
+    .env
+    .functions
+    .write()
+    .insert(name.clone(), Some(params.clone()));
+
+  let mut body = Vec::new();
+  if parser.current_token.token == Token::Var {
+    body.extend(parse_var_section(parser)?);
+  }
+
+  parser.eat(Token::Begin)?;
+  body.extend(parse_block_until(parser, std::slice::from_ref(&end))?);
+  parser.eat(end)?;
+
+  Ok(Statement::Function {
+    name,
+    params,
+    body,
+    location: parser.current_token.location.clone(),
+  })
+}
+
+fn parse_names(parser: &mut Parser) -> Result<Vec<String>, ParserError> {
+  let mut names = Vec::new();
+
+  loop {
+    match &parser.current_token.token {
+      Token::Identifier(name) => names.push(name.clone()),
+      _ => {
+        return Err(ParserError::ExpectedVariableName(
+          parser.current_token.clone(),
+        ))
+      }
+    }
+    parser.next_token();
+
+    if parser.current_token.token != Token::Comma {
+      break;
+    }
+    parser.eat(Token::Comma)?;
+  }
+
+  Ok(names)
+}
+
+/// Parses `inteiro` or `vetor[1..10] de inteiro`, returning the bounds of vectors
+fn parse_declared_type(parser: &mut Parser) -> Result<(Type, Option<(i64, i64)>), ParserError> {
+  match parser.current_token.token.clone() {
+    Token::Type(declared_type) => {
+      parser.next_token();
+      Ok((declared_type, None))
+    }
+    Token::Vector => {
+      parser.eat(Token::Vector)?;
+      parser.eat(Token::BracketL)?;
+      let lower = parse_bound(parser)?;
+      parser.eat(Token::Range)?;
+      let upper = parse_bound(parser)?;
+      parser.eat(Token::BracketR)?;
+      parser.eat(Token::From)?;
+
+      match parser.current_token.token.clone() {
+        Token::Type(element_type) => {
+          parser.next_token();
+          Ok((Type::List(Box::new(element_type)), Some((lower, upper))))
+        }
+        _ => Err(parser.invalid_stmt("Era esperado o tipo dos elementos do vetor")),
+      }
+    }
+    _ => Err(parser.invalid_stmt("Era esperado um tipo")),
+  }
+}
+
+fn parse_bound(parser: &mut Parser) -> Result<i64, ParserError> {
+  match parser.current_token.token {
+    Token::Integer(bound) => {
+      parser.next_token();
+      Ok(bound)
+    }
+    _ => Err(parser.invalid_expr("Limite do vetor deve ser um número inteiro")),
+  }
+}
+
+fn default_value(parser: &Parser, declared_type: &Type) -> Result<Expression, ParserError> {
+  lowering::scalar_default(declared_type, parser.current_token.location.clone())
+    .ok_or_else(|| parser.invalid_stmt(&format!("Tipo inválido para variável: {}", declared_type)))
+}
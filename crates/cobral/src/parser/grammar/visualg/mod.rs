@@ -0,0 +1,82 @@
+//! VisuAlg front-end. Like Portugol, programs are lowered to the regular
+//! CobraL AST and run by the same `Interpreter`.
+
+mod control_flow;
+mod declarations;
+mod program;
+
+use crate::{
+  lexer::token::Token,
+  parser::{error::ParserError, grammar::lowering, Parser},
+  shared::ast::{Expression, Statement},
+};
+
+pub use program::parse_program;
+
+pub fn parse_statements(parser: &mut Parser) -> Result<Vec<Statement>, ParserError> {
+  match &parser.current_token.token {
+    Token::If => Ok(vec![control_flow::parse_if_stmt(parser)?]),
+    Token::For => Ok(vec![control_flow::parse_for_stmt(parser)?]),
+    Token::While => Ok(vec![control_flow::parse_while_stmt(parser)?]),
+    Token::Repeat => control_flow::parse_repeat_stmt(parser),
+    Token::Switch => Ok(vec![control_flow::parse_switch_stmt(parser)?]),
+    Token::Return => Ok(vec![parser.parse_statement()?]),
+    Token::Identifier(name) if name == "leia" => lowering::parse_read_stmt(parser),
+    Token::Identifier(_) => Ok(vec![parse_identifier_stmt(parser)?]),
+    _ => Err(ParserError::UnexpectedToken(parser.current_token.clone())),
+  }
+}
+
+/// Parses statements until one of `terminators`, which is left unconsumed
+fn parse_block_until(
+  parser: &mut Parser,
+  terminators: &[Token],
+) -> Result<Vec<Statement>, ParserError> {
+  let mut items = Vec::new();
+
+  while !terminators.contains(&parser.current_token.token)
+    && parser.current_token.token != Token::EOF
+  {
+    items.extend(parser.parse_statements()?);
+    parser.try_eat(Token::Semicolon)?;
+  }
+
+  Ok(items)
+}
+
+/// Parses `x <- valor`, `v[i] <- valor` and procedure calls
+fn parse_identifier_stmt(parser: &mut Parser) -> Result<Statement, ParserError> {
+  let target = parser.parse_expression()?;
+
+  if parser.current_token.token != Token::Assign {
+    return match target {
+      Expression::Call { .. } => Ok(Statement::Expression(
+        target,
+        parser.current_token.location.clone(),
+      )),
+      _ => Err(ParserError::ExpectedToken(
+        parser.current_token.clone(),
+        Token::Assign,
+      )),
+    };
+  }
+
+  parser.eat(Token::Assign)?;
+  let value = parser.parse_expression()?;
+
+  let (name, index) = match target {
+    Expression::Identifier(name, _) => (name, None),
+    Expression::Index { name, index, .. } => (name, Some(index)),
+    _ => return Err(parser.invalid_stmt("Atribuição inválida")),
+  };
+
+  Ok(Statement::Assignment {
+    target: Box::new(Expression::Identifier(
+      name,
+      parser.current_token.location.clone(),
+    )),
+    index,
+    value: Box::new(value),
+    location: parser.current_token.location.clone(),
+  })
+}
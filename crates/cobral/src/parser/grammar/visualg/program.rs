@@ -0,0 +1,49 @@
+use crate::{
+  lexer::token::Token,
+  parser::{error::ParserError, grammar::lowering, Parser},
+  shared::ast::Statement,
+};
+
+use super::{declarations, parse_block_until};
+
+/// Parses `algoritmo "nome" var ... inicio ... fimalgoritmo`. Declarations and
+/// subprograms come first, followed by the main body as top-level code.
+pub fn parse_program(parser: &mut Parser) -> Result<Vec<Statement>, ParserError> {
+  parser.eat(Token::Algorithm)?;
+
+  if let Token::String(_) = parser.current_token.token {
+    parser.next_token(); // The algorithm name is only descriptive
+  }
+
+  let mut items = Vec::new();
+
+  loop {
+    match parser.current_token.token {
+      Token::Var => items.extend(declarations::parse_var_section(parser)?),
+      Token::Function | Token::Procedure => items.push(declarations::parse_function_stmt(parser)?),
+      Token::Begin => break,
+      _ => {
+        return Err(ParserError::ExpectedToken(
+          parser.current_token.clone(),
+          Token::Begin,
+        ))
+      }
+    }
+  }
+
+  parser.eat(Token::Begin)?;
+  let body = parse_block_until(parser, &[Token::EndAlgorithm])?;
+  parser.eat(Token::EndAlgorithm)?;
+
+  if parser.current_token.token != Token::EOF {
+    return Err(ParserError::UnexpectedToken(parser.current_token.clone()));
+  }
+
+  // e.g. `leia` lowers to `int(ler())`, which lives in "conversao"
+  let imports = lowering::implicit_imports(parser, &items);
+  items.splice(0..0, imports);
+
+  items.extend(body);
+
+  Ok(items)
+}
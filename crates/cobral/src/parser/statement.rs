@@ -14,8 +14,7 @@ impl StatementParser for Parser {
 
     let mut items = Vec::new();
     while self.current_token.token != Token::BraceR && self.current_token.token != Token::EOF {
-      let item = self.parse_statement()?;
-      items.push(item);
+      items.extend(self.parse_statements()?);
       self.try_eat(Token::Semicolon)?;
     }
 
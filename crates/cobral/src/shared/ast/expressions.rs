@@ -2,7 +2,7 @@ use crate::lexer::token::Token;
 
 use super::Location;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub enum Expression {
   // Arithmetic Operations
   Arithmetic {
@@ -9,7 +9,7 @@ use std::error::Error;
 use std::fmt;
 
 /// Represents the location of a node in the source code
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct Location {
   pub file: String,
   pub line: usize,
@@ -55,7 +55,7 @@ impl fmt::Display for ASTError {
 }
 
 /// Represents a type in the language
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
 pub enum Type {
   Integer,
   Float,
@@ -66,3 +66,18 @@ pub enum Type {
   List(Box<Type>),                     // type of list elements
   Any,
 }
+
+impl fmt::Display for Type {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Type::Integer => write!(f, "inteiro"),
+      Type::Float => write!(f, "real"),
+      Type::String => write!(f, "cadeia"),
+      Type::Boolean => write!(f, "logico"),
+      Type::Nil => write!(f, "vazio"),
+      Type::Function(_, _) => write!(f, "funcao"),
+      Type::List(inner) => write!(f, "{}[]", inner),
+      Type::Any => write!(f, "qualquer"),
+    }
+  }
+}
@@ -1,6 +1,6 @@
 use super::{expressions::Expression, Location};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub enum Statement {
   Variable {
     name: String,
@@ -18,6 +18,13 @@ pub enum Statement {
     value: Box<Expression>,
     location: Location,
   },
+  // `declare a, b = ...` (when `declare` is true) and `a, b = ...`
+  Destructure {
+    names: Vec<String>,
+    value: Box<Expression>,
+    declare: bool,
+    location: Location,
+  },
   If {
     condition: Box<Option<Expression>>,
     true_block: Vec<Statement>,
@@ -37,6 +44,12 @@ pub enum Statement {
     body: Vec<Statement>,
     location: Location,
   },
+  ForEach {
+    names: Vec<String>,
+    iterable: Box<Expression>,
+    body: Vec<Statement>,
+    location: Location,
+  },
   Switch {
     expression: Box<Expression>,
     cases: Vec<(Box<Expression>, Vec<Statement>, bool)>,
@@ -65,6 +78,8 @@ impl Statement {
       Statement::If { location, .. } => location.clone(),
       Statement::Return { location, .. } => location.clone(),
       Statement::For { location, .. } => location.clone(),
+      Statement::ForEach { location, .. } => location.clone(),
+      Statement::Destructure { location, .. } => location.clone(),
       Statement::Switch { location, .. } => location.clone(),
       Statement::Function { location, .. } => location.clone(),
       Statement::Import(_, location) => location.clone(),
@@ -73,4 +88,42 @@ impl Statement {
       Statement::While { location, .. } => location.clone(),
     }
   }
+
+  /// Name of the statement as shown to students, e.g. in desk-check tables
+  pub fn kind(&self) -> &'static str {
+    match self {
+      Statement::Variable { .. } => "declaração",
+      Statement::Constant { .. } => "constante",
+      Statement::Assignment { .. } => "atribuição",
+      Statement::Destructure { .. } => "atribuição múltipla",
+      Statement::If { .. } => "se",
+      Statement::While { .. } => "enquanto",
+      Statement::For { .. } => "para",
+      Statement::ForEach { .. } => "para cada",
+      Statement::Switch { .. } => "escolha",
+      Statement::Function { .. } => "função",
+      Statement::Return { .. } => "retorne",
+      Statement::Import(..) => "importe",
+      Statement::Expression(..) => "expressão",
+    }
+  }
+
+  pub fn set_location(&mut self, new_location: Location) {
+    let location = match self {
+      Statement::Expression(_, location) | Statement::Import(_, location) => location,
+      Statement::Assignment { location, .. }
+      | Statement::If { location, .. }
+      | Statement::Return { location, .. }
+      | Statement::For { location, .. }
+      | Statement::ForEach { location, .. }
+      | Statement::Destructure { location, .. }
+      | Statement::Switch { location, .. }
+      | Statement::Function { location, .. }
+      | Statement::Variable { location, .. }
+      | Statement::Constant { location, .. }
+      | Statement::While { location, .. } => location,
+    };
+
+    *location = new_location;
+  }
 }
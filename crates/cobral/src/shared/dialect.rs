@@ -0,0 +1,46 @@
+/// Source languages understood by the lexer and parser. Every dialect is
+/// lowered to the same AST, so the interpreter doesn't need to know which
+/// one a program was written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Dialect {
+  #[default]
+  CobraL,
+  /// Portugol Studio (`programa { funcao inicio() { ... } }`)
+  Portugol,
+  /// VisuAlg (`algoritmo "nome" ... inicio ... fimalgoritmo`)
+  VisuAlg,
+}
+
+impl Dialect {
+  /// Picks the dialect from the program header, i.e. the first word that
+  /// isn't whitespace or a comment.
+  pub fn detect(input: &str) -> Self {
+    match first_word(input).to_lowercase().as_str() {
+      "programa" => Dialect::Portugol,
+      "algoritmo" => Dialect::VisuAlg,
+      _ => Dialect::CobraL,
+    }
+  }
+}
+
+fn first_word(input: &str) -> &str {
+  let mut rest = input.trim_start();
+
+  loop {
+    if let Some(comment) = rest.strip_prefix("//") {
+      rest = comment.split_once('\n').map_or("", |(_, after)| after);
+    } else if let Some(comment) = rest.strip_prefix("/*") {
+      rest = comment.split_once("*/").map_or("", |(_, after)| after);
+    } else {
+      break;
+    }
+
+    rest = rest.trim_start();
+  }
+
+  let end = rest
+    .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+    .unwrap_or(rest.len());
+
+  &rest[..end]
+}
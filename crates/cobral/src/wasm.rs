@@ -1,12 +1,27 @@
-use cobral::event::GLOBAL_EVENT_SYSTEM;
-use cobral::interpreter::{Interpreter, InterpreterState};
-use cobral::lexer::Lexer;
-use cobral::logger::{
-  self,
-  batcher::{LogBatchConfig, LogBatchManager},
+use std::sync::LazyLock;
+
+use cobral::event::EventSystem;
+use cobral::interpreter::{
+  config::InterpreterConfig,
+  host::{EventHost, Host, HostHandle},
+  Interpreter, InterpreterState,
 };
+use cobral::lexer::Lexer;
+use cobral::logger::batcher::LogBatchConfig;
 use cobral::parser::Parser;
 
+/// The exported functions take no handle, so the module runs one program at a
+/// time and its interpreters share these events
+static EVENTS: LazyLock<EventSystem> = LazyLock::new(EventSystem::new);
+
+fn config(host: &EventHost) -> InterpreterConfig {
+  InterpreterConfig {
+    host: HostHandle::new(host.clone()),
+    events: EVENTS.clone(),
+    ..Default::default()
+  }
+}
+
 fn main() {
   let input = "escrever(1 + 2);";
   run(input.as_ptr(), input.len());
@@ -25,7 +40,7 @@ pub extern "C" fn emit_event(
   if let (Ok(event_str), Ok(payload_str)) =
     (std::str::from_utf8(event), std::str::from_utf8(payload))
   {
-    GLOBAL_EVENT_SYSTEM.emit(event_str, payload_str.to_string());
+    EVENTS.emit(event_str, payload_str.to_string());
   }
 }
 
@@ -33,7 +48,7 @@ pub extern "C" fn emit_event(
 pub extern "C" fn register_listener(event_ptr: *const u8, event_len: usize) -> usize {
   let event = unsafe { std::slice::from_raw_parts(event_ptr, event_len) };
   if let Ok(event_str) = std::str::from_utf8(event) {
-    return GLOBAL_EVENT_SYSTEM.listen(
+    return EVENTS.listen(
       event_str,
       Box::new(|payload| {
         println!("Received from JS: {}", payload);
@@ -46,7 +61,7 @@ pub extern "C" fn register_listener(event_ptr: *const u8, event_len: usize) -> u
 
 #[no_mangle]
 pub extern "C" fn unregister_listener(listener_id: usize) {
-  GLOBAL_EVENT_SYSTEM.unlisten(listener_id)
+  EVENTS.unlisten(listener_id)
 }
 
 #[no_mangle]
@@ -61,8 +76,7 @@ pub extern "C" fn run_with_custom_input(
     return;
   }
 
-  // Initialize the LogBatchManager with a configuration
-  LogBatchManager::init(LogBatchConfig::default());
+  let host = EventHost::new(EVENTS.clone(), LogBatchConfig::default());
 
   let input = unsafe { std::slice::from_raw_parts(input_ptr, input_len) };
 
@@ -79,7 +93,7 @@ pub extern "C" fn run_with_custom_input(
   let input_str = match std::str::from_utf8(input) {
     Ok(s) => s.trim(),
     Err(_) => {
-      logger::error("Input is not valid UTF-8.".to_string());
+      host.write_stderr("Input is not valid UTF-8.");
       return;
     }
   };
@@ -87,7 +101,7 @@ pub extern "C" fn run_with_custom_input(
   let tokens = match Lexer::new(input_str) {
     Ok(t) => t,
     Err(e) => {
-      logger::error(e.to_string());
+      host.write_stderr(&e.to_string());
       return;
     }
   };
@@ -95,35 +109,35 @@ pub extern "C" fn run_with_custom_input(
   let stmts = match Parser::new(tokens) {
     Ok(s) => s,
     Err(e) => {
-      logger::error(e.to_string());
+      host.write_stderr(&e.to_string());
       return;
     }
   };
 
-  let mut interpreter = match Interpreter::new(stmts) {
+  let mut interpreter = match Interpreter::with_config(stmts, config(&host)) {
     Ok(i) => i,
     Err(e) => {
-      logger::error(e.to_string());
+      host.write_stderr(&e.to_string());
       return;
     }
   };
 
   // Register the listener for the "log_batch" event
-  let batch_listener_id = GLOBAL_EVENT_SYSTEM.listen(
+  let batch_listener_id = EVENTS.listen(
     "process_logs",
     Box::new(|payload| {
       println!("Received log batch: {}", payload);
       // Forward log batch to JS
-      GLOBAL_EVENT_SYSTEM.emit("wasm_log", payload);
+      EVENTS.emit("wasm_log", payload);
     }),
   );
 
   // Register listener for input requests from the interpreter
-  let input_listener_id = GLOBAL_EVENT_SYSTEM.listen(
+  let input_listener_id = EVENTS.listen(
     "input_requested",
     Box::new(move |_| {
       // Notify JS that we need input
-      GLOBAL_EVENT_SYSTEM.emit("need_input", "true".to_owned());
+      EVENTS.emit("need_input", "true".to_owned());
     }),
   );
 
@@ -133,43 +147,43 @@ pub extern "C" fn run_with_custom_input(
       InterpreterState::Waiting(_) => {
         if let Some(input) = &user_input {
           if let Err(e) = interpreter.provide_input(input.clone()) {
-            logger::error(e.to_string());
+            host.write_stderr(&e.to_string());
             break;
           }
         } else {
           // Signal that we're waiting for input
-          GLOBAL_EVENT_SYSTEM.emit("need_input", "true".to_owned());
+          EVENTS.emit("need_input", "true".to_owned());
           break; // Exit the loop, waiting for JS to provide input
         }
       }
       InterpreterState::Completed => {
-        GLOBAL_EVENT_SYSTEM.emit("execution_completed", "".to_owned());
+        EVENTS.emit("execution_completed", "".to_owned());
         break;
       }
       InterpreterState::Error(e) => {
-        logger::error(e);
-        GLOBAL_EVENT_SYSTEM.emit("execution_error", (&e).to_string());
+        host.write_stderr(&e.to_string());
+        EVENTS.emit("execution_error", (&e).to_string());
         break;
       }
       _ => {
         if let Err(e) = interpreter.run() {
-          logger::error(e.to_string());
-          GLOBAL_EVENT_SYSTEM.emit("execution_error", e.to_string());
+          host.write_stderr(&e.to_string());
+          EVENTS.emit("execution_error", e.to_string());
           break;
         }
       }
     }
 
     // Process any pending logs after each step
-    LogBatchManager.process_batch();
+    host.flush();
   }
 
   // Clean up listeners
-  GLOBAL_EVENT_SYSTEM.unlisten(batch_listener_id);
-  GLOBAL_EVENT_SYSTEM.unlisten(input_listener_id);
+  EVENTS.unlisten(batch_listener_id);
+  EVENTS.unlisten(input_listener_id);
 
   // Keep the original run function
-  LogBatchManager.process_batch();
+  host.flush();
 }
 
 #[no_mangle]
@@ -179,8 +193,7 @@ pub extern "C" fn run(input_ptr: *const u8, input_len: usize) {
     return;
   }
 
-  // Initialize the LogBatchManager with a configuration
-  LogBatchManager::init(LogBatchConfig::default());
+  let host = EventHost::new(EVENTS.clone(), LogBatchConfig::default());
 
   let input = unsafe { std::slice::from_raw_parts(input_ptr, input_len) };
 
@@ -195,7 +208,7 @@ pub extern "C" fn run(input_ptr: *const u8, input_len: usize) {
   let tokens = match Lexer::new(input_str) {
     Ok(t) => t,
     Err(e) => {
-      logger::error(e.to_string());
+      host.write_stderr(&e.to_string());
       return;
     }
   };
@@ -203,29 +216,29 @@ pub extern "C" fn run(input_ptr: *const u8, input_len: usize) {
   let stmts = match Parser::new(tokens) {
     Ok(s) => s,
     Err(e) => {
-      logger::error(e.to_string());
+      host.write_stderr(&e.to_string());
       return;
     }
   };
 
-  let mut _interpreter = match Interpreter::new(stmts) {
+  let mut _interpreter = match Interpreter::with_config(stmts, config(&host)) {
     Ok(i) => i,
     Err(e) => {
-      logger::error(e.to_string());
+      host.write_stderr(&e.to_string());
       return;
     }
   };
 
   // Register the listener for the "log_batch" event
-  let id = GLOBAL_EVENT_SYSTEM.listen(
+  let id = EVENTS.listen(
     "process_logs",
     Box::new(|payload| {
       println!("Received log batch: {}", payload);
     }),
   );
 
-  LogBatchManager.process_batch();
-  GLOBAL_EVENT_SYSTEM.unlisten(id);
+  host.flush();
+  EVENTS.unlisten(id);
   println!("Run successfully!");
 }
 
@@ -238,6 +251,6 @@ pub extern "C" fn provide_input(input_ptr: *const u8, input_len: usize) {
   let input = unsafe { std::slice::from_raw_parts(input_ptr, input_len) };
   if let Ok(input_str) = std::str::from_utf8(input) {
     // Send the input to the appropriate event
-    GLOBAL_EVENT_SYSTEM.emit("input_provided", input_str.to_owned());
+    EVENTS.emit("input_provided", input_str.to_owned());
   }
 }
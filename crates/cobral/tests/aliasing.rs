@@ -0,0 +1,144 @@
+use std::sync::Arc;
+
+use cobral::{
+  interpreter::{
+    config::InterpreterConfig,
+    host::{HostHandle, MemoryHost},
+    vm::Backend,
+    Interpreter, InterpreterState,
+  },
+  lexer::Lexer,
+  parser::Parser,
+};
+
+/// Output of `code` on each backend, which must agree
+fn run(code: &str) -> Vec<String> {
+  let outputs: Vec<Vec<String>> = [Backend::Bytecode, Backend::TreeWalker]
+    .into_iter()
+    .map(|backend| {
+      let host = Arc::new(MemoryHost::default());
+      let config = InterpreterConfig {
+        host: HostHandle::from(host.clone()),
+        backend,
+        ..Default::default()
+      };
+
+      let stmts = Parser::new(Lexer::new(code).unwrap()).unwrap();
+      let interpreter = Interpreter::with_config(stmts, config).unwrap();
+      assert_eq!(interpreter.get_state(), &InterpreterState::Completed);
+
+      host.stdout()
+    })
+    .collect();
+
+  assert_eq!(outputs[0], outputs[1]);
+  outputs[0].clone()
+}
+
+#[test]
+fn assigned_lists_share_elements() {
+  let output = run(
+    r#"
+    declare a = [1, 2, 3]
+    declare b = a
+    b[0] = 10
+    escrever(a)
+  "#,
+  );
+
+  assert_eq!(output, ["[10, 2, 3]"]);
+}
+
+#[test]
+fn functions_change_the_callers_list() {
+  let output = run(
+    r#"
+    funcao zerar(v) {
+      para (declare i = 0; i < 3; i++) {
+        v[i] = 0
+      }
+    }
+
+    declare a = [1, 2, 3]
+    zerar(a)
+    escrever(a)
+  "#,
+  );
+
+  assert_eq!(output, ["[0, 0, 0]"]);
+}
+
+#[test]
+fn copies_share_nothing() {
+  let output = run(
+    r#"
+    declare m = [[1, 2], [3, 4]]
+    declare c = copiar(m)
+    declare linha = c[0]
+    linha[0] = 9
+    c[1] = 0
+    escrever(m)
+    escrever(c)
+  "#,
+  );
+
+  assert_eq!(output, ["[[1, 2], [3, 4]]", "[[9, 2], 0]"]);
+}
+
+#[test]
+fn nested_lists_are_shared_too() {
+  let output = run(
+    r#"
+    declare m = [[1, 2], [3, 4]]
+    declare linha = m[1]
+    linha[1] = 40
+    escrever(m)
+  "#,
+  );
+
+  assert_eq!(output, ["[[1, 2], [3, 40]]"]);
+}
+
+#[test]
+fn library_functions_change_the_callers_list() {
+  let output = run(
+    r#"
+    importe "lista"
+
+    funcao preparar(v) {
+      adicionar(v, 1)
+      ordenar(v)
+    }
+
+    declare a = [3, 2]
+    declare b = a
+    preparar(b)
+    escrever(a)
+  "#,
+  );
+
+  assert_eq!(output, ["[1, 2, 3]"]);
+}
+
+#[test]
+fn reassigning_a_variable_keeps_the_other_list() {
+  let output = run(
+    r#"
+    declare a = [1, 2]
+    declare b = a
+    b = [3, 4]
+    escrever(a, b)
+  "#,
+  );
+
+  assert_eq!(output, ["[1, 2] [3, 4]"]);
+}
+
+#[test]
+fn a_list_cannot_contain_itself() {
+  let stmts = Parser::new(Lexer::new("declare a = [[1]]\na[0] = a").unwrap()).unwrap();
+  let interpreter = Interpreter::with_config(stmts, InterpreterConfig::default());
+
+  let message = interpreter.err().map(|e| e.to_string()).unwrap_or_default();
+  assert!(message.contains("Um vetor não pode conter a si mesmo"));
+}
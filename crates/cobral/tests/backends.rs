@@ -0,0 +1,293 @@
+use std::sync::Arc;
+
+use cobral::{
+  interpreter::{
+    config::{InterpreterConfig, Limit},
+    error::InterpreterError,
+    host::{Host, HostHandle, MemoryHost},
+    module::{ModuleBuilder, ModuleRegistry},
+    vm::Backend,
+    Interpreter,
+  },
+  lexer::Lexer,
+  parser::Parser,
+};
+
+/// Output of `code` and how it ended
+type Run = (Vec<String>, Result<(), InterpreterError>);
+
+fn run_on(code: &str, backend: Backend) -> Run {
+  let host = Arc::new(MemoryHost::default());
+  let config = InterpreterConfig {
+    host: HostHandle::from(host.clone()),
+    backend,
+    ..Default::default()
+  };
+
+  let stmts = Parser::new(Lexer::new(code).unwrap()).unwrap();
+  let result = Interpreter::with_config(stmts, config).map(|_| ());
+
+  (host.stdout(), result)
+}
+
+/// Runs `code` on both backends, which must print the same and end the same
+/// way, with errors at the same location
+fn run(code: &str) -> Run {
+  let bytecode = run_on(code, Backend::Bytecode);
+  let tree_walker = run_on(code, Backend::TreeWalker);
+
+  assert_eq!(bytecode, tree_walker, "the backends disagree on:\n{}", code);
+  bytecode
+}
+
+fn output(code: &str) -> Vec<String> {
+  let (output, result) = run(code);
+  assert_eq!(result, Ok(()), "{}", code);
+  output
+}
+
+fn error(code: &str) -> InterpreterError {
+  run(code).1.expect_err(code)
+}
+
+#[test]
+fn programs() {
+  let programs = [
+    "escrever(1 + 2 * 3, 7 / 2, 7.0 / 2, 2 - 5)",
+    "declare x = 3\nx++\nescrever(x, x--, --x)",
+    "escrever(\"a\" + 1, 1 + \"b\", \"a\" == \"a\", 1 != 2, nao verdadeiro)",
+    "escrever(verdadeiro e falso, verdadeiro ou falso, 1 < 2 e 2 <= 2)",
+    "declare v = [1, [2, 3]]\nv[0] = 9\nescrever(v, v[1])",
+    "declare constante PI = 3.14\nescrever(PI)",
+    "se (1 > 2) {\n  escrever(\"a\")\n} senao se (2 > 1) {\n  escrever(\"b\")\n} senao {\n  escrever(\"c\")\n}",
+    "para (declare i = 0; i < 3; i++) {\n  escrever(i)\n}",
+    "declare i = 0\nenquanto (i < 3) {\n  i = i + 1\n}\nescrever(i)",
+    "para cada (x em [1, 2, 3]) {\n  escrever(x * 2)\n}",
+    "para cada (c em \"olá\") {\n  escrever(c)\n}",
+    "escolha (2) {\n  caso 1:\n    escrever(\"um\")\n    pare\n  caso 2:\n    escrever(\"dois\")\n    pare\n  padrao:\n    escrever(\"outro\")\n}",
+    "funcao f(n) {\n  se (n == 0) {\n    retorne 1\n  }\n  retorne n * f(n - 1)\n}\nescrever(f(10))",
+    "funcao par(n) {\n  declare v = [n, n * 2]\n  retorne v\n}\ndeclare a, b = par(4)\nescrever(a, b)",
+    "funcao soma(n, total) {\n  se (n == 0) {\n    retorne total\n  }\n  retorne soma(n - 1, total + n)\n}\nescrever(soma(1000, 0))",
+    "declare x = 1\nfuncao muda() {\n  x = 2\n}\nmuda()\nescrever(x)",
+  ];
+
+  for program in programs {
+    output(program);
+  }
+}
+
+#[test]
+fn functions_without_retorne_return_their_last_value() {
+  assert_eq!(
+    output("funcao f() {\n  escrever(\"in\")\n}\nescrever(f())"),
+    ["in", "in"]
+  );
+  assert_eq!(output("funcao f() {\n  1 + 2\n}\nescrever(f())"), ["3"]);
+  assert_eq!(
+    output("funcao f() {\n  declare x = 5\n}\nescrever(f())"),
+    ["5"]
+  );
+  assert_eq!(
+    output("declare v = [0]\nfuncao f() {\n  v[0] = 7\n}\nescrever(f())"),
+    ["7"]
+  );
+  assert_eq!(
+    output("funcao f() {\n  se (verdadeiro) {\n    1\n  }\n}\nescrever(f())"),
+    ["None"]
+  );
+  assert_eq!(output("funcao f() {\n  retorne\n}\nescrever(f())"), ["0"]);
+}
+
+#[test]
+fn retorne_leaves_loops() {
+  assert_eq!(
+    output("funcao f() {\n  declare i = 0\n  enquanto (i < 100) {\n    i = i + 1\n    se (i == 3) {\n      retorne i\n    }\n    escrever(i)\n  }\n}\nescrever(f())"),
+    ["1", "2", "3"]
+  );
+  assert_eq!(
+    output("funcao f() {\n  para (declare i = 0; i < 10; i++) {\n    se (i == 2) {\n      retorne i\n    }\n    escrever(i)\n  }\n  retorne -1\n}\nescrever(f())\nescrever(f())"),
+    ["0", "1", "2", "0", "1", "2"]
+  );
+}
+
+#[test]
+fn calls_undo_their_changes_on_return() {
+  assert_eq!(
+    output("declare x = 1\nfuncao g() {\n  x = 3\n  declare y = 4\n}\nfuncao f(x) {\n  declare z = 2\n  g()\n  escrever(x, z)\n}\nf(9)\nescrever(x)"),
+    ["9 2", "1"]
+  );
+  assert!(matches!(
+    error("funcao f() {\n  declare y = 4\n}\nf()\nescrever(y)"),
+    InterpreterError::EvalError(..)
+  ));
+}
+
+#[test]
+fn destructuring() {
+  assert_eq!(
+    output("funcao divide(a, b) {\n  retorne a / b, a % b\n}\ndeclare q, r = divide(7, 2)\nescrever(q, r)"),
+    ["3 1"]
+  );
+  assert_eq!(
+    output("declare a, b = 1, 2\na, b = b, a\nescrever(a, b)"),
+    ["2 1"]
+  );
+  assert_eq!(
+    output("declare pares = [[1, \"um\"], [2, \"dois\"]]\npara cada (numero, nome em pares) {\n  escrever(numero, nome)\n}"),
+    ["1 um", "2 dois"]
+  );
+
+  // The counts must match, and the error points to the statement
+  let (output, result) = run("escrever(0)\ndeclare a, b, c = 1, 2\nescrever(a)");
+  assert_eq!(output, ["0"]);
+  match result {
+    Err(InterpreterError::EvalError(at, message)) => {
+      assert_eq!((at.line, at.column), (2, 10));
+      assert_eq!(message, "Esperados 3 valores, mas foram recebidos 2");
+    }
+    other => panic!("unexpected result: {:?}", other),
+  }
+
+  for code in [
+    "para cada (a, b em [[1, 2, 3]]) {\n  escrever(a)\n}",
+    "declare a = 1\na, b = 1, 2",
+  ] {
+    assert!(matches!(error(code), InterpreterError::EvalError(..)));
+  }
+}
+
+#[test]
+fn deep_recursion_ends_the_same_on_both_backends() {
+  // Far deeper than the call depth limit, so these only finish as tail calls
+  assert_eq!(
+    output("funcao soma(n, total) {\n  se (n == 0) {\n    retorne total\n  }\n  retorne soma(n - 1, total + n)\n}\nescrever(soma(100000, 0))"),
+    ["5000050000"]
+  );
+  assert_eq!(
+    output("importe \"lista\"\nfuncao coleta(n, v) {\n  se (n == 0) {\n    retorne tamanho(v)\n  } senao {\n    adicionar(v, n)\n    retorne coleta(n - 1, v)\n  }\n}\nescrever(coleta(100000, []))"),
+    ["100000"]
+  );
+
+  // Without a tail call every level stays, and both stop at the same depth
+  // and location
+  assert!(matches!(
+    error("funcao f(n) {\n  se (n == 0) {\n    retorne 0\n  }\n  retorne 1 + f(n - 1)\n}\nescrever(f(100000))"),
+    InterpreterError::LimitExceeded(_, Limit::CallDepth(_))
+  ));
+}
+
+#[test]
+fn logical_operands_must_be_booleans() {
+  let (output, result) =
+    run("funcao f() {\n  escrever(\"f\")\n  retorne verdadeiro\n}\nescrever(1 e f())");
+
+  assert!(output.is_empty());
+  assert!(matches!(result, Err(InterpreterError::EvalError(..))));
+
+  error("escrever(verdadeiro ou 2)");
+  error("escrever(nao 1)");
+}
+
+#[test]
+fn error_locations() {
+  let errors = [
+    "declare v = [1, 2]\nescrever(v[5])",
+    "declare v = [1, 2]\nv[5] = 1",
+    "para cada (c em 5) {\n  escrever(c)\n}",
+    "retorne 1",
+    "escrever(1 / 0)",
+    "escrever(x)",
+    "funcao f(a) {\n  retorne a\n}\nf(1, 2)",
+    "se (1) {\n  escrever(1)\n}",
+    "escrever(\"a\" - 1)",
+    "funcao f() {\n  retorne 1\n}\nescrever(\"a\" - f())",
+    "escrever([1] < 2)",
+    "declare a, b = [1]",
+    "escolha (1) {\n  caso \"a\":\n    escrever(1)\n}",
+    "escrever(-\"a\")",
+    "declare x = \"a\"\nx++",
+  ];
+
+  for code in errors {
+    error(code);
+  }
+}
+
+#[test]
+fn seeded_runs_are_the_same_on_both_backends() {
+  let code = "importe \"aleatorio\"\nimporte \"tempo\"\nescrever(aleatorio(), sortear(1, 1000000), agora())\nescrever(aleatorio(), sortear(1, 1000000), agora())";
+  let seeded = |seed, backend| {
+    let host = Arc::new(MemoryHost::default());
+    let config = InterpreterConfig {
+      host: HostHandle::from(host.clone()),
+      backend,
+      seed: Some(seed),
+      ..Default::default()
+    };
+
+    Interpreter::with_config(Parser::new(Lexer::new(code).unwrap()).unwrap(), config).unwrap();
+    host.stdout()
+  };
+
+  let bytecode = seeded(7, Backend::Bytecode);
+  assert_eq!(bytecode, seeded(7, Backend::Bytecode));
+  assert_eq!(bytecode, seeded(7, Backend::TreeWalker));
+  assert_ne!(bytecode[0], bytecode[1]);
+  assert_ne!(bytecode, seeded(8, Backend::Bytecode));
+}
+
+#[test]
+fn registered_modules_run_on_both_backends() {
+  let mut modules = ModuleRegistry::default();
+  modules.register(
+    ModuleBuilder::new("saudacao")
+      .function("ola", "Cumprimenta", |nome: String| {
+        format!("Olá, {}!", nome)
+      })
+      .function("metade", "Metade de um par", |n: i64| match n % 2 {
+        0 => Ok(n / 2),
+        _ => Err(format!("{} é ímpar", n)),
+      })
+      .function("moeda", "Cara ou coroa", |host: &dyn Host| {
+        host.random() < 0.5
+      })
+      .build(),
+  );
+
+  let run_module = |code: &str, backend| {
+    let host = Arc::new(MemoryHost::default());
+    let config = InterpreterConfig {
+      host: HostHandle::from(host.clone()),
+      backend,
+      seed: Some(3),
+      modules: modules.clone(),
+      ..Default::default()
+    };
+
+    let stmts = Parser::with_modules(Lexer::new(code).unwrap(), modules.clone()).unwrap();
+    let result = Interpreter::with_config(stmts, config).map(|_| ());
+    (host.stdout(), result)
+  };
+  let run_both = |code: &str| {
+    let bytecode = run_module(code, Backend::Bytecode);
+    assert_eq!(bytecode, run_module(code, Backend::TreeWalker), "{}", code);
+    bytecode
+  };
+
+  let (output, result) =
+    run_both("importe \"saudacao\"\nescrever(ola(\"Ana\"), metade(8))\nescrever(moeda())");
+  assert_eq!(result, Ok(()));
+  assert_eq!(output[0], "Olá, Ana! 4");
+  assert!(output[1] == "verdadeiro" || output[1] == "falso");
+
+  // Wrong types and the errors of the function point to the call
+  assert!(matches!(
+    run_both("importe \"saudacao\"\nescrever(ola(1))").1,
+    Err(InterpreterError::TypeError(at, message))
+      if at.line == 2 && message == "O argumento 1 de ola deve ser texto, recebeu inteiro"
+  ));
+  assert!(matches!(
+    run_both("importe \"saudacao\"\nescrever(metade(7))").1,
+    Err(InterpreterError::RuntimeError(at, message)) if at.line == 2 && message == "7 é ímpar"
+  ));
+}
@@ -0,0 +1,68 @@
+use std::{env, fs, sync::Arc};
+
+use cobral::{
+  interpreter::{
+    config::InterpreterConfig,
+    host::{HostHandle, MemoryHost},
+    Interpreter,
+  },
+  lexer::Lexer,
+  parser::Parser,
+};
+
+#[test]
+fn imported_files_are_not_counted_as_program_lines() {
+  let library = env::temp_dir().join(format!("cobral-cobertura-{}.cobral", std::process::id()));
+  fs::write(
+    &library,
+    "declare a = 1\n\nse (a == 1) {\n  escrever(\"lib\")\n}\n",
+  )
+  .unwrap();
+
+  let code = format!(
+    "declare d = 1\nimporte \"{}\"\nse (d == 3) {{\n  escrever(\"nunca\")\n}}\nescrever(\"fim\")\n",
+    library.display()
+  );
+  let host = Arc::new(MemoryHost::default());
+  let config = InterpreterConfig {
+    host: HostHandle::from(host.clone()),
+    ..Default::default()
+  };
+
+  let stmts = Parser::new(Lexer::new(&code).unwrap()).unwrap();
+  let interpreter = Interpreter::with_coverage(stmts, config);
+  fs::remove_file(&library).unwrap();
+
+  let coverage = interpreter.unwrap().coverage().unwrap();
+  assert_eq!(host.stdout(), ["lib", "fim"]);
+
+  // The library's `se` is where the program's is, and its block ran, but only
+  // the program's own statements count
+  assert_eq!(coverage.lines[&4], 0);
+  assert_eq!(coverage.covered_lines(), coverage.lines.len() - 1);
+  assert!(coverage.to_lcov("programa.cobral").contains("DA:4,0"));
+
+  let branches: Vec<u64> = coverage.branches[0]
+    .branches
+    .iter()
+    .map(|branch| branch.hits)
+    .collect();
+  assert_eq!(branches, [0, 1]);
+}
+
+#[test]
+fn failing_runs_keep_their_counts() {
+  let code = "declare v = [1]\nescrever(v[0])\nescrever(v[5])\nescrever(\"fim\")\n";
+  let config = InterpreterConfig {
+    host: HostHandle::from(Arc::new(MemoryHost::default())),
+    ..Default::default()
+  };
+
+  let stmts = Parser::new(Lexer::new(code).unwrap()).unwrap();
+  let mut interpreter = Interpreter::covering(stmts, config);
+  assert!(interpreter.run().is_err());
+
+  let coverage = interpreter.coverage().unwrap();
+  let hits: Vec<u64> = coverage.lines.values().copied().collect();
+  assert_eq!(hits, [1, 1, 1, 0]);
+}
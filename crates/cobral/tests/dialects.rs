@@ -0,0 +1,196 @@
+use std::sync::Arc;
+
+use cobral::{
+  interpreter::{
+    config::InterpreterConfig,
+    host::{HostHandle, MemoryHost},
+    vm::Backend,
+    Interpreter, InterpreterState,
+  },
+  lexer::Lexer,
+  parser::Parser,
+  shared::ast::{Expression, Statement},
+};
+
+/// Output of `code` on each backend, which must agree
+fn run(code: &str) -> Vec<String> {
+  let outputs: Vec<Vec<String>> = [Backend::Bytecode, Backend::TreeWalker]
+    .into_iter()
+    .map(|backend| {
+      let host = Arc::new(MemoryHost::default());
+      let config = InterpreterConfig {
+        host: HostHandle::from(host.clone()),
+        backend,
+        ..Default::default()
+      };
+
+      let stmts = Parser::new(Lexer::new(code).unwrap()).unwrap();
+      let interpreter = Interpreter::with_config(stmts, config).unwrap();
+      assert_eq!(interpreter.get_state(), &InterpreterState::Completed);
+
+      host.stdout()
+    })
+    .collect();
+
+  assert_eq!(outputs[0], outputs[1]);
+  outputs[0].clone()
+}
+
+/// Names of the functions called by top-level statements
+fn calls(stmts: &[Statement]) -> Vec<String> {
+  stmts
+    .iter()
+    .filter_map(|stmt| match stmt {
+      Statement::Expression(Expression::Call { callee, .. }, _) => match &**callee {
+        Expression::Identifier(name, _) => Some(name.clone()),
+        _ => None,
+      },
+      _ => None,
+    })
+    .collect()
+}
+
+fn imports(stmts: &[Statement]) -> Vec<String> {
+  stmts
+    .iter()
+    .filter_map(|stmt| match stmt {
+      Statement::Import(name, _) => Some(name.clone()),
+      _ => None,
+    })
+    .collect()
+}
+
+#[test]
+fn visualg_writes_are_lowered_to_the_output_library() {
+  let code = r#"
+algoritmo "saida"
+inicio
+   escreva("a")
+   ESCREVAL("b")
+fimalgoritmo
+"#;
+
+  let stmts = Parser::new(Lexer::new(code).unwrap()).unwrap();
+
+  assert_eq!(imports(&stmts), ["saida"]);
+  assert_eq!(calls(&stmts), ["escreva", "escreval"]);
+}
+
+#[test]
+fn portugol_escreva_is_lowered_to_the_output_library() {
+  let code = r#"
+programa {
+  funcao inicio() {
+    escreva("a")
+  }
+}
+"#;
+
+  let stmts = Parser::new(Lexer::new(code).unwrap()).unwrap();
+
+  assert_eq!(imports(&stmts), ["saida"]);
+  assert_eq!(calls(&stmts), ["escreva"]);
+}
+
+#[test]
+fn cobral_programs_dont_import_the_output_library() {
+  let stmts = Parser::new(Lexer::new("escrever(1)").unwrap()).unwrap();
+
+  assert!(imports(&stmts).is_empty());
+  assert_eq!(calls(&stmts), ["escrever"]);
+}
+
+#[test]
+fn portugol_escreva_concatenates_without_ending_the_line() {
+  let output = run(
+    r#"
+programa {
+  funcao inicio() {
+    inteiro x = 3
+    escreva("x=", x, "\n")
+    escreva("a")
+    escreva("b")
+    escreva("\n", verdadeiro, 1.5)
+  }
+}
+"#,
+  );
+
+  assert_eq!(output, ["x=3", "ab", "verdadeiro1.5"]);
+}
+
+#[test]
+fn portugol_escreva_in_a_function() {
+  let output = run(
+    r#"
+programa {
+  funcao inicio() {
+    para (inteiro i = 1; i <= 3; i++) {
+      linha(i)
+    }
+  }
+
+  funcao linha(inteiro n) {
+    escreva(n, ": ", n * n, "\n")
+  }
+}
+"#,
+  );
+
+  assert_eq!(output, ["1: 1", "2: 4", "3: 9"]);
+}
+
+#[test]
+fn visualg_escreva_and_escreval() {
+  let output = run(
+    r#"
+algoritmo "soma"
+var
+   i, soma : inteiro
+inicio
+   soma <- 0
+   para i de 1 ate 10 faca
+      soma <- soma + i
+   fimpara
+   escreval("Soma: ", soma)
+   escreval(7 div 2, " ", 7 mod 2)
+   escreva("sem ")
+   escreva("quebra")
+   escreval()
+   escreval("fim")
+fimalgoritmo
+"#,
+  );
+
+  assert_eq!(output, ["Soma: 55", "3 1", "sem quebra", "fim"]);
+}
+
+#[test]
+fn visualg_escreva_before_leia_stays_on_the_prompt_line() {
+  let host = Arc::new(MemoryHost::default());
+  let config = InterpreterConfig {
+    host: HostHandle::from(host.clone()),
+    ..Default::default()
+  };
+
+  let code = r#"
+algoritmo "eco"
+var
+   nome : caractere
+inicio
+   escreva("Nome: ")
+   leia(nome)
+   escreval("Olá, ", nome, "!")
+fimalgoritmo
+"#;
+
+  let stmts = Parser::new(Lexer::new(code).unwrap()).unwrap();
+  let mut interpreter = Interpreter::with_config(stmts, config).unwrap();
+
+  assert!(interpreter.is_waiting_for_input());
+  assert_eq!(host.stdout(), ["Nome: "]);
+
+  interpreter.provide_input("Ana".to_string()).unwrap();
+
+  assert_eq!(host.stdout(), ["Nome: Olá, Ana!"]);
+}
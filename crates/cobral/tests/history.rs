@@ -0,0 +1,183 @@
+use std::sync::Arc;
+
+use cobral::{
+  interpreter::{
+    config::InterpreterConfig,
+    debugger::StopReason,
+    history::Event,
+    host::{HostHandle, MemoryHost},
+    value::Value,
+    Interpreter, InterpreterState,
+  },
+  lexer::Lexer,
+  parser::Parser,
+  shared::ast::Statement,
+};
+
+fn parse(code: &str) -> Vec<Statement> {
+  Parser::new(Lexer::new(code).unwrap()).unwrap()
+}
+
+fn config() -> InterpreterConfig {
+  InterpreterConfig {
+    host: HostHandle::from(Arc::new(MemoryHost::default())),
+    ..Default::default()
+  }
+}
+
+fn list(items: &[i64]) -> Value {
+  Value::list(items.iter().map(|n| Value::Integer(*n)).collect())
+}
+
+/// Variables of the innermost frame where the debugger stopped
+fn variables(interpreter: &Interpreter) -> Vec<(String, Value)> {
+  interpreter.stopped().unwrap().frames[0].variables.clone()
+}
+
+#[test]
+fn lists_changed_in_place_are_recorded_for_every_variable_sharing_them() {
+  let code = r#"importe "lista"
+declare v = [1, 2]
+declare w = v
+v[1] = 9
+adicionar(w, 4)
+escrever(v)
+"#;
+  let interpreter = Interpreter::with_history(parse(code), config()).unwrap();
+  let history = interpreter.history().unwrap();
+
+  assert_eq!(
+    history.values_of("v"),
+    [
+      (2, list(&[1, 2])),
+      (4, list(&[1, 9])),
+      (5, list(&[1, 9, 4]))
+    ]
+  );
+  assert_eq!(
+    history.values_of("w"),
+    [
+      (3, list(&[1, 2])),
+      (4, list(&[1, 9])),
+      (5, list(&[1, 9, 4]))
+    ]
+  );
+  assert_eq!(
+    history.steps[3].events,
+    [
+      Event::SetItems {
+        name: "v".into(),
+        items: vec![(1, Value::Integer(9))],
+      },
+      Event::SetItems {
+        name: "w".into(),
+        items: vec![(1, Value::Integer(9))],
+      },
+    ]
+  );
+  assert_eq!(history.steps[5].events, [Event::Output("[1, 9, 4]".into())]);
+}
+
+#[test]
+fn variables_of_a_call_go_away_when_it_returns() {
+  let code = "declare x = 1\nfuncao f(n) {\n  declare y = n\n  x = 5\n  retorne y * 2\n}\ndeclare z = f(3)\nescrever(x, z)\n";
+  let interpreter = Interpreter::with_history(parse(code), config()).unwrap();
+  let history = interpreter.history().unwrap();
+
+  let end = history.state_at(history.len()).unwrap();
+  assert_eq!(
+    end.variables.into_iter().collect::<Vec<_>>(),
+    [
+      ("x".to_string(), Value::Integer(1)),
+      ("z".to_string(), Value::Integer(6)),
+    ]
+  );
+  assert_eq!(history.values_of("x")[1], (5, Value::Integer(5)));
+
+  let returned = &history.steps[5].events;
+  assert!(returned.contains(&Event::Remove { name: "y".into() }));
+  assert!(returned.contains(&Event::Remove { name: "n".into() }));
+}
+
+#[test]
+fn step_back_shows_the_earlier_values() {
+  let code = "declare x = 1\nx = 2\nx = 3\nescrever(x)\n";
+  let mut interpreter = Interpreter::debug(parse(code), config()).unwrap();
+
+  for _ in 0..3 {
+    interpreter.step_over().unwrap();
+  }
+  assert_eq!(variables(&interpreter), [("x".into(), Value::Integer(3))]);
+
+  interpreter.step_back().unwrap();
+  let stopped = interpreter.stopped().unwrap();
+  assert_eq!(stopped.reason, StopReason::StepBack);
+  assert_eq!(stopped.location.line, 3);
+  assert_eq!(variables(&interpreter), [("x".into(), Value::Integer(2))]);
+
+  interpreter.step_back().unwrap();
+  assert_eq!(variables(&interpreter), [("x".into(), Value::Integer(1))]);
+
+  // Stepping forward comes back to the present
+  interpreter.step_over().unwrap();
+  interpreter.step_over().unwrap();
+  let stopped = interpreter.stopped().unwrap();
+  assert_eq!(stopped.reason, StopReason::Step);
+  assert_eq!(stopped.location.line, 4);
+}
+
+#[test]
+fn callers_show_their_variables_from_before_the_call() {
+  let code = "declare x = 1\nfuncao f(x) {\n  declare y = x\n  escrever(y)\n}\nf(7)\n";
+  let mut interpreter = Interpreter::debug(parse(code), config()).unwrap();
+  interpreter.set_breakpoint(4, None).unwrap();
+  interpreter.resume().unwrap();
+
+  let frames = &interpreter.stopped().unwrap().frames;
+  assert_eq!(frames.len(), 2);
+  assert_eq!(
+    frames[0].variables,
+    [
+      ("x".into(), Value::Integer(7)),
+      ("y".into(), Value::Integer(7)),
+    ]
+  );
+  assert_eq!(frames[1].variables, [("x".into(), Value::Integer(1))]);
+}
+
+#[test]
+fn conditional_breakpoints_and_steps_into_and_out_of_calls() {
+  let code = "funcao dobro(n) {\n  declare d = n * 2\n  retorne d\n}\ndeclare x = 0\npara (declare i = 0; i < 3; i++) {\n  x = dobro(i)\n}\nescrever(x)\n";
+  let mut interpreter = Interpreter::debug(parse(code), config()).unwrap();
+  assert_eq!(interpreter.stopped().unwrap().reason, StopReason::Entry);
+
+  // Only the last iteration meets the condition
+  interpreter.set_breakpoint(7, Some("i == 2")).unwrap();
+  interpreter.resume().unwrap();
+  let stopped = interpreter.stopped().unwrap();
+  assert_eq!(stopped.reason, StopReason::Breakpoint);
+  assert_eq!(stopped.location.line, 7);
+  assert_eq!(interpreter.evaluate("i * 10").unwrap(), Value::Integer(20));
+
+  interpreter.step_into().unwrap();
+  let frames = &interpreter.stopped().unwrap().frames;
+  assert_eq!(
+    frames
+      .iter()
+      .map(|frame| (frame.name.as_str(), frame.location.line))
+      .collect::<Vec<_>>(),
+    [("dobro", 2), ("principal", 7)]
+  );
+
+  // The call returns and the loop ends, so the next stop is after it
+  interpreter.step_out().unwrap();
+  let stopped = interpreter.stopped().unwrap();
+  assert_eq!(stopped.reason, StopReason::Step);
+  assert_eq!(stopped.location.line, 9);
+  assert_eq!(variables(&interpreter), [("x".into(), Value::Integer(4))]);
+
+  interpreter.remove_breakpoint(7).unwrap();
+  interpreter.resume().unwrap();
+  assert_eq!(interpreter.get_state(), &InterpreterState::Completed);
+  assert!(interpreter.stopped().is_none());
+}
@@ -0,0 +1,155 @@
+use std::{
+  sync::{Arc, Barrier},
+  thread,
+};
+
+use cobral::{
+  interpreter::{
+    config::InterpreterConfig,
+    error::InterpreterError,
+    host::{Host, HostHandle, MemoryHost},
+    value::Value,
+    vm::Backend,
+    Interpreter, InterpreterState,
+  },
+  lexer::Lexer,
+  parser::Parser,
+};
+
+/// Runs `code` answering each `ler()` with the next input. Both threads wait
+/// at `barrier` while their interpreter is suspended, so the runs interleave
+fn run(code: &str, backend: Backend, inputs: &[&str], barrier: &Barrier) -> Arc<MemoryHost> {
+  let host = Arc::new(MemoryHost::default());
+
+  let config = InterpreterConfig {
+    backend,
+    host: HostHandle::from(host.clone()),
+    ..Default::default()
+  };
+
+  let stmts = Parser::new(Lexer::new(code).unwrap()).unwrap();
+  let mut interpreter = Interpreter::with_config(stmts, config).unwrap();
+
+  for input in inputs {
+    assert!(interpreter.is_waiting_for_input());
+    barrier.wait();
+    interpreter.provide_input(input.to_string()).unwrap();
+  }
+
+  assert_eq!(interpreter.get_state(), &InterpreterState::Completed);
+
+  host
+}
+
+fn run_in_parallel(backend: Backend) {
+  let barrier = Arc::new(Barrier::new(2));
+
+  let first_barrier = barrier.clone();
+  let first = thread::spawn(move || {
+    let code = r#"
+      declare nome = ler("nome?")
+      declare idade = ler("idade?")
+      escrever("primeiro", nome, idade)
+    "#;
+    run(code, backend, &["ana", "30"], &first_barrier)
+  });
+
+  let second_barrier = barrier.clone();
+  let second = thread::spawn(move || {
+    let code = r#"
+      declare cidade = ler("cidade?")
+      erro("segundo")
+      declare pais = ler("pais?")
+      escrever("segundo", cidade, pais)
+    "#;
+    run(code, backend, &["recife", "brasil"], &second_barrier)
+  });
+
+  let first = first.join().unwrap();
+  let second = second.join().unwrap();
+
+  assert_eq!(first.stdout(), ["primeiro ana 30"]);
+  assert!(first.stderr().is_empty());
+  assert_eq!(first.prompts(), ["nome?", "idade?"]);
+
+  assert_eq!(second.stdout(), ["segundo recife brasil"]);
+  assert_eq!(second.stderr(), ["segundo"]);
+  assert_eq!(second.prompts(), ["cidade?", "pais?"]);
+}
+
+#[test]
+fn tree_walkers_on_two_threads_are_isolated() {
+  run_in_parallel(Backend::TreeWalker);
+}
+
+#[test]
+fn machines_on_two_threads_are_isolated() {
+  run_in_parallel(Backend::Bytecode);
+}
+
+/// A host that can't ask for input, like a window that was closed
+struct NoInput;
+
+impl Host for NoInput {
+  fn write_stdout(&self, _: &str) {}
+
+  fn write_stderr(&self, _: &str) {}
+
+  fn write(&self, _: &str) {}
+
+  fn request_input(&self, _: &str) -> Result<(), String> {
+    Err("janela fechada".to_string())
+  }
+}
+
+#[test]
+fn failed_input_requests_end_the_program() {
+  for backend in [Backend::Bytecode, Backend::TreeWalker] {
+    let config = InterpreterConfig {
+      backend,
+      host: HostHandle::new(NoInput),
+      ..Default::default()
+    };
+
+    let stmts = Parser::new(Lexer::new("declare nome = ler()").unwrap()).unwrap();
+    let result = Interpreter::with_config(stmts, config);
+
+    assert!(
+      matches!(&result, Err(InterpreterError::RuntimeError(_, message)) if message.contains("janela fechada")),
+      "{:?}",
+      result.err()
+    );
+  }
+}
+
+#[test]
+fn programs_end_when_the_input_runs_out() {
+  let code = "declare x = 1\nfuncao f() {\n  x = 2\n  retorne ler()\n}\nescrever(f())";
+
+  for backend in [Backend::Bytecode, Backend::TreeWalker] {
+    let config = InterpreterConfig {
+      backend,
+      host: HostHandle::from(Arc::new(MemoryHost::default())),
+      ..Default::default()
+    };
+
+    let stmts = Parser::new(Lexer::new(code).unwrap()).unwrap();
+    let mut interpreter = Interpreter::with_config(stmts, config).unwrap();
+    assert!(interpreter.is_waiting_for_input());
+
+    let error = interpreter.end_input("não há mais linhas");
+    assert!(
+      matches!(&error, InterpreterError::RuntimeError(at, _) if at.line == 4),
+      "{:?}",
+      error
+    );
+    assert_eq!(interpreter.get_state(), &InterpreterState::Error(error));
+    assert!(interpreter.provide_input("tarde".into()).is_err());
+
+    // The call was undone, so the next entry sees the variables from before it
+    interpreter
+      .extend(Parser::new(Lexer::new("escrever(x)").unwrap()).unwrap())
+      .unwrap();
+    assert_eq!(interpreter.variables()[0].1, Value::Integer(1));
+  }
+}
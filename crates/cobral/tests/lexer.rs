@@ -0,0 +1,63 @@
+use cobral::{
+  lexer::{token::Token, Lexer},
+  shared::dialect::Dialect,
+};
+
+fn tokens(code: &str) -> Vec<Token> {
+  Lexer::with_dialect(code, Dialect::CobraL)
+    .unwrap()
+    .into_iter()
+    .map(|labeled| labeled.token)
+    .filter(|token| *token != Token::EOF)
+    .collect()
+}
+
+fn identifier(name: &str) -> Token {
+  Token::Identifier(name.to_string())
+}
+
+#[test]
+fn accented_keywords() {
+  assert_eq!(
+    tokens("não senão função padrão"),
+    [Token::Not, Token::Else, Token::Function, Token::Default]
+  );
+  assert_eq!(
+    tokens("nao senao funcao padrao"),
+    [Token::Not, Token::Else, Token::Function, Token::Default]
+  );
+}
+
+#[test]
+fn decomposed_keywords() {
+  // Each accent written as a combining mark after its letter
+  assert_eq!(
+    tokens("na\u{303}o sena\u{303}o func\u{327}a\u{303}o padra\u{303}o"),
+    [Token::Not, Token::Else, Token::Function, Token::Default]
+  );
+}
+
+#[test]
+fn unicode_identifiers() {
+  assert_eq!(
+    tokens("ação π_2 número"),
+    [identifier("ação"), identifier("π_2"), identifier("número")]
+  );
+}
+
+#[test]
+fn decomposed_identifiers_match_composed_ones() {
+  assert_eq!(tokens("ac\u{327}a\u{303}o"), [identifier("ação")]);
+  assert_eq!(tokens("nu\u{301}mero"), tokens("número"));
+}
+
+#[test]
+fn decomposed_visualg_keywords() {
+  let tokens: Vec<Token> = Lexer::with_dialect("ENTA\u{303}O Fac\u{327}a", Dialect::VisuAlg)
+    .unwrap()
+    .into_iter()
+    .map(|labeled| labeled.token)
+    .collect();
+
+  assert_eq!(tokens, [Token::Then, Token::Do, Token::EOF]);
+}
@@ -0,0 +1,175 @@
+use cobral::{
+  interpreter::{
+    config::{InterpreterConfig, Limit},
+    error::InterpreterError,
+    vm::Backend,
+    Interpreter,
+  },
+  lexer::Lexer,
+  parser::Parser,
+  shared::ast::Statement,
+};
+
+/// Recursion that can't be a tail call, so every level stays on the stack
+const DEEP_RECURSION: &str = r#"
+funcao f(n) {
+  se (n == 0) {
+    retorne 0
+  }
+  retorne 1 + f(n - 1)
+}
+escrever(f(100000))
+"#;
+
+fn parse(code: &str) -> Vec<Statement> {
+  Parser::new(Lexer::new(code).unwrap()).unwrap()
+}
+
+fn call_depth(result: Result<Interpreter, InterpreterError>) -> usize {
+  match result {
+    Err(InterpreterError::LimitExceeded(_, Limit::CallDepth(max))) => max,
+    Err(e) => panic!("unexpected error: {}", e),
+    Ok(_) => panic!("the recursion should hit the call depth limit"),
+  }
+}
+
+#[test]
+fn deep_recursion_stops_before_the_stack_overflows() {
+  let depths: Vec<usize> = [Backend::Bytecode, Backend::TreeWalker]
+    .into_iter()
+    .map(|backend| {
+      let config = InterpreterConfig {
+        backend,
+        ..Default::default()
+      };
+
+      call_depth(Interpreter::with_config(parse(DEEP_RECURSION), config))
+    })
+    .collect();
+
+  assert_eq!(depths[0], depths[1]);
+}
+
+#[test]
+fn deep_recursion_stops_when_profiling() {
+  call_depth(Interpreter::with_profiler(
+    parse(DEEP_RECURSION),
+    InterpreterConfig::default(),
+  ));
+}
+
+#[test]
+fn configured_depth_is_capped_by_the_built_in_one() {
+  let config = InterpreterConfig {
+    backend: Backend::TreeWalker,
+    max_call_depth: Some(usize::MAX),
+    ..Default::default()
+  };
+  let built_in = call_depth(Interpreter::with_config(
+    parse(DEEP_RECURSION),
+    InterpreterConfig {
+      backend: Backend::TreeWalker,
+      ..Default::default()
+    },
+  ));
+
+  assert_eq!(
+    call_depth(Interpreter::with_config(parse(DEEP_RECURSION), config)),
+    built_in
+  );
+
+  let config = InterpreterConfig {
+    max_call_depth: Some(10),
+    ..Default::default()
+  };
+  assert_eq!(
+    call_depth(Interpreter::with_config(parse(DEEP_RECURSION), config)),
+    10
+  );
+}
+
+#[test]
+fn tail_calls_are_not_limited() {
+  let code = r#"
+funcao soma(n, total) {
+  se (n == 0) {
+    retorne total
+  }
+  retorne soma(n - 1, total + n)
+}
+escrever(soma(100000, 0))
+"#;
+
+  for backend in [Backend::Bytecode, Backend::TreeWalker] {
+    let config = InterpreterConfig {
+      backend,
+      ..Default::default()
+    };
+
+    assert!(Interpreter::with_config(parse(code), config).is_ok());
+  }
+}
+
+#[test]
+fn repeated_text_is_checked_before_it_is_built() {
+  // About 2 TB, which would abort the process if it were allocated
+  let code = "importe \"texto\"\nescrever(repetir(\"ab\", 1000000000000))\n";
+
+  for backend in [Backend::Bytecode, Backend::TreeWalker] {
+    let config = InterpreterConfig {
+      backend,
+      max_string_len: Some(100),
+      ..Default::default()
+    };
+
+    assert!(matches!(
+      Interpreter::with_config(parse(code), config),
+      Err(InterpreterError::LimitExceeded(_, Limit::StringLen(100)))
+    ));
+  }
+}
+
+#[test]
+fn replaced_and_joined_text_is_checked_before_it_is_built() {
+  // Both would be about 1 TB from texts within the limit
+  let programs = [
+    "importe \"texto\"\ndeclare t = repetir(\"a\", 1000000)\nescrever(substituir(t, \"a\", t))\n",
+    "importe \"texto\"\nimporte \"lista\"\ndeclare t = repetir(\"a\", 1000000)\nescrever(juntar(criar(1000000, \"\"), t))\n",
+  ];
+
+  for code in programs {
+    for backend in [Backend::Bytecode, Backend::TreeWalker] {
+      let config = InterpreterConfig {
+        backend,
+        max_string_len: Some(1000000),
+        ..Default::default()
+      };
+
+      // At the call, on the last line
+      assert!(matches!(
+        Interpreter::with_config(parse(code), config),
+        Err(InterpreterError::LimitExceeded(at, Limit::StringLen(1000000)))
+          if at.line == code.lines().count()
+      ));
+    }
+  }
+}
+
+#[test]
+fn created_lists_are_checked_before_they_are_built() {
+  // Reserving this many elements would take most of the memory before failing
+  let code = "importe \"lista\"\nescrever(criar(1000000000, 0))\n";
+
+  for backend in [Backend::Bytecode, Backend::TreeWalker] {
+    let config = InterpreterConfig {
+      backend,
+      max_list_len: Some(100),
+      ..Default::default()
+    };
+
+    assert!(matches!(
+      Interpreter::with_config(parse(code), config),
+      Err(InterpreterError::LimitExceeded(_, Limit::ListLen(100)))
+    ));
+  }
+}
@@ -0,0 +1,239 @@
+use std::sync::Arc;
+
+use cobral::{
+  interpreter::{
+    config::InterpreterConfig,
+    host::{HostHandle, MemoryHost},
+    snapshot::{Snapshot, SnapshotError, SNAPSHOT_VERSION},
+    vm::Backend,
+    Interpreter, InterpreterState,
+  },
+  lexer::Lexer,
+  parser::Parser,
+  shared::ast::Statement,
+};
+
+const PROGRAM: &str = r#"
+  importe "matematica"
+
+  declare constante LIMITE = 3
+  declare total = 0
+
+  funcao perguntar(i) {
+    declare resposta = ler("valor", i)
+    retorne resposta + "!"
+  }
+
+  para (declare i = 0; i < LIMITE; i++) {
+    declare lido = perguntar(i)
+    total = total + potencia(2, i)
+    escrever(i, lido, total)
+  }
+
+  escrever("fim", total)
+"#;
+
+fn parse(code: &str) -> Vec<Statement> {
+  Parser::new(Lexer::new(code).unwrap()).unwrap()
+}
+
+fn config(host: &Arc<MemoryHost>) -> InterpreterConfig {
+  InterpreterConfig {
+    host: HostHandle::from(host.clone()),
+    ..Default::default()
+  }
+}
+
+/// Output of the program run without interruptions
+fn uninterrupted(inputs: &[&str]) -> Vec<String> {
+  let host = Arc::new(MemoryHost::default());
+  let mut interpreter = Interpreter::with_config(parse(PROGRAM), config(&host)).unwrap();
+
+  for input in inputs {
+    interpreter.provide_input(input.to_string()).unwrap();
+  }
+
+  assert_eq!(interpreter.get_state(), &InterpreterState::Completed);
+  host.stdout()
+}
+
+#[test]
+fn resumes_after_each_input_from_json() {
+  let inputs = ["a", "b", "c"];
+
+  for saved_at in 0..inputs.len() {
+    let first = Arc::new(MemoryHost::default());
+    let mut interpreter = Interpreter::with_config(parse(PROGRAM), config(&first)).unwrap();
+
+    for input in &inputs[..saved_at] {
+      interpreter.provide_input(input.to_string()).unwrap();
+    }
+
+    let json = interpreter.snapshot().unwrap().to_json();
+    drop(interpreter);
+
+    let second = Arc::new(MemoryHost::default());
+    let snapshot = Snapshot::from_json(&json).unwrap();
+    let mut restored = Interpreter::restore(parse(PROGRAM), snapshot, config(&second)).unwrap();
+
+    assert_eq!(
+      restored.get_state(),
+      &InterpreterState::Waiting(saved_at as u32 + 1)
+    );
+
+    for input in &inputs[saved_at..] {
+      restored.provide_input(input.to_string()).unwrap();
+    }
+
+    assert_eq!(restored.get_state(), &InterpreterState::Completed);
+
+    let mut output = first.stdout();
+    output.extend(second.stdout());
+    assert_eq!(output, uninterrupted(&inputs));
+  }
+}
+
+#[test]
+fn snapshot_round_trips_through_json() {
+  let host = Arc::new(MemoryHost::default());
+  let mut interpreter = Interpreter::with_config(parse(PROGRAM), config(&host)).unwrap();
+  interpreter.provide_input("a".into()).unwrap();
+
+  let json = interpreter.snapshot().unwrap().to_json();
+  let snapshot = Snapshot::from_json(&json).unwrap();
+
+  assert_eq!(snapshot.version(), SNAPSHOT_VERSION);
+  assert_eq!(snapshot.to_json(), json);
+}
+
+#[test]
+fn rejects_other_versions() {
+  let host = Arc::new(MemoryHost::default());
+  let interpreter = Interpreter::with_config(parse(PROGRAM), config(&host)).unwrap();
+
+  let json = interpreter.snapshot().unwrap().to_json().replacen(
+    &format!("\"version\":{}", SNAPSHOT_VERSION),
+    "\"version\":999",
+    1,
+  );
+
+  assert_eq!(
+    Snapshot::from_json(&json).unwrap_err(),
+    SnapshotError::Version(999)
+  );
+}
+
+#[test]
+fn rejects_another_program() {
+  let host = Arc::new(MemoryHost::default());
+  let interpreter = Interpreter::with_config(parse(PROGRAM), config(&host)).unwrap();
+  let snapshot = interpreter.snapshot().unwrap();
+
+  let other = parse(r#"declare x = ler("outro")"#);
+  let restored = Interpreter::restore(other, snapshot, config(&host));
+
+  assert_eq!(restored.err(), Some(SnapshotError::ProgramMismatch));
+}
+
+#[test]
+fn only_waiting_bytecode_sessions_are_saved() {
+  let host = Arc::new(MemoryHost::default());
+
+  let completed = Interpreter::with_config(parse("escrever(1)"), config(&host)).unwrap();
+  assert_eq!(completed.snapshot().unwrap_err(), SnapshotError::NotWaiting);
+
+  let tree_walker = InterpreterConfig {
+    backend: Backend::TreeWalker,
+    ..config(&host)
+  };
+  let waiting = Interpreter::with_config(parse(PROGRAM), tree_walker).unwrap();
+  assert_eq!(
+    waiting.snapshot().unwrap_err(),
+    SnapshotError::UnsupportedBackend
+  );
+}
+
+#[test]
+fn shared_lists_stay_shared() {
+  let code = r#"
+    declare a = [0]
+    declare b = [a, a]
+
+    funcao enche(v) {
+      v[0] = ler()
+    }
+
+    enche(a)
+    escrever(a, b)
+  "#;
+
+  let host = Arc::new(MemoryHost::default());
+  let mut interpreter = Interpreter::with_config(parse(code), config(&host)).unwrap();
+  let json = interpreter.snapshot().unwrap().to_json();
+
+  interpreter.provide_input("7".to_string()).unwrap();
+  let expected = host.stdout();
+  assert_eq!(expected, [r#"["7"] [["7"], ["7"]]"#]);
+
+  let host = Arc::new(MemoryHost::default());
+  let snapshot = Snapshot::from_json(&json).unwrap();
+  let mut restored = Interpreter::restore(parse(code), snapshot, config(&host)).unwrap();
+  restored.provide_input("7".to_string()).unwrap();
+
+  assert_eq!(host.stdout(), expected);
+}
+
+#[test]
+fn rejects_invalid_lists() {
+  let host = Arc::new(MemoryHost::default());
+  let code = "declare v = [1]\ndeclare x = ler()";
+  let interpreter = Interpreter::with_config(parse(code), config(&host)).unwrap();
+  let json = interpreter.snapshot().unwrap().to_json();
+
+  let unknown = json.replacen(r#"{"List":0}"#, r#"{"List":5}"#, 1);
+  let inside_itself = json.replacen(r#"[[{"Integer":1}]]"#, r#"[[{"List":0}]]"#, 1);
+
+  for json in [unknown, inside_itself] {
+    let snapshot = Snapshot::from_json(&json).unwrap();
+
+    assert!(matches!(
+      Interpreter::restore(parse(code), snapshot, config(&host)),
+      Err(SnapshotError::Invalid(_))
+    ));
+  }
+}
+
+#[test]
+fn seeded_runs_continue_their_random_numbers_and_clock() {
+  let code = r#"
+    importe "aleatorio"
+    importe "tempo"
+    escrever(sortear(1, 1000000), agora())
+    ler()
+    escrever(sortear(1, 1000000), agora())
+  "#;
+  let seeded = |host: &Arc<MemoryHost>| InterpreterConfig {
+    seed: Some(42),
+    ..config(host)
+  };
+
+  let host = Arc::new(MemoryHost::default());
+  let mut interpreter = Interpreter::with_config(parse(code), seeded(&host)).unwrap();
+  interpreter.provide_input("x".to_string()).unwrap();
+  let expected = host.stdout();
+
+  let first = Arc::new(MemoryHost::default());
+  let interpreter = Interpreter::with_config(parse(code), seeded(&first)).unwrap();
+  let json = interpreter.snapshot().unwrap().to_json();
+  drop(interpreter);
+
+  let second = Arc::new(MemoryHost::default());
+  let snapshot = Snapshot::from_json(&json).unwrap();
+  let mut restored = Interpreter::restore(parse(code), snapshot, seeded(&second)).unwrap();
+  restored.provide_input("x".to_string()).unwrap();
+
+  let mut output = first.stdout();
+  output.extend(second.stdout());
+  assert_eq!(output, expected);
+  assert_ne!(expected[0], expected[1]);
+}
@@ -0,0 +1,94 @@
+use std::sync::Arc;
+
+use cobral::{
+  interpreter::{
+    config::InterpreterConfig,
+    host::{HostHandle, MemoryHost},
+    Interpreter,
+  },
+  lexer::Lexer,
+  parser::Parser,
+  shared::ast::Statement,
+};
+
+const FAILING: &str = "declare x = 1\nx = x + 1\nescrever(x)\ndeclare v = [1]\nescrever(v[5])\n";
+
+fn parse(code: &str) -> Vec<Statement> {
+  Parser::new(Lexer::new(code).unwrap()).unwrap()
+}
+
+fn config() -> InterpreterConfig {
+  InterpreterConfig {
+    host: HostHandle::from(Arc::new(MemoryHost::default())),
+    ..Default::default()
+  }
+}
+
+#[test]
+fn failing_programs_keep_their_table() {
+  let mut interpreter = Interpreter::tracing(parse(FAILING), config());
+  assert!(interpreter.run().is_err());
+
+  let rows = interpreter.trace().unwrap().rows;
+  let lines: Vec<usize> = rows.iter().map(|row| row.line).collect();
+  assert_eq!(lines, [1, 2, 3, 4, 5]);
+  assert_eq!(rows[2].output, ["2"]);
+}
+
+#[test]
+fn failing_programs_keep_their_profile() {
+  let mut interpreter = Interpreter::profiling(parse(FAILING), config());
+  assert!(interpreter.run().is_err());
+
+  let profile = interpreter.profile().unwrap();
+  let mut lines: Vec<usize> = profile.lines.iter().map(|line| line.line).collect();
+  lines.sort();
+  assert_eq!(lines[..4], [1, 2, 3, 4]);
+}
+
+#[test]
+fn tables_show_what_each_statement_changed() {
+  let code = "declare v = [1]\ndeclare w = v\nv[0] = 5\ndeclare nome = ler()\nescrever(nome)\n";
+  let mut interpreter = Interpreter::tracing(parse(code), config());
+  interpreter.run().unwrap();
+  interpreter.provide_input("Ana".into()).unwrap();
+
+  let rows = interpreter.trace().unwrap().rows;
+  let changed: Vec<&str> = rows[2].changes.iter().map(|c| c.name.as_str()).collect();
+  assert_eq!(changed, ["v", "w"]);
+  assert_eq!(rows[2].changes[1].new.to_string(), "[5]");
+  assert_eq!(rows[3].input, ["Ana"]);
+  assert_eq!(rows[4].output, ["Ana"]);
+}
+
+#[test]
+fn profiles_count_lines_calls_and_stacks() {
+  let code = "funcao fatorial(n) {\n  se (n <= 1) {\n    retorne 1\n  }\n  retorne n * fatorial(n - 1)\n}\npara (declare i = 0; i < 3; i++) {\n  escrever(fatorial(4))\n}\n";
+  let mut interpreter = Interpreter::profiling(parse(code), config());
+  interpreter.run().unwrap();
+
+  let profile = interpreter.profile().unwrap();
+  let mut lines: Vec<(usize, u64)> = profile
+    .lines
+    .iter()
+    .map(|line| (line.line, line.count))
+    .collect();
+  lines.sort();
+  assert_eq!(lines, [(1, 1), (2, 12), (3, 3), (5, 9), (7, 1), (8, 3)]);
+
+  // Recursive calls count once each, and the time of the outer call includes
+  // the inner ones
+  let fatorial = &profile.functions[0];
+  assert_eq!((fatorial.name.as_str(), fatorial.calls), ("fatorial", 12));
+  assert!(fatorial.inclusive >= fatorial.exclusive);
+
+  let stacks: Vec<&str> = profile
+    .stacks
+    .iter()
+    .map(|(stack, _)| stack.as_str())
+    .collect();
+  assert!(stacks.contains(&"principal;fatorial;fatorial;fatorial;fatorial"));
+  assert!(!stacks
+    .iter()
+    .any(|stack| stack.matches("fatorial").count() > 4));
+}
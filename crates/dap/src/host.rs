@@ -0,0 +1,41 @@
+use cobral::interpreter::host::Host;
+
+use crate::protocol::Writer;
+
+/// stdout carries the protocol, so the program output goes in `output` events
+pub struct DapHost {
+  writer: Writer,
+}
+
+impl DapHost {
+  pub fn new(writer: Writer) -> Self {
+    DapHost { writer }
+  }
+}
+
+impl Host for DapHost {
+  fn write_stdout(&self, text: &str) {
+    self.writer.output("stdout", format!("{}\n", text));
+  }
+
+  fn write_stderr(&self, text: &str) {
+    self.writer.output("stderr", format!("{}\n", text));
+  }
+
+  fn write(&self, text: &str) {
+    self.writer.output("stdout", text);
+  }
+
+  fn request_input(&self, prompt: &str) -> Result<(), String> {
+    if !prompt.is_empty() {
+      self.writer.output("stdout", format!("{}\n", prompt));
+    }
+
+    self.writer.output(
+      "console",
+      "Aguardando entrada: digite o valor no console de depuração\n",
+    );
+
+    Ok(())
+  }
+}
@@ -0,0 +1,49 @@
+//! Debug Adapter Protocol server for CobraL. Editors start it and talk to it
+//! over stdin/stdout, e.g. VS Code with a `debugAdapter` executable.
+
+mod host;
+mod protocol;
+mod session;
+
+use std::{
+  io,
+  sync::{mpsc, Arc, Mutex},
+  thread,
+};
+
+use cobral::interpreter::debugger::PauseHandle;
+use protocol::{read_message, Writer};
+use serde_json::json;
+use session::Session;
+
+fn main() {
+  let writer = Writer::new();
+
+  let running: Arc<Mutex<Option<PauseHandle>>> = Arc::new(Mutex::new(None));
+  let (sender, receiver) = mpsc::channel();
+
+  // Requests are read on their own thread, so `pause` arrives while the
+  // session is blocked running the program
+  let reader_writer = writer.clone();
+  let reader_running = running.clone();
+  thread::spawn(move || {
+    let mut stdin = io::stdin().lock();
+
+    while let Ok(Some(request)) = read_message(&mut stdin) {
+      if request["command"] == "pause" {
+        if let Some(handle) = &*reader_running.lock().unwrap_or_else(|e| e.into_inner()) {
+          handle.pause();
+        }
+
+        reader_writer.respond(&request, json!({}));
+        continue;
+      }
+
+      if sender.send(request).is_err() {
+        break;
+      }
+    }
+  });
+
+  Session::new(writer, running).run(receiver);
+}
@@ -0,0 +1,104 @@
+use std::{
+  io::{self, BufRead, Write},
+  sync::{Arc, Mutex},
+};
+
+use serde_json::{json, Value};
+
+/// Reads one message framed by a `Content-Length` header, or `None` at the end
+/// of the input
+pub fn read_message(reader: &mut impl BufRead) -> io::Result<Option<Value>> {
+  let mut length = None;
+
+  loop {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+      return Ok(None);
+    }
+
+    let line = line.trim_end();
+    if line.is_empty() {
+      break;
+    }
+
+    if let Some(value) = line.strip_prefix("Content-Length:") {
+      length = value.trim().parse::<usize>().ok();
+    }
+  }
+
+  let Some(length) = length else {
+    return Err(io::Error::new(
+      io::ErrorKind::InvalidData,
+      "Cabeçalho Content-Length ausente",
+    ));
+  };
+
+  let mut body = vec![0; length];
+  reader.read_exact(&mut body)?;
+
+  serde_json::from_slice(&body)
+    .map(Some)
+    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Sends responses and events to stdout. Shared by the request reader and the
+/// session, so messages are numbered under the same lock they are written with
+#[derive(Clone)]
+pub struct Writer {
+  out: Arc<Mutex<(i64, io::Stdout)>>,
+}
+
+impl Writer {
+  pub fn new() -> Self {
+    Writer {
+      out: Arc::new(Mutex::new((1, io::stdout()))),
+    }
+  }
+
+  pub fn respond(&self, request: &Value, body: Value) {
+    self.send(json!({
+      "type": "response",
+      "request_seq": request["seq"],
+      "command": request["command"],
+      "success": true,
+      "body": body,
+    }));
+  }
+
+  pub fn fail(&self, request: &Value, message: impl ToString) {
+    self.send(json!({
+      "type": "response",
+      "request_seq": request["seq"],
+      "command": request["command"],
+      "success": false,
+      "message": message.to_string(),
+    }));
+  }
+
+  pub fn event(&self, event: &str, body: Value) {
+    self.send(json!({
+      "type": "event",
+      "event": event,
+      "body": body,
+    }));
+  }
+
+  pub fn output(&self, category: &str, output: impl ToString) {
+    self.event(
+      "output",
+      json!({ "category": category, "output": output.to_string() }),
+    );
+  }
+
+  fn send(&self, mut message: Value) {
+    let mut out = self.out.lock().unwrap_or_else(|e| e.into_inner());
+    let (seq, stdout) = &mut *out;
+
+    message["seq"] = json!(*seq);
+    *seq += 1;
+
+    let body = message.to_string();
+    let _ = write!(stdout, "Content-Length: {}\r\n\r\n{}", body.len(), body);
+    let _ = stdout.flush();
+  }
+}
@@ -0,0 +1,430 @@
+use std::{
+  fs,
+  path::Path,
+  sync::{mpsc::Receiver, Arc, Mutex},
+};
+
+use cobral::{
+  interpreter::{
+    config::InterpreterConfig,
+    host::HostHandle,
+    debugger::{PauseHandle, StopReason, Stopped},
+    error::InterpreterError,
+    value::Value,
+    Interpreter, InterpreterState,
+  },
+  lexer::Lexer,
+  parser::Parser,
+};
+use serde_json::{json, Value as Json};
+
+use crate::{host::DapHost, protocol::Writer};
+
+/// CobraL programs have a single thread of execution
+const THREAD_ID: i64 = 1;
+
+pub struct Session {
+  writer: Writer,
+
+  /// Set while the program runs, so the reader thread can pause it
+  running: Arc<Mutex<Option<PauseHandle>>>,
+  interpreter: Option<Interpreter>,
+  program: String,
+  stop_on_entry: bool,
+  configured: bool,
+  started: bool,
+  breakpoints: Vec<(usize, Option<String>)>,
+}
+
+impl Session {
+  pub fn new(writer: Writer, running: Arc<Mutex<Option<PauseHandle>>>) -> Self {
+    Session {
+      writer,
+      running,
+      interpreter: None,
+      program: String::new(),
+      stop_on_entry: false,
+      configured: false,
+      started: false,
+      breakpoints: Vec::new(),
+    }
+  }
+
+  pub fn run(mut self, requests: Receiver<Json>) {
+    for request in requests {
+      let command = request["command"].as_str().unwrap_or_default().to_string();
+      let arguments = &request["arguments"];
+
+      let result = match command.as_str() {
+        "initialize" => self.initialize(&request),
+        "launch" => self.launch(&request, arguments),
+        "setBreakpoints" => self.set_breakpoints(&request, arguments),
+        "configurationDone" => self.configuration_done(&request),
+        "threads" => self.threads(&request),
+        "stackTrace" => self.stack_trace(&request),
+        "scopes" => self.scopes(&request, arguments),
+        "variables" => self.variables(&request, arguments),
+        "continue" => self.step(&request, Interpreter::resume),
+        "next" => self.step(&request, Interpreter::step_over),
+        "stepIn" => self.step(&request, Interpreter::step_into),
+        "stepOut" => self.step(&request, Interpreter::step_out),
+        "stepBack" => self.step(&request, Interpreter::step_back),
+        "reverseContinue" => self.step(&request, Interpreter::reverse_continue),
+        "evaluate" => self.evaluate(&request, arguments),
+        "disconnect" | "terminate" => {
+          self.writer.respond(&request, json!({}));
+          break;
+        }
+        _ => Err(format!("Comando não suportado: {}", command)),
+      };
+
+      if let Err(message) = result {
+        self.writer.fail(&request, message);
+      }
+    }
+  }
+
+  fn initialize(&mut self, request: &Json) -> Result<(), String> {
+    self.writer.respond(
+      request,
+      json!({
+        "supportsConfigurationDoneRequest": true,
+        "supportsConditionalBreakpoints": true,
+        "supportsEvaluateForHovers": true,
+        "supportsStepBack": true,
+      }),
+    );
+    self.writer.event("initialized", json!({}));
+
+    Ok(())
+  }
+
+  fn launch(&mut self, request: &Json, arguments: &Json) -> Result<(), String> {
+    let program = arguments["program"]
+      .as_str()
+      .ok_or("Argumento 'program' ausente")?;
+
+    let code = fs::read_to_string(program)
+      .map_err(|e| format!("Erro ao ler arquivo '{}': {}", program, e))?;
+    let tokens = Lexer::new(&code).map_err(|e| e.to_string())?;
+    let stmts = Parser::new(tokens).map_err(|e| e.to_string())?;
+
+    let config = InterpreterConfig {
+      host: HostHandle::new(DapHost::new(self.writer.clone())),
+      ..Default::default()
+    };
+    let interpreter = Interpreter::debug(stmts, config).map_err(|e| e.to_string())?;
+
+    self.program = program.to_string();
+    self.stop_on_entry = arguments["stopOnEntry"].as_bool().unwrap_or(false);
+    self.interpreter = Some(interpreter);
+    self.apply_breakpoints()?;
+
+    self.writer.respond(request, json!({}));
+    self.start();
+
+    Ok(())
+  }
+
+  fn set_breakpoints(&mut self, request: &Json, arguments: &Json) -> Result<(), String> {
+    let previous = std::mem::take(&mut self.breakpoints);
+
+    if let Some(interpreter) = &self.interpreter {
+      for (line, _) in &previous {
+        interpreter
+          .remove_breakpoint(*line)
+          .map_err(|e| e.to_string())?;
+      }
+    }
+
+    self.breakpoints = arguments["breakpoints"]
+      .as_array()
+      .into_iter()
+      .flatten()
+      .filter_map(|breakpoint| {
+        let line = breakpoint["line"].as_u64()? as usize;
+        let condition = breakpoint["condition"]
+          .as_str()
+          .filter(|condition| !condition.trim().is_empty())
+          .map(str::to_string);
+
+        Some((line, condition))
+      })
+      .collect();
+
+    let verified = self.apply_breakpoints();
+    let breakpoints: Vec<Json> = self
+      .breakpoints
+      .iter()
+      .map(|(line, _)| match &verified {
+        Ok(()) => json!({ "verified": true, "line": line }),
+        Err(message) => json!({ "verified": false, "line": line, "message": message }),
+      })
+      .collect();
+
+    self
+      .writer
+      .respond(request, json!({ "breakpoints": breakpoints }));
+
+    Ok(())
+  }
+
+  fn configuration_done(&mut self, request: &Json) -> Result<(), String> {
+    self.configured = true;
+    self.writer.respond(request, json!({}));
+    self.start();
+
+    Ok(())
+  }
+
+  fn threads(&mut self, request: &Json) -> Result<(), String> {
+    self.writer.respond(
+      request,
+      json!({ "threads": [{ "id": THREAD_ID, "name": "principal" }] }),
+    );
+
+    Ok(())
+  }
+
+  fn stack_trace(&mut self, request: &Json) -> Result<(), String> {
+    let stopped = self.stopped()?;
+
+    let source = json!({
+      "name": Path::new(&self.program).file_name().and_then(|name| name.to_str()),
+      "path": self.program,
+    });
+
+    let frames: Vec<Json> = stopped
+      .frames
+      .iter()
+      .enumerate()
+      .map(|(id, frame)| {
+        json!({
+          "id": id,
+          "name": frame.name,
+          "line": frame.location.line,
+          "column": frame.location.column,
+          "source": source,
+        })
+      })
+      .collect();
+
+    self.writer.respond(
+      request,
+      json!({ "stackFrames": frames, "totalFrames": frames.len() }),
+    );
+
+    Ok(())
+  }
+
+  /// Each frame has a single scope, referenced by its index plus one
+  fn scopes(&mut self, request: &Json, arguments: &Json) -> Result<(), String> {
+    let frame = arguments["frameId"].as_u64().unwrap_or(0);
+
+    self.writer.respond(
+      request,
+      json!({
+        "scopes": [{
+          "name": "Variáveis",
+          "variablesReference": frame + 1,
+          "expensive": false,
+        }]
+      }),
+    );
+
+    Ok(())
+  }
+
+  fn variables(&mut self, request: &Json, arguments: &Json) -> Result<(), String> {
+    let reference = arguments["variablesReference"].as_u64().unwrap_or(0) as usize;
+    let stopped = self.stopped()?;
+
+    let variables: Vec<Json> = reference
+      .checked_sub(1)
+      .and_then(|frame| stopped.frames.get(frame))
+      .map(|frame| {
+        frame
+          .variables
+          .iter()
+          .map(|(name, value)| {
+            json!({
+              "name": name,
+              "value": display(value),
+              "type": value.type_name(),
+              "variablesReference": 0,
+            })
+          })
+          .collect()
+      })
+      .unwrap_or_default();
+
+    self
+      .writer
+      .respond(request, json!({ "variables": variables }));
+
+    Ok(())
+  }
+
+  fn step(
+    &mut self,
+    request: &Json,
+    action: fn(&mut Interpreter) -> Result<(), InterpreterError>,
+  ) -> Result<(), String> {
+    if self.state() != Some(InterpreterState::Paused) {
+      return Err("O programa não está pausado".into());
+    }
+
+    self
+      .writer
+      .respond(request, json!({ "allThreadsContinued": true }));
+    self.drive(action);
+
+    Ok(())
+  }
+
+  /// While `ler()` waits, whatever is typed in the debug console is the input.
+  /// Otherwise expressions are evaluated over the paused program
+  fn evaluate(&mut self, request: &Json, arguments: &Json) -> Result<(), String> {
+    let expression = arguments["expression"].as_str().unwrap_or_default();
+
+    if matches!(self.state(), Some(InterpreterState::Waiting(_))) {
+      self.writer.respond(
+        request,
+        json!({ "result": expression, "variablesReference": 0 }),
+      );
+
+      let input = expression.to_string();
+      self.drive(move |interpreter| interpreter.provide_input(input));
+
+      return Ok(());
+    }
+
+    let interpreter = self
+      .interpreter
+      .as_mut()
+      .ok_or("Nenhum programa em execução")?;
+    let value = interpreter
+      .evaluate(expression)
+      .map_err(|e| e.to_string())?;
+
+    self.writer.respond(
+      request,
+      json!({
+        "result": display(&value),
+        "type": value.type_name(),
+        "variablesReference": 0,
+      }),
+    );
+
+    Ok(())
+  }
+
+  /// Runs the program once both `launch` and `configurationDone` arrived
+  fn start(&mut self) {
+    if self.started || !self.configured || self.interpreter.is_none() {
+      return;
+    }
+
+    self.started = true;
+
+    if self.stop_on_entry {
+      self.report();
+    } else {
+      self.drive(Interpreter::resume);
+    }
+  }
+
+  fn drive(&mut self, action: impl FnOnce(&mut Interpreter) -> Result<(), InterpreterError>) {
+    let Some(interpreter) = self.interpreter.as_mut() else {
+      return;
+    };
+
+    *self.running.lock().unwrap_or_else(|e| e.into_inner()) = interpreter.pause_handle();
+
+    // Errors end up in the interpreter state, which is reported below
+    let _ = action(interpreter);
+
+    *self.running.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    self.report();
+  }
+
+  /// Tells the editor why the program is no longer running
+  fn report(&mut self) {
+    let Some(interpreter) = &self.interpreter else {
+      return;
+    };
+
+    match interpreter.get_state() {
+      InterpreterState::Paused => {
+        let reason = match interpreter.stopped().map(|stopped| stopped.reason) {
+          Some(StopReason::Entry) => "entry",
+          Some(StopReason::Breakpoint) => "breakpoint",
+          Some(StopReason::Pause) => "pause",
+          _ => "step",
+        };
+
+        self.writer.event(
+          "stopped",
+          json!({
+            "reason": reason,
+            "threadId": THREAD_ID,
+            "allThreadsStopped": true,
+          }),
+        );
+      }
+      InterpreterState::Completed => self.exit(0),
+      InterpreterState::Error(e) => {
+        self.writer.output("stderr", format!("{}\n", e));
+        self.exit(1);
+      }
+      InterpreterState::Waiting(_) | InterpreterState::Running => {}
+    }
+  }
+
+  fn exit(&self, code: i64) {
+    self.writer.event("exited", json!({ "exitCode": code }));
+    self.writer.event("terminated", json!({}));
+  }
+
+  fn apply_breakpoints(&self) -> Result<(), String> {
+    let Some(interpreter) = &self.interpreter else {
+      return Ok(());
+    };
+
+    for (line, condition) in &self.breakpoints {
+      interpreter
+        .set_breakpoint(*line, condition.as_deref())
+        .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+  }
+
+  fn state(&self) -> Option<InterpreterState> {
+    self
+      .interpreter
+      .as_ref()
+      .map(|interpreter| interpreter.get_state().clone())
+  }
+
+  fn stopped(&self) -> Result<&Stopped, String> {
+    self
+      .interpreter
+      .as_ref()
+      .and_then(Interpreter::stopped)
+      .ok_or_else(|| "O programa não está pausado".into())
+  }
+}
+
+fn display(value: &Value) -> String {
+  match value {
+    Value::String(s) => format!("\"{}\"", s),
+    Value::List(items) => format!(
+      "[{}]",
+      items.read().iter().map(display).collect::<Vec<_>>().join(", ")
+    ),
+    Value::Boolean(true) => "verdadeiro".into(),
+    Value::Boolean(false) => "falso".into(),
+    _ => value.to_string(),
+  }
+}
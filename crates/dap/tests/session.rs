@@ -0,0 +1,347 @@
+use std::{
+  collections::VecDeque,
+  env, fs,
+  io::{BufRead, BufReader, Write},
+  path::PathBuf,
+  process::{Child, ChildStdin, Command, Stdio},
+  sync::mpsc::{self, Receiver},
+  thread,
+  time::Duration,
+};
+
+use serde_json::{json, Value};
+
+/// How long to wait for a message before the adapter is considered stuck
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+const PROGRAM: &str = r#"funcao dobro(n) {
+  declare r = n * 2
+  retorne r
+}
+declare x = 5
+declare y = dobro(x)
+escrever(y)
+declare nome = ler()
+escrever("Olá, " + nome)
+"#;
+
+/// A `cobral-dap` process driven over stdin/stdout, like an editor would
+struct Adapter {
+  child: Child,
+  stdin: ChildStdin,
+  messages: Receiver<Value>,
+  /// Messages read while waiting for another one
+  pending: VecDeque<Value>,
+  seq: i64,
+  program: PathBuf,
+}
+
+impl Adapter {
+  fn start(name: &str, code: &str) -> Self {
+    let program =
+      env::temp_dir().join(format!("cobral-dap-{}-{}.cobral", std::process::id(), name));
+    fs::write(&program, code).unwrap();
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_cobral-dap"))
+      .stdin(Stdio::piped())
+      .stdout(Stdio::piped())
+      .spawn()
+      .unwrap();
+
+    let stdin = child.stdin.take().unwrap();
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+
+    let (sender, messages) = mpsc::channel();
+    thread::spawn(move || {
+      while let Some(message) = read_message(&mut stdout) {
+        if sender.send(message).is_err() {
+          break;
+        }
+      }
+    });
+
+    Adapter {
+      child,
+      stdin,
+      messages,
+      pending: VecDeque::new(),
+      seq: 0,
+      program,
+    }
+  }
+
+  fn send(&mut self, command: &str, arguments: Value) -> i64 {
+    self.seq += 1;
+
+    let body = json!({
+      "seq": self.seq,
+      "type": "request",
+      "command": command,
+      "arguments": arguments,
+    })
+    .to_string();
+
+    write!(self.stdin, "Content-Length: {}\r\n\r\n{}", body.len(), body).unwrap();
+    self.stdin.flush().unwrap();
+
+    self.seq
+  }
+
+  /// Sends a request and returns its response
+  fn request(&mut self, command: &str, arguments: Value) -> Value {
+    let seq = self.send(command, arguments);
+    self.wait_for(|message| message["type"] == "response" && message["request_seq"] == seq)
+  }
+
+  /// Body of a request that must succeed
+  fn body(&mut self, command: &str, arguments: Value) -> Value {
+    let response = self.request(command, arguments);
+    assert_eq!(response["success"], true, "{}", response);
+    response["body"].clone()
+  }
+
+  fn event(&mut self, event: &str) -> Value {
+    self.wait_for(|message| message["type"] == "event" && message["event"] == event)["body"].clone()
+  }
+
+  /// Program output up to the next message matching `until`, which is kept
+  fn output_until(&mut self, until: impl Fn(&Value) -> bool) -> String {
+    let mut output = String::new();
+
+    loop {
+      let message = self.next();
+
+      if until(&message) {
+        self.pending.push_front(message);
+        return output;
+      }
+
+      if message["event"] == "output" && message["body"]["category"] == "stdout" {
+        output.push_str(message["body"]["output"].as_str().unwrap());
+      }
+    }
+  }
+
+  fn wait_for(&mut self, matches: impl Fn(&Value) -> bool) -> Value {
+    if let Some(index) = self.pending.iter().position(&matches) {
+      return self.pending.remove(index).unwrap();
+    }
+
+    loop {
+      let message = self.next_from_adapter();
+
+      if matches(&message) {
+        return message;
+      }
+
+      self.pending.push_back(message);
+    }
+  }
+
+  fn next(&mut self) -> Value {
+    match self.pending.pop_front() {
+      Some(message) => message,
+      None => self.next_from_adapter(),
+    }
+  }
+
+  fn next_from_adapter(&mut self) -> Value {
+    self
+      .messages
+      .recv_timeout(TIMEOUT)
+      .expect("the adapter stopped answering")
+  }
+
+  /// `initialize`, `launch` with the breakpoints, then `configurationDone`
+  fn launch(&mut self, breakpoints: &[Value], stop_on_entry: bool) {
+    let capabilities = self.body("initialize", json!({ "adapterID": "cobral" }));
+    assert_eq!(capabilities["supportsConfigurationDoneRequest"], true);
+    self.event("initialized");
+
+    let program = self.program.to_str().unwrap().to_string();
+    self.body(
+      "launch",
+      json!({ "program": program, "stopOnEntry": stop_on_entry }),
+    );
+
+    let verified = self.body(
+      "setBreakpoints",
+      json!({ "source": { "path": program }, "breakpoints": breakpoints }),
+    );
+    for breakpoint in verified["breakpoints"].as_array().unwrap() {
+      assert_eq!(breakpoint["verified"], true, "{}", breakpoint);
+    }
+
+    self.body("configurationDone", json!({}));
+  }
+
+  /// Reason of the next stop
+  fn stopped(&mut self) -> String {
+    self.event("stopped")["reason"]
+      .as_str()
+      .unwrap()
+      .to_string()
+  }
+
+  /// Name and line of each frame, innermost first
+  fn frames(&mut self) -> Vec<(String, u64)> {
+    self.body("stackTrace", json!({ "threadId": 1 }))["stackFrames"]
+      .as_array()
+      .unwrap()
+      .iter()
+      .map(|frame| {
+        (
+          frame["name"].as_str().unwrap().to_string(),
+          frame["line"].as_u64().unwrap(),
+        )
+      })
+      .collect()
+  }
+
+  /// Variables of a frame, as displayed by the editor
+  fn variables(&mut self, frame: u64) -> Vec<(String, String)> {
+    let scopes = self.body("scopes", json!({ "frameId": frame }));
+    let reference = scopes["scopes"][0]["variablesReference"].clone();
+
+    self.body("variables", json!({ "variablesReference": reference }))["variables"]
+      .as_array()
+      .unwrap()
+      .iter()
+      .map(|variable| {
+        (
+          variable["name"].as_str().unwrap().to_string(),
+          variable["value"].as_str().unwrap().to_string(),
+        )
+      })
+      .collect()
+  }
+
+  fn evaluate(&mut self, expression: &str) -> Value {
+    self.body(
+      "evaluate",
+      json!({ "expression": expression, "context": "repl" }),
+    )
+  }
+}
+
+impl Drop for Adapter {
+  fn drop(&mut self) {
+    let _ = self.child.kill();
+    let _ = self.child.wait();
+    let _ = fs::remove_file(&self.program);
+  }
+}
+
+fn read_message(reader: &mut impl BufRead) -> Option<Value> {
+  let mut length = None;
+
+  loop {
+    let mut line = String::new();
+    if reader.read_line(&mut line).ok()? == 0 {
+      return None;
+    }
+
+    let line = line.trim_end();
+    if line.is_empty() {
+      break;
+    }
+
+    if let Some(value) = line.strip_prefix("Content-Length:") {
+      length = value.trim().parse::<usize>().ok();
+    }
+  }
+
+  let mut body = vec![0; length?];
+  reader.read_exact(&mut body).ok()?;
+
+  serde_json::from_slice(&body).ok()
+}
+
+fn strings(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+  pairs
+    .iter()
+    .map(|(a, b)| (a.to_string(), b.to_string()))
+    .collect()
+}
+
+#[test]
+fn breakpoints_steps_and_inspection() {
+  let mut adapter = Adapter::start("passos", PROGRAM);
+  adapter.launch(&[json!({ "line": 6 })], false);
+
+  assert_eq!(adapter.stopped(), "breakpoint");
+  assert_eq!(adapter.frames(), [("principal".to_string(), 6)]);
+  assert_eq!(adapter.variables(0), strings(&[("x", "5")]));
+  assert_eq!(adapter.evaluate("x * 3")["result"], "15");
+
+  adapter.body("stepIn", json!({ "threadId": 1 }));
+  assert_eq!(adapter.stopped(), "step");
+  assert_eq!(
+    adapter.frames(),
+    [("dobro".to_string(), 2), ("principal".to_string(), 6)]
+  );
+  assert_eq!(adapter.variables(0), strings(&[("n", "5"), ("x", "5")]));
+  assert_eq!(adapter.variables(1), strings(&[("x", "5")]));
+
+  adapter.body("next", json!({ "threadId": 1 }));
+  assert_eq!(adapter.stopped(), "step");
+  assert_eq!(adapter.frames()[0], ("dobro".to_string(), 3));
+  assert_eq!(adapter.evaluate("r")["result"], "10");
+
+  // The program runs until `ler()`, which is answered from the console
+  adapter.body("continue", json!({ "threadId": 1 }));
+  assert_eq!(
+    adapter.output_until(|message| message["body"]["category"] == "console"),
+    "10\n"
+  );
+  assert_eq!(adapter.evaluate("Ana")["result"], "Ana");
+  assert_eq!(
+    adapter.output_until(|message| message["event"] == "exited"),
+    "Olá, Ana\n"
+  );
+  assert_eq!(adapter.event("exited")["exitCode"], 0);
+  adapter.event("terminated");
+
+  adapter.body("disconnect", json!({}));
+}
+
+#[test]
+fn stop_on_entry_and_runtime_errors() {
+  let mut adapter = Adapter::start("erro", "declare v = [1]\nescrever(v[3])\n");
+  adapter.launch(&[], true);
+
+  assert_eq!(adapter.stopped(), "entry");
+  assert_eq!(adapter.frames(), [("principal".to_string(), 1)]);
+
+  let response = adapter.request("evaluate", json!({ "expression": "nada" }));
+  assert_eq!(response["success"], false);
+
+  adapter.body("continue", json!({ "threadId": 1 }));
+  let error = adapter.wait_for(|message| message["body"]["category"] == "stderr");
+  assert!(
+    error["body"]["output"]
+      .as_str()
+      .unwrap()
+      .contains("Linha 2"),
+    "{}",
+    error
+  );
+  assert_eq!(adapter.event("exited")["exitCode"], 1);
+
+  // Once the program ended, it can't be stepped
+  let response = adapter.request("next", json!({ "threadId": 1 }));
+  assert_eq!(response["success"], false);
+}
+
+#[test]
+fn conditional_breakpoints() {
+  let code = "para (declare i = 0; i < 5; i++) {\n  escrever(i)\n}\n";
+  let mut adapter = Adapter::start("condicao", code);
+  adapter.launch(&[json!({ "line": 2, "condition": "i == 3" })], false);
+
+  assert_eq!(adapter.stopped(), "breakpoint");
+  assert_eq!(adapter.evaluate("i")["result"], "3");
+
+  adapter.body("continue", json!({ "threadId": 1 }));
+  assert_eq!(adapter.event("exited")["exitCode"], 0);
+}
@@ -5,6 +5,17 @@ import type { Token, Tokenizer } from "@packages/monaco/helpers/tokenizer";
 import type { Scope } from "@packages/monaco/helpers/scope";
 import { exists } from "@tauri-apps/plugin-fs";
 
+// Libraries of the interpreter, imported by name instead of by path
+const BUILTIN_MODULES = [
+	"matematica",
+	"conversao",
+	"aleatorio",
+	"texto",
+	"lista",
+	"saida",
+	"tempo",
+];
+
 const createImportError = (
 	path: string,
 	pathToken: Token,
@@ -40,7 +51,7 @@ export const checkImportError = async (
 				const importPath = pathToken.value.slice(1, -1);
 
 				// Create a Promise for checking this import
-				if (["matematica", "conversao"].includes(importPath)) return [];
+				if (BUILTIN_MODULES.includes(importPath)) continue;
 				const checkPromise = exists(importPath)
 					.then((fileExists) => {
 						if (!fileExists) {
@@ -1,4 +1,4 @@
-import { ArchiveIcon, BanIcon, SparklesIcon } from "lucide-react";
+import { ArchiveIcon, BanIcon, GaugeIcon, SparklesIcon } from "lucide-react";
 import { emit, listen } from "@tauri-apps/api/event";
 import { useContext, useEffect, useRef } from "react";
 
@@ -10,10 +10,15 @@ export const Terminal = () => {
 	const { clearLogs, value } = useContext(EditorContext);
 
 	const runButton = useRef<HTMLButtonElement>(null);
+	const profileButton = useRef<HTMLButtonElement>(null);
 	const breakButton = useRef<HTMLButtonElement>(null);
 
-	const handleRun = async () => {
+	// Id of the last run, sent with the events that stop it or report its end
+	const run = useRef(0);
+
+	const handleRun = async (profile = false) => {
 		if (runButton.current) runButton.current.disabled = true;
+		if (profileButton.current) profileButton.current.disabled = true;
 		if (breakButton.current) breakButton.current.disabled = false;
 
 		const inputs =
@@ -24,12 +29,14 @@ export const Terminal = () => {
 
 		clearLogs();
 
-		await emit("break_exec");
-		await invoke("eval", { input: value });
+		await emit("break_exec", run.current);
+		run.current += 1;
+		await invoke("eval", { input: value, profile, run: run.current });
 	};
 
 	const handleBreak = async () => {
 		if (runButton.current) runButton.current.disabled = false;
+		if (profileButton.current) profileButton.current.disabled = false;
 		if (breakButton.current) breakButton.current.disabled = true;
 
 		// Get all inputs and disable them
@@ -39,18 +46,20 @@ export const Terminal = () => {
 			input.disabled = true;
 		}
 
-		await emit("break_exec");
+		await emit("break_exec", run.current);
 	};
 
 	useEffect(() => {
 		if (runButton.current) runButton.current.disabled = false;
+		if (profileButton.current) profileButton.current.disabled = false;
 		if (breakButton.current) breakButton.current.disabled = true;
 
-		const finish = listen("exec_finished", async () => {
+		const finish = listen<number>("exec_finished", (event) => {
+			if (event.payload !== run.current) return;
+
 			if (runButton.current) runButton.current.disabled = false;
+			if (profileButton.current) profileButton.current.disabled = false;
 			if (breakButton.current) breakButton.current.disabled = true;
-
-			await emit("break_exec");
 		});
 
 		return () => {
@@ -67,11 +76,21 @@ export const Terminal = () => {
 						variant="expandIcon"
 						Icon={SparklesIcon}
 						iconPlacement="left"
-						onClick={handleRun}
+						onClick={() => handleRun()}
 					>
 						Executar
 					</Button>
 
+					<Button
+						ref={profileButton}
+						variant="expandIcon"
+						Icon={GaugeIcon}
+						iconPlacement="left"
+						onClick={() => handleRun(true)}
+					>
+						Perfil
+					</Button>
+
 					<Button
 						ref={breakButton}
 						variant="expandIcon"